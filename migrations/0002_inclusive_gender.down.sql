@@ -0,0 +1,15 @@
+-- Fails on purpose if any row uses a value the old schema cannot represent.
+ALTER TABLE "people"
+    DROP CONSTRAINT IF EXISTS people_gender_self_described_check,
+    DROP CONSTRAINT IF EXISTS people_gender_description_check,
+    DROP COLUMN IF EXISTS pronouns,
+    DROP COLUMN IF EXISTS gender_description;
+
+ALTER TYPE gender RENAME TO gender_old;
+CREATE TYPE gender AS ENUM ('male', 'female');
+
+ALTER TABLE "people"
+    ALTER COLUMN gender TYPE gender USING gender::text::gender,
+    ALTER COLUMN gender SET NOT NULL;
+
+DROP TYPE gender_old;
@@ -0,0 +1,15 @@
+ALTER TYPE gender ADD VALUE IF NOT EXISTS 'non_binary';
+ALTER TYPE gender ADD VALUE IF NOT EXISTS 'prefer_not_to_say';
+ALTER TYPE gender ADD VALUE IF NOT EXISTS 'self_described';
+
+ALTER TABLE "people"
+    ALTER COLUMN gender DROP NOT NULL,
+    ADD COLUMN gender_description VARCHAR(100),
+    ADD COLUMN pronouns VARCHAR(50),
+    -- New enum values cannot be referenced in the transaction that adds them, hence the cast.
+    ADD CONSTRAINT people_gender_description_check CHECK (
+        gender_description IS NULL OR gender::text = 'self_described'
+    ),
+    ADD CONSTRAINT people_gender_self_described_check CHECK (
+        gender IS NULL OR gender::text <> 'self_described' OR gender_description IS NOT NULL
+    );
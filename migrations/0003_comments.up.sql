@@ -0,0 +1,23 @@
+CREATE TABLE
+    "comments" (
+        id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+        post_id UUID NOT NULL,
+        author_id UUID NOT NULL,
+        parent_id UUID,
+        body VARCHAR(2047) NOT NULL,
+        created_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW(),
+        updated_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW(),
+
+        CONSTRAINT comments_id_post_id_key UNIQUE (id, post_id),
+        CONSTRAINT fk_post FOREIGN KEY(post_id) REFERENCES posts(id) ON DELETE CASCADE,
+        CONSTRAINT fk_author FOREIGN KEY(author_id) REFERENCES people(id) ON DELETE CASCADE,
+        -- A reply must belong to the same post as the comment it answers.
+        CONSTRAINT fk_parent FOREIGN KEY(parent_id, post_id) REFERENCES comments(id, post_id) ON DELETE CASCADE
+    );
+
+CREATE INDEX comments_post_id_created_at_idx ON comments (post_id, created_at);
+CREATE INDEX comments_parent_id_created_at_idx ON comments (parent_id, created_at);
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS "post_reactions";
+
+DROP TYPE IF EXISTS reaction_kind;
@@ -0,0 +1,15 @@
+CREATE TYPE reaction_kind AS ENUM ('like', 'love', 'insightful', 'funny', 'sad');
+
+CREATE TABLE
+    "post_reactions" (
+        post_id UUID NOT NULL,
+        person_id UUID NOT NULL,
+        kind reaction_kind NOT NULL,
+        created_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW(),
+
+        PRIMARY KEY (post_id, person_id, kind),
+        CONSTRAINT fk_post FOREIGN KEY(post_id) REFERENCES posts(id) ON DELETE CASCADE,
+        CONSTRAINT fk_person FOREIGN KEY(person_id) REFERENCES people(id) ON DELETE CASCADE
+    );
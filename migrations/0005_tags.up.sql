@@ -0,0 +1,21 @@
+CREATE TABLE
+    "tags" (
+        id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+        name VARCHAR(50) NOT NULL,
+        slug VARCHAR(50) NOT NULL UNIQUE,
+        created_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW()
+    );
+
+CREATE TABLE
+    "post_tags" (
+        post_id UUID NOT NULL,
+        tag_id UUID NOT NULL,
+
+        PRIMARY KEY (post_id, tag_id),
+        CONSTRAINT fk_post FOREIGN KEY(post_id) REFERENCES posts(id) ON DELETE CASCADE,
+        CONSTRAINT fk_tag FOREIGN KEY(tag_id) REFERENCES tags(id) ON DELETE CASCADE
+    );
+
+CREATE INDEX post_tags_tag_id_idx ON post_tags (tag_id);
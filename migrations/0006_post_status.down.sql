@@ -0,0 +1,11 @@
+DROP INDEX IF EXISTS posts_author_id_idx;
+DROP INDEX IF EXISTS posts_status_published_at_idx;
+
+ALTER TABLE posts
+    DROP CONSTRAINT IF EXISTS posts_published_at_check,
+    DROP CONSTRAINT IF EXISTS fk_author,
+    DROP COLUMN IF EXISTS author_id,
+    DROP COLUMN IF EXISTS published_at,
+    DROP COLUMN IF EXISTS status;
+
+DROP TYPE IF EXISTS post_status;
@@ -0,0 +1,17 @@
+CREATE TYPE post_status AS ENUM ('draft', 'published', 'archived');
+
+-- Posts created before this migration were public, so they stay published.
+ALTER TABLE posts
+    ADD COLUMN status post_status NOT NULL DEFAULT 'published',
+    ADD COLUMN published_at TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN author_id UUID,
+    ADD CONSTRAINT fk_author FOREIGN KEY(author_id) REFERENCES people(id) ON DELETE SET NULL;
+
+UPDATE posts SET published_at = created_at;
+
+ALTER TABLE posts
+    ALTER COLUMN status SET DEFAULT 'draft',
+    ADD CONSTRAINT posts_published_at_check CHECK (status = 'draft' OR published_at IS NOT NULL);
+
+CREATE INDEX posts_status_published_at_idx ON posts (status, published_at);
+CREATE INDEX posts_author_id_idx ON posts (author_id);
@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS posts_scheduled_at_idx;
+
+ALTER TABLE posts
+    DROP CONSTRAINT IF EXISTS posts_scheduled_at_check,
+    DROP COLUMN IF EXISTS scheduled_at;
@@ -0,0 +1,6 @@
+-- A scheduled post is a draft with a `scheduled_at` time, it is published once that time has passed.
+ALTER TABLE posts
+    ADD COLUMN scheduled_at TIMESTAMP WITH TIME ZONE,
+    ADD CONSTRAINT posts_scheduled_at_check CHECK (scheduled_at IS NULL OR status = 'draft');
+
+CREATE INDEX posts_scheduled_at_idx ON posts (scheduled_at) WHERE scheduled_at IS NOT NULL;
@@ -0,0 +1,16 @@
+CREATE TABLE
+    "post_revisions" (
+        id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+        post_id UUID NOT NULL,
+        editor_id UUID,
+        title VARCHAR(255) NOT NULL,
+        description VARCHAR(4095) NOT NULL,
+        created_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW(),
+
+        CONSTRAINT fk_post FOREIGN KEY(post_id) REFERENCES posts(id) ON DELETE CASCADE,
+        CONSTRAINT fk_editor FOREIGN KEY(editor_id) REFERENCES people(id) ON DELETE SET NULL
+    );
+
+CREATE INDEX post_revisions_post_id_created_at_idx ON post_revisions (post_id, created_at);
@@ -0,0 +1,7 @@
+DROP TRIGGER IF EXISTS people_bump_version ON people;
+DROP TRIGGER IF EXISTS posts_bump_version ON posts;
+
+DROP FUNCTION IF EXISTS bump_version();
+
+ALTER TABLE people DROP COLUMN IF EXISTS version;
+ALTER TABLE posts DROP COLUMN IF EXISTS version;
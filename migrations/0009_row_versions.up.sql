@@ -0,0 +1,16 @@
+-- Every write bumps `version`, which is exposed as the ETag of a post or a profile.
+ALTER TABLE posts ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE people ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+
+CREATE OR REPLACE FUNCTION bump_version() RETURNS TRIGGER AS $$
+BEGIN
+    NEW.version = OLD.version + 1;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER posts_bump_version BEFORE UPDATE ON posts
+    FOR EACH ROW EXECUTE FUNCTION bump_version();
+
+CREATE TRIGGER people_bump_version BEFORE UPDATE ON people
+    FOR EACH ROW EXECUTE FUNCTION bump_version();
@@ -0,0 +1,11 @@
+DROP TRIGGER IF EXISTS comments_set_updated_at ON comments;
+DROP TRIGGER IF EXISTS emails_set_updated_at ON emails;
+DROP TRIGGER IF EXISTS people_set_updated_at ON people;
+DROP TRIGGER IF EXISTS posts_set_updated_at ON posts;
+
+DROP FUNCTION IF EXISTS set_updated_at();
+
+ALTER TABLE comments ALTER COLUMN updated_at DROP NOT NULL;
+ALTER TABLE emails ALTER COLUMN updated_at DROP NOT NULL;
+ALTER TABLE people ALTER COLUMN updated_at DROP NOT NULL;
+ALTER TABLE posts ALTER COLUMN updated_at DROP NOT NULL;
@@ -0,0 +1,29 @@
+-- `updated_at` is owned by the database: every write moves it, whoever issues it.
+CREATE OR REPLACE FUNCTION set_updated_at() RETURNS TRIGGER AS $$
+BEGIN
+    NEW.updated_at = NOW();
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+UPDATE posts SET updated_at = COALESCE(created_at, NOW()) WHERE updated_at IS NULL;
+UPDATE people SET updated_at = COALESCE(created_at, NOW()) WHERE updated_at IS NULL;
+UPDATE emails SET updated_at = NOW() WHERE updated_at IS NULL;
+UPDATE comments SET updated_at = COALESCE(created_at, NOW()) WHERE updated_at IS NULL;
+
+ALTER TABLE posts ALTER COLUMN updated_at SET NOT NULL;
+ALTER TABLE people ALTER COLUMN updated_at SET NOT NULL;
+ALTER TABLE emails ALTER COLUMN updated_at SET NOT NULL;
+ALTER TABLE comments ALTER COLUMN updated_at SET NOT NULL;
+
+CREATE TRIGGER posts_set_updated_at BEFORE UPDATE ON posts
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER people_set_updated_at BEFORE UPDATE ON people
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER emails_set_updated_at BEFORE UPDATE ON emails
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER comments_set_updated_at BEFORE UPDATE ON comments
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
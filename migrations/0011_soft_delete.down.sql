@@ -0,0 +1,9 @@
+DROP INDEX IF EXISTS people_deleted_at_idx;
+DROP INDEX IF EXISTS posts_deleted_at_idx;
+
+-- Rows still in the trash would otherwise come back to life.
+DELETE FROM people WHERE deleted_at IS NOT NULL;
+DELETE FROM posts WHERE deleted_at IS NOT NULL;
+
+ALTER TABLE people DROP COLUMN IF EXISTS deleted_at;
+ALTER TABLE posts DROP COLUMN IF EXISTS deleted_at;
@@ -0,0 +1,6 @@
+-- Deleted posts and accounts stay in the trash until they are restored or purged.
+ALTER TABLE posts ADD COLUMN deleted_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE people ADD COLUMN deleted_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX posts_deleted_at_idx ON posts (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX people_deleted_at_idx ON people (deleted_at) WHERE deleted_at IS NOT NULL;
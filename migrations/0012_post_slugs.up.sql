@@ -0,0 +1,35 @@
+-- Existing posts get an ASCII slug of their title, numbered when titles collide.
+CREATE EXTENSION IF NOT EXISTS "unaccent";
+
+ALTER TABLE posts ADD COLUMN slug VARCHAR(255);
+
+WITH base AS (
+    SELECT id, created_at,
+        COALESCE(NULLIF(TRIM(BOTH '-' FROM LEFT(REGEXP_REPLACE(LOWER(unaccent(title)), '[^a-z0-9]+', '-', 'g'), 100)), ''), 'post') AS slug
+        FROM posts
+),
+numbered AS (
+    SELECT id, slug, ROW_NUMBER() OVER (PARTITION BY slug ORDER BY created_at, id) AS n
+        FROM base
+)
+UPDATE posts
+    SET slug = CASE WHEN numbered.n = 1 THEN numbered.slug ELSE numbered.slug || '-' || numbered.n END
+    FROM numbered
+    WHERE posts.id = numbered.id;
+
+ALTER TABLE posts ALTER COLUMN slug SET NOT NULL;
+ALTER TABLE posts ADD CONSTRAINT posts_slug_key UNIQUE (slug);
+
+-- Slugs a post had before its title changed, so that old links keep working.
+CREATE TABLE
+    "post_slug_redirects" (
+        slug VARCHAR(255) NOT NULL PRIMARY KEY,
+        post_id UUID NOT NULL,
+        created_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW(),
+
+        CONSTRAINT fk_post FOREIGN KEY(post_id) REFERENCES posts(id) ON DELETE CASCADE
+    );
+
+CREATE INDEX post_slug_redirects_post_id_idx ON post_slug_redirects (post_id);
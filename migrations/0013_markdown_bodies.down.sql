@@ -0,0 +1,6 @@
+ALTER TABLE posts DROP COLUMN IF EXISTS reading_time;
+ALTER TABLE posts DROP COLUMN IF EXISTS excerpt;
+ALTER TABLE posts DROP COLUMN IF EXISTS description_html;
+
+ALTER TABLE post_revisions ALTER COLUMN description TYPE VARCHAR(4095) USING LEFT(description, 4095);
+ALTER TABLE posts ALTER COLUMN description TYPE VARCHAR(4095) USING LEFT(description, 4095);
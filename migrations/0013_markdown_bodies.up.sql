@@ -0,0 +1,18 @@
+-- `description` holds CommonMark; its sanitized HTML, excerpt and reading time
+-- in minutes are rendered on write and cached next to it.
+ALTER TABLE posts ALTER COLUMN description TYPE TEXT;
+ALTER TABLE post_revisions ALTER COLUMN description TYPE TEXT;
+
+ALTER TABLE posts ADD COLUMN description_html TEXT;
+ALTER TABLE posts ADD COLUMN excerpt TEXT;
+ALTER TABLE posts ADD COLUMN reading_time INTEGER;
+
+-- Existing descriptions are plain text, which renders as a single paragraph.
+UPDATE posts
+    SET description_html = '<p>' || REPLACE(REPLACE(REPLACE(TRIM(description), '&', '&amp;'), '<', '&lt;'), '>', '&gt;') || '</p>',
+        excerpt = LEFT(REGEXP_REPLACE(TRIM(description), '\s+', ' ', 'g'), 200),
+        reading_time = GREATEST(1, CEIL(COALESCE(ARRAY_LENGTH(REGEXP_SPLIT_TO_ARRAY(TRIM(description), '\s+'), 1), 0) / 200.0));
+
+ALTER TABLE posts ALTER COLUMN description_html SET NOT NULL;
+ALTER TABLE posts ALTER COLUMN excerpt SET NOT NULL;
+ALTER TABLE posts ALTER COLUMN reading_time SET NOT NULL;
@@ -0,0 +1,4 @@
+ALTER TABLE people DROP COLUMN IF EXISTS avatar_url;
+ALTER TABLE people DROP COLUMN IF EXISTS avatar_key;
+
+DROP TABLE IF EXISTS attachments;
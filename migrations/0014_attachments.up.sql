@@ -0,0 +1,24 @@
+-- Files uploaded to a post. The bytes live in the configured storage backend
+-- under `storage_key`, `url` is where that backend serves them from.
+CREATE TABLE
+    "attachments" (
+        id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+        post_id UUID NOT NULL,
+        uploader_id UUID,
+        storage_key VARCHAR(255) NOT NULL UNIQUE,
+        url VARCHAR(1023) NOT NULL,
+        filename VARCHAR(255) NOT NULL,
+        content_type VARCHAR(100) NOT NULL,
+        size BIGINT NOT NULL CHECK (size > 0),
+        created_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW(),
+
+        CONSTRAINT fk_post FOREIGN KEY(post_id) REFERENCES posts(id) ON DELETE CASCADE,
+        CONSTRAINT fk_uploader FOREIGN KEY(uploader_id) REFERENCES people(id) ON DELETE SET NULL
+    );
+
+CREATE INDEX attachments_post_id_created_at_idx ON attachments (post_id, created_at);
+
+ALTER TABLE people ADD COLUMN avatar_key VARCHAR(255);
+ALTER TABLE people ADD COLUMN avatar_url VARCHAR(1023);
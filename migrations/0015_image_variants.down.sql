@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS image_variants;
+
+DROP TYPE IF EXISTS image_variant_status;
@@ -0,0 +1,30 @@
+CREATE TYPE image_variant_status AS ENUM ('pending', 'ready', 'failed');
+
+-- Resized copies of uploaded images, keyed by the storage key of the original
+-- so that attachments and avatars share them. Rows are queued as `pending`
+-- on upload and filled in by a background job.
+CREATE TABLE
+    "image_variants" (
+        id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+        source_key VARCHAR(255) NOT NULL,
+        name VARCHAR(50) NOT NULL,
+        max_size INTEGER NOT NULL CHECK (max_size > 0),
+        status image_variant_status NOT NULL DEFAULT 'pending',
+        storage_key VARCHAR(255) UNIQUE,
+        url VARCHAR(1023),
+        width INTEGER,
+        height INTEGER,
+        attempts INTEGER NOT NULL DEFAULT 0,
+        locked_until TIMESTAMP WITH TIME ZONE,
+        created_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW(),
+        updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+        CONSTRAINT image_variants_source_key_name_key UNIQUE (source_key, name)
+    );
+
+CREATE INDEX image_variants_pending_idx ON image_variants (created_at) WHERE status = 'pending';
+
+CREATE TRIGGER image_variants_set_updated_at BEFORE UPDATE ON image_variants
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
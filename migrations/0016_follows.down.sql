@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS follows;
+
+DROP TYPE IF EXISTS follow_status;
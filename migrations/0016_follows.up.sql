@@ -0,0 +1,22 @@
+CREATE TYPE follow_status AS ENUM ('pending', 'accepted');
+
+-- Who follows whom. Following a private profile starts out as a `pending`
+-- request that the followee has to accept.
+CREATE TABLE
+    "follows" (
+        follower_id UUID NOT NULL,
+        followee_id UUID NOT NULL,
+        status follow_status NOT NULL DEFAULT 'accepted',
+        created_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW(),
+        accepted_at TIMESTAMP WITH TIME ZONE,
+
+        PRIMARY KEY (follower_id, followee_id),
+        CONSTRAINT follows_not_self CHECK (follower_id <> followee_id),
+        CONSTRAINT fk_follower FOREIGN KEY(follower_id) REFERENCES people(id) ON DELETE CASCADE,
+        CONSTRAINT fk_followee FOREIGN KEY(followee_id) REFERENCES people(id) ON DELETE CASCADE
+    );
+
+CREATE INDEX follows_followee_id_status_idx ON follows (followee_id, status, created_at);
+CREATE INDEX follows_follower_id_status_idx ON follows (follower_id, status, created_at);
@@ -0,0 +1 @@
+DROP INDEX IF EXISTS posts_author_id_published_at_idx;
@@ -0,0 +1,4 @@
+-- Serves the home feed, which walks the published posts of each followed
+-- author newest first.
+CREATE INDEX posts_author_id_published_at_idx ON posts (author_id, published_at DESC, id DESC)
+    WHERE status = 'published' AND deleted_at IS NULL;
@@ -0,0 +1,31 @@
+-- A block cuts all contact between two people both ways, a mute only hides
+-- the muted person's content from the muter.
+CREATE TABLE
+    "blocks" (
+        blocker_id UUID NOT NULL,
+        blocked_id UUID NOT NULL,
+        created_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW(),
+
+        PRIMARY KEY (blocker_id, blocked_id),
+        CONSTRAINT blocks_not_self CHECK (blocker_id <> blocked_id),
+        CONSTRAINT fk_blocker FOREIGN KEY(blocker_id) REFERENCES people(id) ON DELETE CASCADE,
+        CONSTRAINT fk_blocked FOREIGN KEY(blocked_id) REFERENCES people(id) ON DELETE CASCADE
+    );
+
+CREATE INDEX blocks_blocked_id_idx ON blocks (blocked_id);
+
+CREATE TABLE
+    "mutes" (
+        muter_id UUID NOT NULL,
+        muted_id UUID NOT NULL,
+        created_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW(),
+
+        PRIMARY KEY (muter_id, muted_id),
+        CONSTRAINT mutes_not_self CHECK (muter_id <> muted_id),
+        CONSTRAINT fk_muter FOREIGN KEY(muter_id) REFERENCES people(id) ON DELETE CASCADE,
+        CONSTRAINT fk_muted FOREIGN KEY(muted_id) REFERENCES people(id) ON DELETE CASCADE
+    );
@@ -0,0 +1,5 @@
+ALTER TABLE people DROP COLUMN disabled_notification_kinds;
+
+DROP TABLE IF EXISTS notifications;
+
+DROP TYPE IF EXISTS notification_kind;
@@ -0,0 +1,32 @@
+CREATE TYPE notification_kind AS ENUM (
+    'comment',
+    'reply',
+    'follow',
+    'follow_request',
+    'follow_accepted',
+    'reaction'
+);
+
+-- What happened is kept in `payload`, whose shape depends on `kind`.
+CREATE TABLE
+    "notifications" (
+        id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+        recipient_id UUID NOT NULL,
+        actor_id UUID NOT NULL,
+        kind notification_kind NOT NULL,
+        payload JSONB NOT NULL,
+        read_at TIMESTAMP WITH TIME ZONE,
+        created_at TIMESTAMP
+        WITH
+            TIME ZONE NOT NULL DEFAULT NOW(),
+
+        CONSTRAINT fk_recipient FOREIGN KEY(recipient_id) REFERENCES people(id) ON DELETE CASCADE,
+        CONSTRAINT fk_actor FOREIGN KEY(actor_id) REFERENCES people(id) ON DELETE CASCADE
+    );
+
+CREATE INDEX notifications_recipient_id_created_at_idx ON notifications (recipient_id, created_at DESC, id DESC);
+CREATE INDEX notifications_recipient_id_unread_idx ON notifications (recipient_id) WHERE read_at IS NULL;
+
+-- Kinds of notifications the person does not want to receive.
+ALTER TABLE people
+ADD COLUMN disabled_notification_kinds notification_kind[] NOT NULL DEFAULT '{}';
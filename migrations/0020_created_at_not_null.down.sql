@@ -0,0 +1,12 @@
+ALTER TABLE mutes ALTER COLUMN created_at DROP NOT NULL;
+ALTER TABLE blocks ALTER COLUMN created_at DROP NOT NULL;
+ALTER TABLE follows ALTER COLUMN created_at DROP NOT NULL;
+ALTER TABLE image_variants ALTER COLUMN created_at DROP NOT NULL;
+ALTER TABLE attachments ALTER COLUMN created_at DROP NOT NULL;
+ALTER TABLE post_slug_redirects ALTER COLUMN created_at DROP NOT NULL;
+ALTER TABLE post_revisions ALTER COLUMN created_at DROP NOT NULL;
+ALTER TABLE tags ALTER COLUMN created_at DROP NOT NULL;
+ALTER TABLE post_reactions ALTER COLUMN created_at DROP NOT NULL;
+ALTER TABLE comments ALTER COLUMN created_at DROP NOT NULL;
+ALTER TABLE people ALTER COLUMN created_at DROP NOT NULL;
+ALTER TABLE posts ALTER COLUMN created_at DROP NOT NULL;
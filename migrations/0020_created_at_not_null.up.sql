@@ -0,0 +1,26 @@
+-- Every row gets `created_at` from its default, so no model has to treat it as optional.
+UPDATE posts SET created_at = COALESCE(updated_at, NOW()) WHERE created_at IS NULL;
+UPDATE people SET created_at = COALESCE(updated_at, NOW()) WHERE created_at IS NULL;
+UPDATE comments SET created_at = COALESCE(updated_at, NOW()) WHERE created_at IS NULL;
+UPDATE post_reactions SET created_at = NOW() WHERE created_at IS NULL;
+UPDATE tags SET created_at = NOW() WHERE created_at IS NULL;
+UPDATE post_revisions SET created_at = NOW() WHERE created_at IS NULL;
+UPDATE post_slug_redirects SET created_at = NOW() WHERE created_at IS NULL;
+UPDATE attachments SET created_at = NOW() WHERE created_at IS NULL;
+UPDATE image_variants SET created_at = NOW() WHERE created_at IS NULL;
+UPDATE follows SET created_at = NOW() WHERE created_at IS NULL;
+UPDATE blocks SET created_at = NOW() WHERE created_at IS NULL;
+UPDATE mutes SET created_at = NOW() WHERE created_at IS NULL;
+
+ALTER TABLE posts ALTER COLUMN created_at SET NOT NULL;
+ALTER TABLE people ALTER COLUMN created_at SET NOT NULL;
+ALTER TABLE comments ALTER COLUMN created_at SET NOT NULL;
+ALTER TABLE post_reactions ALTER COLUMN created_at SET NOT NULL;
+ALTER TABLE tags ALTER COLUMN created_at SET NOT NULL;
+ALTER TABLE post_revisions ALTER COLUMN created_at SET NOT NULL;
+ALTER TABLE post_slug_redirects ALTER COLUMN created_at SET NOT NULL;
+ALTER TABLE attachments ALTER COLUMN created_at SET NOT NULL;
+ALTER TABLE image_variants ALTER COLUMN created_at SET NOT NULL;
+ALTER TABLE follows ALTER COLUMN created_at SET NOT NULL;
+ALTER TABLE blocks ALTER COLUMN created_at SET NOT NULL;
+ALTER TABLE mutes ALTER COLUMN created_at SET NOT NULL;
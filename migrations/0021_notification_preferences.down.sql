@@ -0,0 +1,9 @@
+ALTER TABLE people
+ADD COLUMN disabled_notification_kinds notification_kind[] NOT NULL DEFAULT '{}';
+
+-- Copying the preferences back bumps the version of those profiles.
+UPDATE people SET disabled_notification_kinds = notification_preferences.disabled_kinds
+    FROM notification_preferences
+    WHERE notification_preferences.person_id = people.id;
+
+DROP TABLE IF EXISTS notification_preferences;
@@ -0,0 +1,15 @@
+-- Kept apart from `people` so changing them doesn't bump the profile's version
+-- or `updated_at`. People without a row receive every kind of notification.
+CREATE TABLE
+    "notification_preferences" (
+        person_id UUID NOT NULL PRIMARY KEY,
+        disabled_kinds notification_kind[] NOT NULL DEFAULT '{}',
+
+        CONSTRAINT fk_person FOREIGN KEY(person_id) REFERENCES people(id) ON DELETE CASCADE
+    );
+
+INSERT INTO notification_preferences (person_id, disabled_kinds)
+    SELECT id, disabled_notification_kinds FROM people
+    WHERE disabled_notification_kinds <> '{}';
+
+ALTER TABLE people DROP COLUMN disabled_notification_kinds;
@@ -1,24 +1,119 @@
+/// A resized copy generated for every uploaded image, e.g. `card:640` for a
+/// variant named `card` that fits in a 640px square.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThumbnailSpec {
+    pub name: String,
+    pub size: u32,
+}
+
+impl ThumbnailSpec {
+    /// Parses a comma separated list of `name:size` pairs.
+    pub fn parse_list(value: &str) -> Vec<ThumbnailSpec> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|spec| !spec.is_empty())
+            .map(|spec| {
+                let (name, size) = spec
+                    .split_once(':')
+                    .expect("THUMBNAIL_VARIANTS must be a list of name:size pairs!");
+
+                ThumbnailSpec {
+                    name: name.trim().to_string(),
+                    size: size.trim().parse::<u32>().unwrap(),
+                }
+            })
+            .collect()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Config {
     pub db_url: String,
     pub host_ip: String,
     pub url: String,
     pub port: u16,
+    pub min_age: u32,
+    pub jwt_secret: String,
+    pub jwt_max_age: i64,
+    pub scheduler_interval: u64,
+    pub require_if_match: bool,
+    pub trash_retention_days: i64,
+    pub purge_interval: u64,
+    pub storage_backend: String,
+    pub storage_local_root: String,
+    pub storage_public_url: Option<String>,
+    pub s3_endpoint: Option<String>,
+    pub s3_bucket: Option<String>,
+    pub s3_region: String,
+    pub s3_access_key_id: Option<String>,
+    pub s3_secret_access_key: Option<String>,
+    pub max_upload_size: usize,
+    pub thumbnail_variants: Vec<ThumbnailSpec>,
+    pub thumbnail_interval: u64,
 }
 
 impl Config {
     pub fn init() -> Config {
         let db_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set!");
         let port = std::env::var("PORT").expect("PORT must be set!");
+        let jwt_secret = std::env::var("JWT_SECRET").expect("JWT_SECRET must be set!");
+        let jwt_max_age = std::env::var("JWT_MAX_AGE").unwrap_or(String::from("60"));
         let host_ip = std::env::var("HOST").unwrap_or(String::from("127.0.0.1"));
         let url = std::env::var("URL").unwrap_or(String::from("http://localhost"));
+        let min_age = std::env::var("MIN_AGE").unwrap_or(String::from("13"));
+        let scheduler_interval = std::env::var("SCHEDULER_INTERVAL").unwrap_or(String::from("30"));
+        let require_if_match = std::env::var("REQUIRE_IF_MATCH").unwrap_or(String::from("false"));
+        let trash_retention_days =
+            std::env::var("TRASH_RETENTION_DAYS").unwrap_or(String::from("30"));
+        let purge_interval = std::env::var("PURGE_INTERVAL").unwrap_or(String::from("3600"));
+        let storage_backend = std::env::var("STORAGE_BACKEND").unwrap_or(String::from("local"));
+        let storage_local_root =
+            std::env::var("STORAGE_LOCAL_ROOT").unwrap_or(String::from("uploads"));
+        let storage_public_url = std::env::var("STORAGE_PUBLIC_URL").ok();
+        let s3_endpoint = std::env::var("S3_ENDPOINT").ok();
+        let s3_bucket = std::env::var("S3_BUCKET").ok();
+        let s3_region = std::env::var("S3_REGION").unwrap_or(String::from("us-east-1"));
+        let s3_access_key_id = std::env::var("S3_ACCESS_KEY_ID").ok();
+        let s3_secret_access_key = std::env::var("S3_SECRET_ACCESS_KEY").ok();
+        let max_upload_size = std::env::var("MAX_UPLOAD_SIZE").unwrap_or(String::from("5242880"));
+        let thumbnail_variants =
+            std::env::var("THUMBNAIL_VARIANTS").unwrap_or(String::from("avatar:128,card:640"));
+        let thumbnail_interval = std::env::var("THUMBNAIL_INTERVAL").unwrap_or(String::from("10"));
         let port_u16 = port.parse::<u16>().unwrap();
+        let min_age_u32 = min_age.parse::<u32>().unwrap();
+        let jwt_max_age_i64 = jwt_max_age.parse::<i64>().unwrap();
+        let scheduler_interval_u64 = scheduler_interval.parse::<u64>().unwrap();
+        let require_if_match_bool = require_if_match.parse::<bool>().unwrap();
+        let trash_retention_days_i64 = trash_retention_days.parse::<i64>().unwrap();
+        let purge_interval_u64 = purge_interval.parse::<u64>().unwrap();
+        let max_upload_size_usize = max_upload_size.parse::<usize>().unwrap();
+        let thumbnail_variants_list = ThumbnailSpec::parse_list(&thumbnail_variants);
+        let thumbnail_interval_u64 = thumbnail_interval.parse::<u64>().unwrap();
 
         Config {
             db_url,
             host_ip,
             url,
             port: port_u16,
+            min_age: min_age_u32,
+            jwt_secret,
+            jwt_max_age: jwt_max_age_i64,
+            scheduler_interval: scheduler_interval_u64,
+            require_if_match: require_if_match_bool,
+            trash_retention_days: trash_retention_days_i64,
+            purge_interval: purge_interval_u64,
+            storage_backend,
+            storage_local_root,
+            storage_public_url,
+            s3_endpoint,
+            s3_bucket,
+            s3_region,
+            s3_access_key_id,
+            s3_secret_access_key,
+            max_upload_size: max_upload_size_usize,
+            thumbnail_variants: thumbnail_variants_list,
+            thumbnail_interval: thumbnail_interval_u64,
         }
     }
 }
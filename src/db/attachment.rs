@@ -0,0 +1,106 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{dtos::attachment::CreateAttachmentDto, models::Attachment};
+
+use super::{image_variant::ImageVariantExt, DBClient};
+
+#[async_trait]
+pub trait AttachmentExt {
+    /// Attachments of all the given posts with their image variants, oldest first.
+    async fn get_posts_attachments(
+        &self,
+        post_ids: &[Uuid],
+    ) -> Result<Vec<Attachment>, sqlx::Error>;
+
+    async fn save_attachment(
+        &self,
+        post_id: Uuid,
+        uploader_id: Uuid,
+        dto: CreateAttachmentDto,
+    ) -> Result<Attachment, sqlx::Error>;
+
+    /// Removes an attachment, returning it so its file can be removed from storage.
+    async fn delete_attachment(
+        &self,
+        post_id: Uuid,
+        attachment_id: Uuid,
+    ) -> Result<Option<Attachment>, sqlx::Error>;
+}
+
+#[async_trait]
+impl AttachmentExt for DBClient {
+    async fn get_posts_attachments(
+        &self,
+        post_ids: &[Uuid],
+    ) -> Result<Vec<Attachment>, sqlx::Error> {
+        let mut attachments: Vec<Attachment> = sqlx::query_as(
+            r#"
+            SELECT * FROM attachments
+                WHERE post_id = ANY($1)
+                ORDER BY created_at, id
+        "#,
+        )
+        .bind(post_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let storage_keys: Vec<String> = attachments
+            .iter()
+            .map(|attachment| attachment.storage_key.to_owned())
+            .collect();
+
+        let variants = self.get_image_variants(&storage_keys).await?;
+
+        for attachment in attachments.iter_mut() {
+            attachment.variants = variants
+                .iter()
+                .filter(|variant| variant.source_key == attachment.storage_key)
+                .cloned()
+                .collect();
+        }
+
+        Ok(attachments)
+    }
+
+    async fn save_attachment(
+        &self,
+        post_id: Uuid,
+        uploader_id: Uuid,
+        dto: CreateAttachmentDto,
+    ) -> Result<Attachment, sqlx::Error> {
+        let attachment = sqlx::query_as(
+            r#"
+            INSERT INTO attachments (post_id, uploader_id, storage_key, url, filename, content_type, size)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                RETURNING *
+        "#,
+        )
+        .bind(post_id)
+        .bind(uploader_id)
+        .bind(dto.storage_key)
+        .bind(dto.url)
+        .bind(dto.filename)
+        .bind(dto.content_type)
+        .bind(dto.size)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(attachment)
+    }
+
+    async fn delete_attachment(
+        &self,
+        post_id: Uuid,
+        attachment_id: Uuid,
+    ) -> Result<Option<Attachment>, sqlx::Error> {
+        let attachment =
+            sqlx::query_as(r#"DELETE FROM attachments WHERE id = $1 AND post_id = $2 RETURNING *"#)
+                .bind(attachment_id)
+                .bind(post_id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(attachment)
+    }
+}
@@ -0,0 +1,244 @@
+use async_trait::async_trait;
+use sqlx::{Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use crate::models::{Person, User};
+
+use super::{follow::FOLLOW_COUNTS, DBClient};
+
+#[async_trait]
+pub trait BlockExt {
+    /// Blocks a person and drops the follows between the two of them, both ways.
+    async fn save_block(&self, blocker_id: Uuid, blocked_id: Uuid) -> Result<bool, sqlx::Error>;
+
+    async fn delete_block(&self, blocker_id: Uuid, blocked_id: Uuid) -> Result<bool, sqlx::Error>;
+
+    /// Whether either of the two people blocked the other.
+    async fn is_blocked(&self, person_id: Uuid, other_id: Uuid) -> Result<bool, sqlx::Error>;
+
+    /// Users blocked by `blocker_id`, most recent first.
+    async fn get_blocked_users(
+        &self,
+        blocker_id: Uuid,
+        page: u32,
+        limit: usize,
+    ) -> Result<Vec<User>, sqlx::Error>;
+
+    async fn save_mute(&self, muter_id: Uuid, muted_id: Uuid) -> Result<bool, sqlx::Error>;
+
+    async fn delete_mute(&self, muter_id: Uuid, muted_id: Uuid) -> Result<bool, sqlx::Error>;
+
+    /// Users muted by `muter_id`, most recent first.
+    async fn get_muted_users(
+        &self,
+        muter_id: Uuid,
+        page: u32,
+        limit: usize,
+    ) -> Result<Vec<User>, sqlx::Error>;
+}
+
+#[async_trait]
+impl BlockExt for DBClient {
+    async fn save_block(&self, blocker_id: Uuid, blocked_id: Uuid) -> Result<bool, sqlx::Error> {
+        let mut is_saved = false;
+
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO blocks (blocker_id, blocked_id)
+                VALUES ($1, $2)
+                ON CONFLICT DO NOTHING
+        "#,
+        )
+        .bind(blocker_id)
+        .bind(blocked_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM follows
+                WHERE (follower_id = $1 AND followee_id = $2)
+                OR (follower_id = $2 AND followee_id = $1)
+        "#,
+        )
+        .bind(blocker_id)
+        .bind(blocked_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        if result.rows_affected() > 0 {
+            is_saved = true;
+        }
+
+        Ok(is_saved)
+    }
+
+    async fn delete_block(&self, blocker_id: Uuid, blocked_id: Uuid) -> Result<bool, sqlx::Error> {
+        let mut is_deleted = false;
+
+        let result = sqlx::query("DELETE FROM blocks WHERE blocker_id = $1 AND blocked_id = $2")
+            .bind(blocker_id)
+            .bind(blocked_id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() > 0 {
+            is_deleted = true;
+        }
+
+        Ok(is_deleted)
+    }
+
+    async fn is_blocked(&self, person_id: Uuid, other_id: Uuid) -> Result<bool, sqlx::Error> {
+        let is_blocked = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM blocks
+                    WHERE (blocker_id = $1 AND blocked_id = $2)
+                    OR (blocker_id = $2 AND blocked_id = $1)
+            )
+        "#,
+        )
+        .bind(person_id)
+        .bind(other_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(is_blocked)
+    }
+
+    async fn get_blocked_users(
+        &self,
+        blocker_id: Uuid,
+        page: u32,
+        limit: usize,
+    ) -> Result<Vec<User>, sqlx::Error> {
+        let offset: u32 = (page - 1) * limit as u32;
+
+        let people: Vec<Person> = sqlx::query_as(&format!(
+            r#"
+            SELECT people.*, {FOLLOW_COUNTS} FROM blocks
+                JOIN people ON people.id = blocks.blocked_id
+                WHERE blocks.blocker_id = $1 AND people.deleted_at IS NULL
+                ORDER BY blocks.created_at DESC, people.id
+                OFFSET $2
+                LIMIT $3
+        "#
+        ))
+        .bind(blocker_id)
+        .bind(offset as i64)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        self.with_avatar_variants(people).await
+    }
+
+    async fn save_mute(&self, muter_id: Uuid, muted_id: Uuid) -> Result<bool, sqlx::Error> {
+        let mut is_saved = false;
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO mutes (muter_id, muted_id)
+                VALUES ($1, $2)
+                ON CONFLICT DO NOTHING
+        "#,
+        )
+        .bind(muter_id)
+        .bind(muted_id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            is_saved = true;
+        }
+
+        Ok(is_saved)
+    }
+
+    async fn delete_mute(&self, muter_id: Uuid, muted_id: Uuid) -> Result<bool, sqlx::Error> {
+        let mut is_deleted = false;
+
+        let result = sqlx::query("DELETE FROM mutes WHERE muter_id = $1 AND muted_id = $2")
+            .bind(muter_id)
+            .bind(muted_id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() > 0 {
+            is_deleted = true;
+        }
+
+        Ok(is_deleted)
+    }
+
+    async fn get_muted_users(
+        &self,
+        muter_id: Uuid,
+        page: u32,
+        limit: usize,
+    ) -> Result<Vec<User>, sqlx::Error> {
+        let offset: u32 = (page - 1) * limit as u32;
+
+        let people: Vec<Person> = sqlx::query_as(&format!(
+            r#"
+            SELECT people.*, {FOLLOW_COUNTS} FROM mutes
+                JOIN people ON people.id = mutes.muted_id
+                WHERE mutes.muter_id = $1 AND people.deleted_at IS NULL
+                ORDER BY mutes.created_at DESC, people.id
+                OFFSET $2
+                LIMIT $3
+        "#
+        ))
+        .bind(muter_id)
+        .bind(offset as i64)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        self.with_avatar_variants(people).await
+    }
+}
+
+/// Leaves out rows whose `person_column` blocked the viewer or was blocked by
+/// them. Anonymous viewers are not filtered.
+pub(super) fn push_blocked_people_filter(
+    query_builder: &mut QueryBuilder<'_, Postgres>,
+    person_column: &str,
+    viewer_id: Option<Uuid>,
+) {
+    let Some(viewer_id) = viewer_id else {
+        return;
+    };
+
+    query_builder.push(" AND NOT EXISTS (SELECT 1 FROM blocks WHERE (blocks.blocker_id = ");
+    query_builder.push_bind(viewer_id);
+    query_builder.push(format!(
+        " AND blocks.blocked_id = {person_column}) OR (blocks.blocker_id = {person_column} AND blocks.blocked_id = "
+    ));
+    query_builder.push_bind(viewer_id);
+    query_builder.push("))");
+}
+
+/// Like `push_blocked_people_filter`, but also leaves out people the viewer
+/// muted. Mutes only apply to feeds, so this is for post listings and the
+/// like rather than search or comments.
+pub(super) fn push_hidden_people_filter(
+    query_builder: &mut QueryBuilder<'_, Postgres>,
+    person_column: &str,
+    viewer_id: Option<Uuid>,
+) {
+    let Some(viewer_id) = viewer_id else {
+        return;
+    };
+
+    push_blocked_people_filter(query_builder, person_column, Some(viewer_id));
+
+    query_builder.push(" AND NOT EXISTS (SELECT 1 FROM mutes WHERE mutes.muter_id = ");
+    query_builder.push_bind(viewer_id);
+    query_builder.push(format!(" AND mutes.muted_id = {person_column})"));
+}
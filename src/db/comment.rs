@@ -0,0 +1,161 @@
+use async_trait::async_trait;
+use sqlx::QueryBuilder;
+use uuid::Uuid;
+
+use crate::{
+    dtos::comment::{CommentSort, CreateCommentDto, SearchCommentQueryDto, UpdateCommentDto},
+    models::Comment,
+};
+
+use super::{block::push_blocked_people_filter, DBClient};
+
+#[async_trait]
+pub trait CommentExt {
+    async fn get_comment(&self, comment_id: Uuid) -> Result<Option<Comment>, sqlx::Error>;
+
+    /// Comments on a post, or replies to a comment, leaving out those of
+    /// people the viewer blocked or was blocked by.
+    async fn get_comments(
+        &self,
+        post_id: Uuid,
+        parent_id: Option<Uuid>,
+        query: SearchCommentQueryDto,
+        viewer_id: Option<Uuid>,
+    ) -> Result<Vec<Comment>, sqlx::Error>;
+
+    async fn save_comment(
+        &self,
+        post_id: Uuid,
+        author_id: Uuid,
+        dto: CreateCommentDto,
+    ) -> Result<Comment, sqlx::Error>;
+
+    async fn update_comment(
+        &self,
+        comment_id: Uuid,
+        dto: UpdateCommentDto,
+    ) -> Result<bool, sqlx::Error>;
+
+    async fn delete_comment(&self, comment_id: Uuid) -> Result<bool, sqlx::Error>;
+}
+
+#[async_trait]
+impl CommentExt for DBClient {
+    async fn get_comment(&self, comment_id: Uuid) -> Result<Option<Comment>, sqlx::Error> {
+        let comment = sqlx::query_as(
+            r#"
+            SELECT c.*, (SELECT COUNT(*) FROM comments r WHERE r.parent_id = c.id) AS replies_count
+                FROM comments c
+                WHERE c.id = $1
+        "#,
+        )
+        .bind(comment_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(comment)
+    }
+
+    async fn get_comments(
+        &self,
+        post_id: Uuid,
+        parent_id: Option<Uuid>,
+        query: SearchCommentQueryDto,
+        viewer_id: Option<Uuid>,
+    ) -> Result<Vec<Comment>, sqlx::Error> {
+        let page = query.page.unwrap_or(1);
+        let limit = query.limit.unwrap_or(10);
+        let offset: u32 = (page - 1) * limit as u32;
+
+        let mut query_builder = QueryBuilder::new(
+            r#"
+            SELECT c.*, (SELECT COUNT(*) FROM comments r WHERE r.parent_id = c.id) AS replies_count
+                FROM comments c
+                WHERE c.post_id = "#,
+        );
+        query_builder.push_bind(post_id);
+
+        if let Some(parent_id) = parent_id {
+            query_builder.push(" AND c.parent_id = ");
+            query_builder.push_bind(parent_id);
+        } else {
+            query_builder.push(" AND c.parent_id IS NULL ");
+        }
+
+        push_blocked_people_filter(&mut query_builder, "c.author_id", viewer_id);
+
+        match query.sort.unwrap_or_default() {
+            CommentSort::Oldest => query_builder.push(" ORDER BY c.created_at ASC, c.id ASC "),
+            CommentSort::Newest => query_builder.push(" ORDER BY c.created_at DESC, c.id DESC "),
+        };
+
+        query_builder.push(" OFFSET ");
+        query_builder.push_bind(offset as i64);
+
+        query_builder.push(" LIMIT ");
+        query_builder.push_bind(limit as i64);
+
+        let comments = query_builder.build_query_as().fetch_all(&self.pool).await?;
+
+        Ok(comments)
+    }
+
+    async fn save_comment(
+        &self,
+        post_id: Uuid,
+        author_id: Uuid,
+        dto: CreateCommentDto,
+    ) -> Result<Comment, sqlx::Error> {
+        let comment = sqlx::query_as(
+            r#"
+            INSERT INTO comments (post_id, author_id, parent_id, body)
+                VALUES ($1, $2, $3, $4)
+                RETURNING *
+        "#,
+        )
+        .bind(post_id)
+        .bind(author_id)
+        .bind(dto.parent_id)
+        .bind(dto.body)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(comment)
+    }
+
+    async fn update_comment(
+        &self,
+        comment_id: Uuid,
+        dto: UpdateCommentDto,
+    ) -> Result<bool, sqlx::Error> {
+        let mut is_updated = false;
+
+        let result = sqlx::query!(
+            "UPDATE comments SET body = $1 WHERE id = $2",
+            dto.body,
+            comment_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            is_updated = true;
+        }
+
+        Ok(is_updated)
+    }
+
+    async fn delete_comment(&self, comment_id: Uuid) -> Result<bool, sqlx::Error> {
+        let mut is_deleted = false;
+
+        let result = sqlx::query!("DELETE FROM comments WHERE id = $1", comment_id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() > 0 {
+            is_deleted = true;
+        }
+
+        Ok(is_deleted)
+    }
+}
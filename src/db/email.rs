@@ -1,5 +1,4 @@
 use async_trait::async_trait;
-use sqlx::QueryBuilder;
 use uuid::Uuid;
 
 use crate::{
@@ -7,10 +6,11 @@ use crate::{
     models::Email,
 };
 
-use super::DBClient;
+use super::{update::UpdateBuilder, DBClient};
 
 #[async_trait]
 pub trait EmailExt {
+    /// Emails of trashed people are left out, as the people themselves are.
     async fn get_person_emails(&self, person_id: Uuid) -> Result<Vec<Email>, sqlx::Error>;
 
     async fn get_email_by_id(&self, email_id: Uuid) -> Result<Option<Email>, sqlx::Error>;
@@ -32,7 +32,11 @@ impl EmailExt for DBClient {
     async fn get_person_emails(&self, person_id: Uuid) -> Result<Vec<Email>, sqlx::Error> {
         let emails = sqlx::query_as!(
             Email,
-            r#"SELECT * FROM emails WHERE owner_id = $1"#,
+            r#"
+            SELECT emails.* FROM emails
+                INNER JOIN people ON people.id = emails.owner_id
+                WHERE emails.owner_id = $1 AND people.deleted_at IS NULL
+            "#,
             person_id
         )
         .fetch_all(&self.pool)
@@ -42,9 +46,17 @@ impl EmailExt for DBClient {
     }
 
     async fn get_email_by_id(&self, email_id: Uuid) -> Result<Option<Email>, sqlx::Error> {
-        let email = sqlx::query_as!(Email, r#"SELECT * FROM emails WHERE id = $1"#, email_id)
-            .fetch_optional(&self.pool)
-            .await?;
+        let email = sqlx::query_as!(
+            Email,
+            r#"
+            SELECT emails.* FROM emails
+                INNER JOIN people ON people.id = emails.owner_id
+                WHERE emails.id = $1 AND people.deleted_at IS NULL
+            "#,
+            email_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
 
         Ok(email)
     }
@@ -53,7 +65,7 @@ impl EmailExt for DBClient {
         let new_email = sqlx::query_as!(
             Email,
             r#"
-                INSERT INTO emails (owner_id, address, is_private, is_primary) VALUES ($1, $2, $3, $4) RETURNING *
+                INSERT INTO emails (owner_id, address, is_private, is_primary) VALUES ($1, $2, COALESCE($3, false), COALESCE($4, false)) RETURNING *
             "#,
             owner_id,
             dto.address, dto.is_private, dto.is_primary
@@ -72,52 +84,17 @@ impl EmailExt for DBClient {
     ) -> Result<bool, sqlx::Error> {
         let mut is_updated: bool = false;
 
-        let mut query_builder = QueryBuilder::new("UPDATE emails");
-
-        let mut is_using_dto = false;
-
-        if let Some(address) = dto.address {
-            is_using_dto = true;
+        let mut update = UpdateBuilder::new("emails");
 
-            query_builder.push(" SET ");
-            query_builder.push("address = ");
-            query_builder.push_bind(address);
-        }
-
-        if let Some(is_private) = dto.is_private {
-            if !is_using_dto {
-                query_builder.push(" SET ");
-                is_using_dto = true;
-            } else {
-                query_builder.push(", ");
-            }
-
-            query_builder.push("is_private = ");
-            query_builder.push_bind(is_private);
-        }
-
-        if let Some(is_primary) = dto.is_primary {
-            if !is_using_dto {
-                query_builder.push(" SET ");
-                is_using_dto = true;
-            } else {
-                query_builder.push(", ");
-            }
+        update
+            .set_some("address", dto.address)
+            .set_some("is_private", dto.is_private)
+            .set_some("is_primary", dto.is_primary)
+            .set_some("is_verified", dto.is_verified);
 
-            query_builder.push("is_primary = ");
-            query_builder.push_bind(is_primary);
-        }
-
-        if let Some(is_verified) = dto.is_verified {
-            if !is_using_dto {
-                query_builder.push(" SET ");
-            } else {
-                query_builder.push(", ");
-            }
-
-            query_builder.push("is_verified = ");
-            query_builder.push_bind(is_verified);
-        }
+        let Some(mut query_builder) = update.finish() else {
+            return Ok(is_updated);
+        };
 
         query_builder.push(" WHERE id = ");
         query_builder.push_bind(email_id);
@@ -0,0 +1,124 @@
+use sqlx::postgres::PgDatabaseError;
+
+// Postgres SQLSTATE codes, see https://www.postgresql.org/docs/current/errcodes-appendix.html
+const UNIQUE_VIOLATION: &str = "23505";
+const FOREIGN_KEY_VIOLATION: &str = "23503";
+const CHECK_VIOLATION: &str = "23514";
+const NOT_NULL_VIOLATION: &str = "23502";
+const INVALID_TEXT_REPRESENTATION: &str = "22P02";
+const STRING_DATA_RIGHT_TRUNCATION: &str = "22001";
+
+// Constraints whose generated names do not map cleanly onto the field a client sent.
+const KNOWN_CONSTRAINTS: &[(&str, &str)] = &[
+    ("people_username_key", "username"),
+    ("emails_address_key", "email"),
+    ("fk_owner", "owner_id"),
+    ("fk_post", "post_id"),
+    ("fk_author", "author_id"),
+    ("fk_parent", "parent_id"),
+    ("fk_editor", "editor_id"),
+    ("people_gender_self_described_check", "gender_description"),
+];
+
+// Enum types mapped to the column that uses them.
+const KNOWN_ENUMS: &[(&str, &str)] = &[
+    ("gender", "gender"),
+    ("person_role", "role"),
+    ("reaction_kind", "kind"),
+    ("post_status", "status"),
+    ("image_variant_status", "status"),
+    ("follow_status", "status"),
+    ("notification_kind", "kind"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViolationKind {
+    Unique,
+    ForeignKey,
+    Check,
+    NotNull,
+    InvalidValue,
+    ValueTooLong,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintViolation {
+    pub kind: ViolationKind,
+    pub constraint: Option<String>,
+    pub field: Option<String>,
+}
+
+/// Classifies a database error that was caused by the data a client sent, as
+/// opposed to an actual server failure. Returns `None` for everything else.
+pub fn constraint_violation(error: &sqlx::Error) -> Option<ConstraintViolation> {
+    let db_err = match error {
+        sqlx::Error::Database(db_err) => db_err,
+        _ => return None,
+    };
+
+    let pg_err = db_err.try_downcast_ref::<PgDatabaseError>()?;
+
+    let kind = match pg_err.code() {
+        UNIQUE_VIOLATION => ViolationKind::Unique,
+        FOREIGN_KEY_VIOLATION => ViolationKind::ForeignKey,
+        CHECK_VIOLATION => ViolationKind::Check,
+        NOT_NULL_VIOLATION => ViolationKind::NotNull,
+        INVALID_TEXT_REPRESENTATION => ViolationKind::InvalidValue,
+        STRING_DATA_RIGHT_TRUNCATION => ViolationKind::ValueTooLong,
+        _ => return None,
+    };
+
+    let constraint = pg_err.constraint().map(|c| c.to_string());
+
+    let field = match kind {
+        ViolationKind::NotNull => pg_err.column().map(|c| c.to_string()),
+        ViolationKind::InvalidValue => enum_field(pg_err.message()),
+        _ => constraint
+            .as_deref()
+            .map(|c| constraint_field(c, pg_err.table())),
+    };
+
+    Some(ConstraintViolation {
+        kind,
+        constraint,
+        field,
+    })
+}
+
+fn constraint_field(constraint: &str, table: Option<&str>) -> String {
+    if let Some((_, field)) = KNOWN_CONSTRAINTS.iter().find(|(c, _)| *c == constraint) {
+        return field.to_string();
+    }
+
+    // Postgres names implicit constraints `<table>_<columns>_<suffix>`.
+    let mut field = constraint;
+
+    if let Some(table) = table {
+        field = field
+            .strip_prefix(table)
+            .and_then(|f| f.strip_prefix('_'))
+            .unwrap_or(field);
+    }
+
+    for suffix in ["_key", "_fkey", "_check", "_pkey"] {
+        if let Some(stripped) = field.strip_suffix(suffix) {
+            field = stripped;
+            break;
+        }
+    }
+
+    field.to_string()
+}
+
+fn enum_field(message: &str) -> Option<String> {
+    // e.g. `invalid input value for enum gender: "robot"`
+    let enum_name = message
+        .strip_prefix("invalid input value for enum ")?
+        .split(':')
+        .next()?;
+
+    KNOWN_ENUMS
+        .iter()
+        .find(|(e, _)| *e == enum_name)
+        .map(|(_, field)| field.to_string())
+}
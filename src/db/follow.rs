@@ -0,0 +1,245 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::models::{Follow, FollowStatus, Person, User};
+
+use super::DBClient;
+
+/// Selects the accepted followers and followings of `people` rows, leaving out
+/// accounts that are in the trash.
+pub(super) const FOLLOW_COUNTS: &str = r#"
+    (SELECT COUNT(*) FROM follows JOIN people AS follower ON follower.id = follows.follower_id
+        WHERE follows.followee_id = people.id AND follows.status = 'accepted' AND follower.deleted_at IS NULL
+    ) AS followers_count,
+    (SELECT COUNT(*) FROM follows JOIN people AS followee ON followee.id = follows.followee_id
+        WHERE follows.follower_id = people.id AND follows.status = 'accepted' AND followee.deleted_at IS NULL
+    ) AS following_count
+"#;
+
+#[async_trait]
+pub trait FollowExt {
+    async fn get_follow(
+        &self,
+        follower_id: Uuid,
+        followee_id: Uuid,
+    ) -> Result<Option<Follow>, sqlx::Error>;
+
+    /// Follows a user, or asks to when their profile is private. Returns `None`
+    /// when the follow already exists, the user is gone or either of them
+    /// blocked the other.
+    async fn save_follow(
+        &self,
+        follower_id: Uuid,
+        followee_id: Uuid,
+    ) -> Result<Option<Follow>, sqlx::Error>;
+
+    /// Accepts a pending follow request.
+    async fn accept_follow(
+        &self,
+        follower_id: Uuid,
+        followee_id: Uuid,
+    ) -> Result<bool, sqlx::Error>;
+
+    /// Accepts every pending request to follow a user, returning how many there were.
+    async fn accept_follow_requests(&self, followee_id: Uuid) -> Result<u64, sqlx::Error>;
+
+    /// Removes a follow, or only one in the given `status`.
+    async fn delete_follow(
+        &self,
+        follower_id: Uuid,
+        followee_id: Uuid,
+        status: Option<FollowStatus>,
+    ) -> Result<bool, sqlx::Error>;
+
+    /// Users following `user_id` with the given `status`, most recent first.
+    async fn get_followers(
+        &self,
+        user_id: Uuid,
+        status: FollowStatus,
+        page: u32,
+        limit: usize,
+    ) -> Result<Vec<User>, sqlx::Error>;
+
+    /// Users followed by `user_id`, most recent first.
+    async fn get_following(
+        &self,
+        user_id: Uuid,
+        page: u32,
+        limit: usize,
+    ) -> Result<Vec<User>, sqlx::Error>;
+}
+
+#[async_trait]
+impl FollowExt for DBClient {
+    async fn get_follow(
+        &self,
+        follower_id: Uuid,
+        followee_id: Uuid,
+    ) -> Result<Option<Follow>, sqlx::Error> {
+        let follow =
+            sqlx::query_as(r#"SELECT * FROM follows WHERE follower_id = $1 AND followee_id = $2"#)
+                .bind(follower_id)
+                .bind(followee_id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(follow)
+    }
+
+    async fn save_follow(
+        &self,
+        follower_id: Uuid,
+        followee_id: Uuid,
+    ) -> Result<Option<Follow>, sqlx::Error> {
+        // The privacy of the profile is read in the same statement, so a
+        // request can't slip through while the profile is made private.
+        let follow = sqlx::query_as(
+            r#"
+            INSERT INTO follows (follower_id, followee_id, status, accepted_at)
+                SELECT $1, id,
+                    CASE WHEN is_profile_private THEN 'pending'::follow_status ELSE 'accepted'::follow_status END,
+                    CASE WHEN is_profile_private THEN NULL ELSE NOW() END
+                FROM people
+                WHERE id = $2 AND role = 'user' AND deleted_at IS NULL
+                AND NOT EXISTS (
+                    SELECT 1 FROM blocks
+                        WHERE (blocker_id = $1 AND blocked_id = $2)
+                        OR (blocker_id = $2 AND blocked_id = $1)
+                )
+                ON CONFLICT DO NOTHING
+                RETURNING *
+        "#,
+        )
+        .bind(follower_id)
+        .bind(followee_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(follow)
+    }
+
+    async fn accept_follow(
+        &self,
+        follower_id: Uuid,
+        followee_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let mut is_accepted = false;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE follows
+                SET status = 'accepted', accepted_at = NOW()
+                WHERE follower_id = $1 AND followee_id = $2 AND status = 'pending'
+        "#,
+        )
+        .bind(follower_id)
+        .bind(followee_id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            is_accepted = true;
+        }
+
+        Ok(is_accepted)
+    }
+
+    async fn accept_follow_requests(&self, followee_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE follows
+                SET status = 'accepted', accepted_at = NOW()
+                WHERE followee_id = $1 AND status = 'pending'
+        "#,
+        )
+        .bind(followee_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn delete_follow(
+        &self,
+        follower_id: Uuid,
+        followee_id: Uuid,
+        status: Option<FollowStatus>,
+    ) -> Result<bool, sqlx::Error> {
+        let mut is_deleted = false;
+
+        let result = sqlx::query(
+            r#"
+            DELETE FROM follows
+                WHERE follower_id = $1 AND followee_id = $2
+                AND ($3::follow_status IS NULL OR status = $3)
+        "#,
+        )
+        .bind(follower_id)
+        .bind(followee_id)
+        .bind(status)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            is_deleted = true;
+        }
+
+        Ok(is_deleted)
+    }
+
+    async fn get_followers(
+        &self,
+        user_id: Uuid,
+        status: FollowStatus,
+        page: u32,
+        limit: usize,
+    ) -> Result<Vec<User>, sqlx::Error> {
+        let offset: u32 = (page - 1) * limit as u32;
+
+        let people: Vec<Person> = sqlx::query_as(&format!(
+            r#"
+            SELECT people.*, {FOLLOW_COUNTS} FROM follows
+                JOIN people ON people.id = follows.follower_id
+                WHERE follows.followee_id = $1 AND follows.status = $2 AND people.deleted_at IS NULL
+                ORDER BY follows.created_at DESC, people.id
+                OFFSET $3
+                LIMIT $4
+        "#
+        ))
+        .bind(user_id)
+        .bind(status)
+        .bind(offset as i64)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        self.with_avatar_variants(people).await
+    }
+
+    async fn get_following(
+        &self,
+        user_id: Uuid,
+        page: u32,
+        limit: usize,
+    ) -> Result<Vec<User>, sqlx::Error> {
+        let offset: u32 = (page - 1) * limit as u32;
+
+        let people: Vec<Person> = sqlx::query_as(&format!(
+            r#"
+            SELECT people.*, {FOLLOW_COUNTS} FROM follows
+                JOIN people ON people.id = follows.followee_id
+                WHERE follows.follower_id = $1 AND follows.status = 'accepted' AND people.deleted_at IS NULL
+                ORDER BY follows.created_at DESC, people.id
+                OFFSET $2
+                LIMIT $3
+        "#
+        ))
+        .bind(user_id)
+        .bind(offset as i64)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        self.with_avatar_variants(people).await
+    }
+}
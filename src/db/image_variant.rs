@@ -0,0 +1,192 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{config::ThumbnailSpec, models::ImageVariant};
+
+use super::DBClient;
+
+#[async_trait]
+pub trait ImageVariantExt {
+    /// Variants of all the given originals, ordered by size.
+    async fn get_image_variants(
+        &self,
+        source_keys: &[String],
+    ) -> Result<Vec<ImageVariant>, sqlx::Error>;
+
+    /// Queues one `pending` variant per spec for an uploaded image.
+    async fn queue_image_variants(
+        &self,
+        source_key: &str,
+        specs: &[ThumbnailSpec],
+    ) -> Result<Vec<ImageVariant>, sqlx::Error>;
+
+    /// Leases up to `limit` pending variants for `lease_seconds`, so that other
+    /// instances skip them until the lease runs out.
+    async fn claim_pending_image_variants(
+        &self,
+        limit: i64,
+        lease_seconds: i64,
+    ) -> Result<Vec<ImageVariant>, sqlx::Error>;
+
+    /// Marks a variant as ready. Returns `false` when the variant was removed
+    /// while it was being rendered.
+    async fn complete_image_variant(
+        &self,
+        variant_id: Uuid,
+        storage_key: &str,
+        url: &str,
+        width: i32,
+        height: i32,
+    ) -> Result<bool, sqlx::Error>;
+
+    /// Releases a variant that could not be rendered, giving up on it after
+    /// `max_attempts`.
+    async fn fail_image_variant(
+        &self,
+        variant_id: Uuid,
+        max_attempts: i32,
+    ) -> Result<(), sqlx::Error>;
+
+    /// Removes the variants of the given originals, returning the storage keys
+    /// of the files that were already rendered.
+    async fn delete_image_variants(
+        &self,
+        source_keys: &[String],
+    ) -> Result<Vec<String>, sqlx::Error>;
+}
+
+#[async_trait]
+impl ImageVariantExt for DBClient {
+    async fn get_image_variants(
+        &self,
+        source_keys: &[String],
+    ) -> Result<Vec<ImageVariant>, sqlx::Error> {
+        let variants = sqlx::query_as(
+            r#"
+            SELECT * FROM image_variants
+                WHERE source_key = ANY($1)
+                ORDER BY max_size, name
+        "#,
+        )
+        .bind(source_keys)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(variants)
+    }
+
+    async fn queue_image_variants(
+        &self,
+        source_key: &str,
+        specs: &[ThumbnailSpec],
+    ) -> Result<Vec<ImageVariant>, sqlx::Error> {
+        let names: Vec<String> = specs.iter().map(|spec| spec.name.to_owned()).collect();
+        let sizes: Vec<i32> = specs.iter().map(|spec| spec.size as i32).collect();
+
+        let variants = sqlx::query_as(
+            r#"
+            INSERT INTO image_variants (source_key, name, max_size)
+                SELECT $1, name, max_size FROM UNNEST($2::VARCHAR[], $3::INTEGER[]) AS spec(name, max_size)
+                ON CONFLICT (source_key, name) DO NOTHING
+                RETURNING *
+        "#,
+        )
+        .bind(source_key)
+        .bind(names)
+        .bind(sizes)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(variants)
+    }
+
+    async fn claim_pending_image_variants(
+        &self,
+        limit: i64,
+        lease_seconds: i64,
+    ) -> Result<Vec<ImageVariant>, sqlx::Error> {
+        let variants = sqlx::query_as(
+            r#"
+            UPDATE image_variants
+                SET attempts = attempts + 1,
+                    locked_until = NOW() + make_interval(secs => $2::int)
+                WHERE id IN (
+                    SELECT id FROM image_variants
+                        WHERE status = 'pending'
+                            AND (locked_until IS NULL OR locked_until < NOW())
+                        ORDER BY created_at
+                        LIMIT $1
+                        FOR UPDATE SKIP LOCKED
+                )
+                RETURNING *
+        "#,
+        )
+        .bind(limit)
+        .bind(lease_seconds)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(variants)
+    }
+
+    async fn complete_image_variant(
+        &self,
+        variant_id: Uuid,
+        storage_key: &str,
+        url: &str,
+        width: i32,
+        height: i32,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE image_variants
+                SET status = 'ready', storage_key = $2, url = $3, width = $4, height = $5, locked_until = NULL
+                WHERE id = $1 AND status = 'pending'
+        "#,
+        )
+        .bind(variant_id)
+        .bind(storage_key)
+        .bind(url)
+        .bind(width)
+        .bind(height)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn fail_image_variant(
+        &self,
+        variant_id: Uuid,
+        max_attempts: i32,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE image_variants
+                SET status = CASE WHEN attempts >= $2 THEN 'failed'::image_variant_status ELSE status END,
+                    locked_until = NULL
+                WHERE id = $1 AND status = 'pending'
+        "#,
+        )
+        .bind(variant_id)
+        .bind(max_attempts)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_image_variants(
+        &self,
+        source_keys: &[String],
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let storage_keys: Vec<Option<String>> = sqlx::query_scalar(
+            r#"DELETE FROM image_variants WHERE source_key = ANY($1) RETURNING storage_key"#,
+        )
+        .bind(source_keys)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(storage_keys.into_iter().flatten().collect())
+    }
+}
@@ -1,8 +1,19 @@
 use sqlx::{Pool, Postgres};
 
+pub mod attachment;
+pub mod block;
+pub mod comment;
 pub mod email;
+pub mod error;
+pub mod follow;
+pub mod image_variant;
+pub mod notification;
 pub mod person;
 pub mod post;
+pub mod reaction;
+pub mod revision;
+pub mod tag;
+pub mod update;
 
 #[derive(Clone, Debug)]
 pub struct DBClient {
@@ -0,0 +1,254 @@
+use async_trait::async_trait;
+use sqlx::{types::Json, QueryBuilder};
+use uuid::Uuid;
+
+use crate::{
+    models::{Notification, NotificationKind, NotificationPayload},
+    utils::cursor::Cursor,
+};
+
+use super::{block::push_hidden_people_filter, DBClient};
+
+#[async_trait]
+pub trait NotificationExt {
+    /// Notifies `recipient_id` of something `actor_id` did. Returns `None`
+    /// when nothing was saved: people are not notified of their own actions,
+    /// of kinds they turned off, or by someone they blocked, muted or were
+    /// blocked by.
+    async fn save_notification(
+        &self,
+        recipient_id: Uuid,
+        actor_id: Uuid,
+        payload: NotificationPayload,
+    ) -> Result<Option<Notification>, sqlx::Error>;
+
+    /// Notifications of `recipient_id`, newest first, starting after `after`
+    /// when given.
+    async fn get_notifications(
+        &self,
+        recipient_id: Uuid,
+        unread_only: bool,
+        after: Option<Cursor>,
+        limit: i64,
+    ) -> Result<Vec<Notification>, sqlx::Error>;
+
+    async fn get_unread_notifications_count(&self, recipient_id: Uuid) -> Result<i64, sqlx::Error>;
+
+    /// Marking a notification that was already read leaves its `read_at` as it was.
+    async fn mark_notification_read(
+        &self,
+        notification_id: Uuid,
+        recipient_id: Uuid,
+    ) -> Result<bool, sqlx::Error>;
+
+    /// Returns how many notifications were unread.
+    async fn mark_all_notifications_read(&self, recipient_id: Uuid) -> Result<u64, sqlx::Error>;
+
+    /// Kinds of notifications the person turned off.
+    async fn get_disabled_notification_kinds(
+        &self,
+        person_id: Uuid,
+    ) -> Result<Option<Vec<NotificationKind>>, sqlx::Error>;
+
+    /// Turns kinds of notifications on and off, returning the ones that are now off.
+    async fn update_disabled_notification_kinds(
+        &self,
+        person_id: Uuid,
+        enabled: &[NotificationKind],
+        disabled: &[NotificationKind],
+    ) -> Result<Option<Vec<NotificationKind>>, sqlx::Error>;
+}
+
+#[async_trait]
+impl NotificationExt for DBClient {
+    async fn save_notification(
+        &self,
+        recipient_id: Uuid,
+        actor_id: Uuid,
+        payload: NotificationPayload,
+    ) -> Result<Option<Notification>, sqlx::Error> {
+        let notification = sqlx::query_as(
+            r#"
+            INSERT INTO notifications (recipient_id, actor_id, kind, payload)
+                SELECT id, $2, $3, $4
+                FROM people
+                WHERE id = $1 AND id <> $2 AND deleted_at IS NULL
+                AND NOT EXISTS (
+                    SELECT 1 FROM notification_preferences
+                        WHERE person_id = $1 AND $3 = ANY(disabled_kinds)
+                )
+                AND NOT EXISTS (
+                    SELECT 1 FROM blocks
+                        WHERE (blocker_id = $1 AND blocked_id = $2)
+                        OR (blocker_id = $2 AND blocked_id = $1)
+                )
+                AND NOT EXISTS (SELECT 1 FROM mutes WHERE muter_id = $1 AND muted_id = $2)
+                RETURNING *
+        "#,
+        )
+        .bind(recipient_id)
+        .bind(actor_id)
+        .bind(payload.kind())
+        .bind(Json(payload))
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(notification)
+    }
+
+    async fn get_notifications(
+        &self,
+        recipient_id: Uuid,
+        unread_only: bool,
+        after: Option<Cursor>,
+        limit: i64,
+    ) -> Result<Vec<Notification>, sqlx::Error> {
+        let mut query_builder =
+            QueryBuilder::new("SELECT * FROM notifications WHERE notifications.recipient_id = ");
+        query_builder.push_bind(recipient_id);
+
+        // Hides what people did before they were blocked or muted.
+        push_hidden_people_filter(
+            &mut query_builder,
+            "notifications.actor_id",
+            Some(recipient_id),
+        );
+
+        if unread_only {
+            query_builder.push(" AND notifications.read_at IS NULL");
+        }
+
+        if let Some(cursor) = after {
+            query_builder.push(" AND (notifications.created_at, notifications.id) < (");
+            query_builder.push_bind(cursor.timestamp);
+            query_builder.push(", ");
+            query_builder.push_bind(cursor.id);
+            query_builder.push(")");
+        }
+
+        query_builder.push(" ORDER BY notifications.created_at DESC, notifications.id DESC LIMIT ");
+        query_builder.push_bind(limit);
+
+        let notifications = query_builder.build_query_as().fetch_all(&self.pool).await?;
+
+        Ok(notifications)
+    }
+
+    async fn get_unread_notifications_count(&self, recipient_id: Uuid) -> Result<i64, sqlx::Error> {
+        let mut query_builder = QueryBuilder::new(
+            r#"
+            SELECT COUNT(*) FROM notifications
+                WHERE notifications.read_at IS NULL
+                AND notifications.recipient_id = "#,
+        );
+        query_builder.push_bind(recipient_id);
+
+        push_hidden_people_filter(
+            &mut query_builder,
+            "notifications.actor_id",
+            Some(recipient_id),
+        );
+
+        let count = query_builder
+            .build_query_scalar()
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count)
+    }
+
+    async fn mark_notification_read(
+        &self,
+        notification_id: Uuid,
+        recipient_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let mut is_marked = false;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE notifications
+                SET read_at = COALESCE(read_at, NOW())
+                WHERE id = $1 AND recipient_id = $2
+        "#,
+        )
+        .bind(notification_id)
+        .bind(recipient_id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            is_marked = true;
+        }
+
+        Ok(is_marked)
+    }
+
+    async fn mark_all_notifications_read(&self, recipient_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE notifications
+                SET read_at = NOW()
+                WHERE recipient_id = $1 AND read_at IS NULL
+        "#,
+        )
+        .bind(recipient_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn get_disabled_notification_kinds(
+        &self,
+        person_id: Uuid,
+    ) -> Result<Option<Vec<NotificationKind>>, sqlx::Error> {
+        let kinds = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(notification_preferences.disabled_kinds, '{}')
+                FROM people
+                LEFT JOIN notification_preferences ON notification_preferences.person_id = people.id
+                WHERE people.id = $1 AND people.deleted_at IS NULL
+        "#,
+        )
+        .bind(person_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(kinds)
+    }
+
+    async fn update_disabled_notification_kinds(
+        &self,
+        person_id: Uuid,
+        enabled: &[NotificationKind],
+        disabled: &[NotificationKind],
+    ) -> Result<Option<Vec<NotificationKind>>, sqlx::Error> {
+        let kinds = sqlx::query_scalar(
+            r#"
+            INSERT INTO notification_preferences (person_id, disabled_kinds)
+                SELECT id, ARRAY(
+                    SELECT DISTINCT kind FROM UNNEST($3::notification_kind[]) AS kind
+                        WHERE kind <> ALL($2)
+                        ORDER BY kind
+                )
+                FROM people
+                WHERE id = $1 AND deleted_at IS NULL
+            ON CONFLICT (person_id) DO UPDATE
+                SET disabled_kinds = ARRAY(
+                    SELECT DISTINCT kind
+                        FROM UNNEST(notification_preferences.disabled_kinds || $3) AS kind
+                        WHERE kind <> ALL($2)
+                        ORDER BY kind
+                )
+                RETURNING disabled_kinds
+        "#,
+        )
+        .bind(person_id)
+        .bind(enabled)
+        .bind(disabled)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(kinds)
+    }
+}
@@ -1,5 +1,4 @@
 use async_trait::async_trait;
-use chrono::DateTime;
 use sqlx::QueryBuilder;
 use uuid::Uuid;
 
@@ -8,21 +7,30 @@ use crate::{
         CreateAdminDto, CreateUserDto, SearchAdminQueryDto, SearchUserQueryDto, UpdateAdminDto,
         UpdateUserDto,
     },
-    models::{Admin, Email, Gender, Person, User},
+    models::{Admin, Email, Person, PurgedRow, User},
 };
 
-use super::DBClient;
+use super::{
+    block::push_blocked_people_filter, follow::FOLLOW_COUNTS, image_variant::ImageVariantExt,
+    update::UpdateBuilder, DBClient,
+};
 
 #[async_trait]
 pub trait PersonExt {
+    async fn get_person(&self, person_id: Uuid) -> Result<Option<Person>, sqlx::Error>;
+
+    async fn get_person_by_username(&self, username: &str) -> Result<Option<Person>, sqlx::Error>;
+
     async fn get_user(&self, user_id: Uuid) -> Result<Option<User>, sqlx::Error>;
 
     async fn get_admin(&self, admin_id: Uuid) -> Result<Option<Admin>, sqlx::Error>;
 
+    /// Searches users, leaving out those the viewer blocked or was blocked by.
     async fn get_users(
         &self,
         query: SearchUserQueryDto,
         fetch_emails: bool,
+        viewer_id: Option<Uuid>,
     ) -> Result<Vec<User>, sqlx::Error>;
 
     async fn get_admins(
@@ -35,24 +43,90 @@ pub trait PersonExt {
 
     async fn save_admin(&self, dto: CreateAdminDto) -> Result<Admin, sqlx::Error>;
 
-    async fn update_user(&self, user_id: Uuid, dto: UpdateUserDto) -> Result<bool, sqlx::Error>;
+    /// With `expected_versions` the user is only updated while its version is one of them.
+    async fn update_user(
+        &self,
+        user_id: Uuid,
+        dto: UpdateUserDto,
+        expected_versions: Option<Vec<i32>>,
+    ) -> Result<bool, sqlx::Error>;
+
+    /// With `expected_versions` the admin is only updated while its version is one of them.
+    async fn update_admin(
+        &self,
+        admin_id: Uuid,
+        dto: UpdateAdminDto,
+        expected_versions: Option<Vec<i32>>,
+    ) -> Result<bool, sqlx::Error>;
 
-    async fn update_admin(&self, admin_id: Uuid, dto: UpdateAdminDto) -> Result<bool, sqlx::Error>;
+    /// Points the avatar of a user at another stored file, or clears it with `None`.
+    async fn update_user_avatar(
+        &self,
+        user_id: Uuid,
+        avatar_key: Option<String>,
+        avatar_url: Option<String>,
+    ) -> Result<bool, sqlx::Error>;
 
+    /// Moves a user to the trash, where the account can be restored until it is purged.
     async fn delete_user(&self, user_id: Uuid) -> Result<bool, sqlx::Error>;
 
+    /// Moves an admin to the trash, where the account can be restored until it is purged.
     async fn delete_admin(&self, admin_id: Uuid) -> Result<bool, sqlx::Error>;
+
+    /// Trashed users, without their emails.
+    async fn get_deleted_users(&self) -> Result<Vec<User>, sqlx::Error>;
+
+    /// Trashed admins, without their emails.
+    async fn get_deleted_admins(&self) -> Result<Vec<Admin>, sqlx::Error>;
+
+    /// Takes a user out of the trash, unless it was deleted more than
+    /// `retention_days` ago.
+    async fn restore_user(&self, user_id: Uuid, retention_days: i64) -> Result<bool, sqlx::Error>;
+
+    /// Takes an admin out of the trash, unless it was deleted more than
+    /// `retention_days` ago.
+    async fn restore_admin(&self, admin_id: Uuid, retention_days: i64)
+        -> Result<bool, sqlx::Error>;
+
+    /// Hard-deletes up to `limit` accounts that stayed in the trash longer than
+    /// `retention_days`, along with their emails, returning their ids and the
+    /// storage keys of their avatars.
+    async fn purge_deleted_people(
+        &self,
+        retention_days: i64,
+        limit: i64,
+    ) -> Result<Vec<PurgedRow>, sqlx::Error>;
 }
 
 #[async_trait]
 impl PersonExt for DBClient {
-    async fn get_user(&self, user_id: Uuid) -> Result<Option<User>, sqlx::Error> {
-        let person: Option<Person> =
-            sqlx::query_as(r#"SELECT * FROM people WHERE id = $1 AND role = 'user'"#)
-                .bind(user_id)
+    async fn get_person(&self, person_id: Uuid) -> Result<Option<Person>, sqlx::Error> {
+        let person = sqlx::query_as(r#"SELECT * FROM people WHERE id = $1 AND deleted_at IS NULL"#)
+            .bind(person_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(person)
+    }
+
+    async fn get_person_by_username(&self, username: &str) -> Result<Option<Person>, sqlx::Error> {
+        let person =
+            sqlx::query_as(r#"SELECT * FROM people WHERE username = $1 AND deleted_at IS NULL"#)
+                .bind(username)
                 .fetch_optional(&self.pool)
                 .await?;
 
+        Ok(person)
+    }
+
+    async fn get_user(&self, user_id: Uuid) -> Result<Option<User>, sqlx::Error> {
+        let person: Option<Person> = sqlx::query_as(&format!(
+            r#"SELECT *, {FOLLOW_COUNTS} FROM people WHERE id = $1 AND role = 'user' AND deleted_at IS NULL"#
+        ))
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
         if let Some(mut person) = person {
             let emails = sqlx::query_as!(
                 Email,
@@ -64,6 +138,10 @@ impl PersonExt for DBClient {
 
             person.emails = emails;
 
+            if let Some(avatar_key) = &person.avatar_key {
+                person.avatar_variants = self.get_image_variants(&[avatar_key.to_owned()]).await?;
+            }
+
             Ok(Some(User::from(person)))
         } else {
             Ok(None)
@@ -71,11 +149,12 @@ impl PersonExt for DBClient {
     }
 
     async fn get_admin(&self, admin_id: Uuid) -> Result<Option<Admin>, sqlx::Error> {
-        let person: Option<Person> =
-            sqlx::query_as(r#"SELECT * FROM people WHERE id = $1 AND role = 'admin'"#)
-                .bind(admin_id)
-                .fetch_optional(&self.pool)
-                .await?;
+        let person: Option<Person> = sqlx::query_as(
+            r#"SELECT * FROM people WHERE id = $1 AND role = 'admin' AND deleted_at IS NULL"#,
+        )
+        .bind(admin_id)
+        .fetch_optional(&self.pool)
+        .await?;
 
         if let Some(mut person) = person {
             let emails = sqlx::query_as!(
@@ -98,12 +177,15 @@ impl PersonExt for DBClient {
         &self,
         query: SearchUserQueryDto,
         fetch_emails: bool,
+        viewer_id: Option<Uuid>,
     ) -> Result<Vec<User>, sqlx::Error> {
         let page = query.page.unwrap_or(1);
         let limit = query.limit.unwrap_or(6);
         let offset: u32 = (page - 1) * limit as u32;
 
-        let mut query_builder = QueryBuilder::new(r#"SELECT * FROM people WHERE (role = 'user')"#);
+        let mut query_builder = QueryBuilder::new(format!(
+            r#"SELECT *, {FOLLOW_COUNTS} FROM people WHERE (role = 'user') AND deleted_at IS NULL"#
+        ));
 
         let mut is_using_query = false;
 
@@ -143,6 +225,8 @@ impl PersonExt for DBClient {
             query_builder.push(" ) ");
         }
 
+        push_blocked_people_filter(&mut query_builder, "people.id", viewer_id);
+
         query_builder.push(" OFFSET ");
         query_builder.push_bind(offset as i64);
 
@@ -151,25 +235,23 @@ impl PersonExt for DBClient {
 
         let people: Vec<Person> = query_builder.build_query_as().fetch_all(&self.pool).await?;
 
-        let mut result: Vec<User> = vec![];
+        let mut users = self.with_avatar_variants(people).await?;
 
-        for mut person in people.into_iter() {
-            if fetch_emails {
+        if fetch_emails {
+            for user in users.iter_mut() {
                 let emails = sqlx::query_as!(
                     Email,
                     r#"SELECT * FROM emails WHERE owner_id = $1"#,
-                    person.id
+                    user.id
                 )
                 .fetch_all(&self.pool)
                 .await?;
 
-                person.emails = emails;
+                user.emails = emails;
             }
-
-            result.push(User::from(person))
         }
 
-        Ok(result)
+        Ok(users)
     }
 
     async fn get_admins(
@@ -181,7 +263,9 @@ impl PersonExt for DBClient {
         let limit = query.limit.unwrap_or(6);
         let offset: u32 = (page - 1) * limit as u32;
 
-        let mut query_builder = QueryBuilder::new(r#"SELECT * FROM people WHERE (role = 'admin')"#);
+        let mut query_builder = QueryBuilder::new(
+            r#"SELECT * FROM people WHERE (role = 'admin') AND deleted_at IS NULL"#,
+        );
 
         let mut is_using_query = false;
 
@@ -251,155 +335,120 @@ impl PersonExt for DBClient {
     }
 
     async fn save_user(&self, dto: CreateUserDto) -> Result<User, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
         let mut new_person: Person = sqlx::query_as(r#"
             INSERT INTO people 
-                (firstname, lastname, username, gender, is_profile_private, birthdate, password, role)
-                VALUES ($1, $2, $3, $4, $5, $6, $7, 'user')
+                (firstname, lastname, username, gender, gender_description, pronouns, is_profile_private, birthdate, password, role)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, 'user')
                 RETURNING *
         "#)
             .bind(dto.firstname)
             .bind(dto.lastname)
             .bind(dto.username)
-            .bind(Gender::from(dto.gender))
-            .bind(dto.is_profile_private)
-            .bind(DateTime::parse_from_str(&dto.birthdate, "%Y-%m-%d").unwrap())
+            .bind(dto.gender)
+            .bind(dto.gender_description)
+            .bind(dto.pronouns)
+            .bind(dto.is_profile_private.unwrap_or(false))
+            .bind(dto.birthdate)
             .bind(dto.password)
-            .fetch_one(&self.pool).await?;
+            .fetch_one(&mut *tx).await?;
 
         let email = sqlx::query_as!(
         Email,
         r#"INSERT INTO emails (address, owner_id, is_primary, is_private) VALUES ($1, $2, true, true) RETURNING *"#,
         dto.email, new_person.id)
-            .fetch_one(&self.pool)
+            .fetch_one(&mut *tx)
             .await?;
 
+        tx.commit().await?;
+
         new_person.emails = vec![email];
 
         Ok(User::from(new_person))
     }
 
     async fn save_admin(&self, dto: CreateAdminDto) -> Result<Admin, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
         let mut new_person: Person = sqlx::query_as(
             r#"
             INSERT INTO people 
-                (firstname, lastname, username, gender, birthdate, password, role)
-                VALUES ($1, $2, $3, $4, $5, $6, 'user')
+                (firstname, lastname, username, gender, gender_description, pronouns, birthdate, password, role)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 'admin')
                 RETURNING *
         "#,
         )
         .bind(dto.firstname)
         .bind(dto.lastname)
         .bind(dto.username)
-        .bind(Gender::from(dto.gender))
-        .bind(DateTime::parse_from_str(&dto.birthdate, "%Y-%m-%d").unwrap())
+        .bind(dto.gender)
+        .bind(dto.gender_description)
+        .bind(dto.pronouns)
+        .bind(dto.birthdate)
         .bind(dto.password)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *tx)
         .await?;
 
         let email = sqlx::query_as!(
         Email,
         r#"INSERT INTO emails (address, owner_id, is_primary, is_private) VALUES ($1, $2, true, true) RETURNING *"#,
         dto.email, new_person.id)
-            .fetch_one(&self.pool)
+            .fetch_one(&mut *tx)
             .await?;
 
+        tx.commit().await?;
+
         new_person.emails = vec![email];
 
         Ok(Admin::from(new_person))
     }
 
-    async fn update_user(&self, user_id: Uuid, dto: UpdateUserDto) -> Result<bool, sqlx::Error> {
+    async fn update_user(
+        &self,
+        user_id: Uuid,
+        dto: UpdateUserDto,
+        expected_versions: Option<Vec<i32>>,
+    ) -> Result<bool, sqlx::Error> {
         let mut is_updated: bool = false;
 
-        let mut query_builder = QueryBuilder::new(r#"UPDATE people"#);
-
-        let mut is_using_dto = false;
-
-        if let Some(firstname) = dto.firstname {
-            if !is_using_dto {
-                query_builder.push(" SET ");
-                is_using_dto = true;
-            }
-
-            query_builder.push("firstname = ");
-            query_builder.push_bind(firstname);
-        }
-
-        if let Some(lastname) = dto.lastname {
-            if !is_using_dto {
-                query_builder.push(" SET ");
-                is_using_dto = true;
-            } else {
-                query_builder.push(",");
-            }
-
-            query_builder.push("lastname = ");
-            query_builder.push_bind(lastname);
-        }
-
-        if let Some(birthdate) = dto.birthdate {
-            if !is_using_dto {
-                query_builder.push(" SET ");
-                is_using_dto = true;
-            } else {
-                query_builder.push(",");
-            }
+        let mut update = UpdateBuilder::new("people");
 
-            query_builder.push("birthdate = ");
-            query_builder.push_bind(DateTime::parse_from_str(&birthdate, "%Y-%m-%d").unwrap());
-        }
+        update
+            .set_some("firstname", dto.firstname)
+            .set_some("lastname", dto.lastname)
+            .set_some("birthdate", dto.birthdate);
 
+        // Changing the gender always replaces the self-description, so that a
+        // stale description never outlives a `self_described` gender.
         if let Some(gender) = dto.gender {
-            if !is_using_dto {
-                query_builder.push(" SET ");
-                is_using_dto = true;
-            } else {
-                query_builder.push(",");
-            }
-
-            query_builder.push("gender = ");
-            query_builder.push_bind(Gender::from(gender));
-        }
-
-        if let Some(is_profile_private) = dto.is_profile_private {
-            if !is_using_dto {
-                query_builder.push(" SET ");
-                is_using_dto = true;
-            } else {
-                query_builder.push(",");
-            }
-
-            query_builder.push("is_profile_private = ");
-            query_builder.push_bind(is_profile_private);
+            update
+                .set("gender", gender)
+                .set("gender_description", dto.gender_description);
+        } else {
+            update.set_some("gender_description", dto.gender_description);
         }
 
-        if let Some(biography) = dto.biography {
-            if !is_using_dto {
-                query_builder.push(" SET ");
-                is_using_dto = true;
-            } else {
-                query_builder.push(",");
-            }
+        update
+            .set_some("pronouns", dto.pronouns)
+            .set_some("is_profile_private", dto.is_profile_private)
+            .set_some("biography", dto.biography)
+            .set_some("username", dto.username);
 
-            query_builder.push("biography = ");
-            query_builder.push_bind(biography);
-        }
-
-        if let Some(username) = dto.username {
-            if !is_using_dto {
-                query_builder.push(" SET ");
-            } else {
-                query_builder.push(",");
-            }
-
-            query_builder.push("username = ");
-            query_builder.push_bind(username);
-        }
+        let Some(mut query_builder) = update.finish() else {
+            return Ok(is_updated);
+        };
 
         query_builder.push(" WHERE id = ");
         query_builder.push_bind(user_id);
 
-        query_builder.push(" AND role = 'user' ");
+        query_builder.push(" AND role = 'user' AND deleted_at IS NULL ");
+
+        if let Some(versions) = expected_versions {
+            query_builder.push(" AND version = ANY(");
+            query_builder.push_bind(versions);
+            query_builder.push(")");
+        }
 
         let result = query_builder.build().execute(&self.pool).await?;
 
@@ -410,76 +459,73 @@ impl PersonExt for DBClient {
         Ok(is_updated)
     }
 
-    async fn update_admin(&self, admin_id: Uuid, dto: UpdateAdminDto) -> Result<bool, sqlx::Error> {
+    async fn update_admin(
+        &self,
+        admin_id: Uuid,
+        dto: UpdateAdminDto,
+        expected_versions: Option<Vec<i32>>,
+    ) -> Result<bool, sqlx::Error> {
         let mut is_updated: bool = false;
 
-        let mut query_builder = QueryBuilder::new(r#"UPDATE people"#);
-
-        let mut is_using_dto = false;
+        let mut update = UpdateBuilder::new("people");
 
-        if let Some(firstname) = dto.firstname {
-            if !is_using_dto {
-                query_builder.push(" SET ");
-                is_using_dto = true;
-            }
+        update
+            .set_some("firstname", dto.firstname)
+            .set_some("lastname", dto.lastname)
+            .set_some("birthdate", dto.birthdate);
 
-            query_builder.push("firstname = ");
-            query_builder.push_bind(firstname);
+        if let Some(gender) = dto.gender {
+            update
+                .set("gender", gender)
+                .set("gender_description", dto.gender_description);
+        } else {
+            update.set_some("gender_description", dto.gender_description);
         }
 
-        if let Some(lastname) = dto.lastname {
-            if !is_using_dto {
-                query_builder.push(" SET ");
-                is_using_dto = true;
-            } else {
-                query_builder.push(",");
-            }
-
-            query_builder.push("lastname = ");
-            query_builder.push_bind(lastname);
-        }
+        update
+            .set_some("pronouns", dto.pronouns)
+            .set_some("username", dto.username);
 
-        if let Some(birthdate) = dto.birthdate {
-            if !is_using_dto {
-                query_builder.push(" SET ");
-                is_using_dto = true;
-            } else {
-                query_builder.push(",");
-            }
+        let Some(mut query_builder) = update.finish() else {
+            return Ok(is_updated);
+        };
 
-            query_builder.push("birthdate = ");
-            query_builder.push_bind(DateTime::parse_from_str(&birthdate, "%Y-%m-%d").unwrap());
-        }
+        query_builder.push(" WHERE id = ");
+        query_builder.push_bind(admin_id);
 
-        if let Some(gender) = dto.gender {
-            if !is_using_dto {
-                query_builder.push(" SET ");
-                is_using_dto = true;
-            } else {
-                query_builder.push(",");
-            }
+        query_builder.push(" AND role = 'admin' AND deleted_at IS NULL ");
 
-            query_builder.push("gender = ");
-            query_builder.push_bind(Gender::from(gender));
+        if let Some(versions) = expected_versions {
+            query_builder.push(" AND version = ANY(");
+            query_builder.push_bind(versions);
+            query_builder.push(")");
         }
 
-        if let Some(username) = dto.username {
-            if !is_using_dto {
-                query_builder.push(" SET ");
-            } else {
-                query_builder.push(",");
-            }
+        let result = query_builder.build().execute(&self.pool).await?;
 
-            query_builder.push("username = ");
-            query_builder.push_bind(username);
+        if result.rows_affected() > 0 {
+            is_updated = true;
         }
 
-        query_builder.push(" WHERE id = ");
-        query_builder.push_bind(admin_id);
+        Ok(is_updated)
+    }
 
-        query_builder.push(" AND role = 'admin' ");
+    async fn update_user_avatar(
+        &self,
+        user_id: Uuid,
+        avatar_key: Option<String>,
+        avatar_url: Option<String>,
+    ) -> Result<bool, sqlx::Error> {
+        let mut is_updated = false;
 
-        let result = query_builder.build().execute(&self.pool).await?;
+        let result = sqlx::query!(
+            "UPDATE people SET avatar_key = $2, avatar_url = $3 WHERE id = $1 AND role = 'user' AND deleted_at IS NULL",
+            user_id,
+            avatar_key,
+            avatar_url
+        )
+        .execute(&self.pool)
+        .await?;
 
         if result.rows_affected() > 0 {
             is_updated = true;
@@ -492,7 +538,7 @@ impl PersonExt for DBClient {
         let mut is_deleted = false;
 
         let result = sqlx::query!(
-            "DELETE FROM people WHERE id = $1 AND role = 'user'",
+            "UPDATE people SET deleted_at = NOW() WHERE id = $1 AND role = 'user' AND deleted_at IS NULL",
             user_id
         )
         .execute(&self.pool)
@@ -509,7 +555,7 @@ impl PersonExt for DBClient {
         let mut is_deleted = false;
 
         let result = sqlx::query!(
-            "DELETE FROM people WHERE id = $1 AND role = 'admin'",
+            "UPDATE people SET deleted_at = NOW() WHERE id = $1 AND role = 'admin' AND deleted_at IS NULL",
             admin_id
         )
         .execute(&self.pool)
@@ -521,4 +567,128 @@ impl PersonExt for DBClient {
 
         Ok(is_deleted)
     }
+
+    async fn get_deleted_users(&self) -> Result<Vec<User>, sqlx::Error> {
+        let people: Vec<Person> = sqlx::query_as(
+            r#"SELECT * FROM people WHERE role = 'user' AND deleted_at IS NOT NULL ORDER BY deleted_at DESC"#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(people.into_iter().map(User::from).collect())
+    }
+
+    async fn get_deleted_admins(&self) -> Result<Vec<Admin>, sqlx::Error> {
+        let people: Vec<Person> = sqlx::query_as(
+            r#"SELECT * FROM people WHERE role = 'admin' AND deleted_at IS NOT NULL ORDER BY deleted_at DESC"#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(people.into_iter().map(Admin::from).collect())
+    }
+
+    async fn restore_user(&self, user_id: Uuid, retention_days: i64) -> Result<bool, sqlx::Error> {
+        let mut is_restored = false;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE people
+                SET deleted_at = NULL
+                WHERE id = $1 AND role = 'user'
+                AND deleted_at > NOW() - make_interval(days => $2::int)
+        "#,
+        )
+        .bind(user_id)
+        .bind(retention_days)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            is_restored = true;
+        }
+
+        Ok(is_restored)
+    }
+
+    async fn restore_admin(
+        &self,
+        admin_id: Uuid,
+        retention_days: i64,
+    ) -> Result<bool, sqlx::Error> {
+        let mut is_restored = false;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE people
+                SET deleted_at = NULL
+                WHERE id = $1 AND role = 'admin'
+                AND deleted_at > NOW() - make_interval(days => $2::int)
+        "#,
+        )
+        .bind(admin_id)
+        .bind(retention_days)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            is_restored = true;
+        }
+
+        Ok(is_restored)
+    }
+
+    async fn purge_deleted_people(
+        &self,
+        retention_days: i64,
+        limit: i64,
+    ) -> Result<Vec<PurgedRow>, sqlx::Error> {
+        // Emails and comments go with the account through their cascading foreign keys.
+        let purged = sqlx::query_as(
+            r#"
+            DELETE FROM people
+                WHERE id IN (
+                    SELECT id FROM people
+                        WHERE deleted_at <= NOW() - make_interval(days => $1::int)
+                        LIMIT $2
+                        FOR UPDATE SKIP LOCKED
+                )
+                RETURNING id, ARRAY_REMOVE(ARRAY[avatar_key], NULL) AS storage_keys
+        "#,
+        )
+        .bind(retention_days)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(purged)
+    }
+}
+
+impl DBClient {
+    /// Turns people into users, loading the variants of their avatars in one query.
+    pub(super) async fn with_avatar_variants(
+        &self,
+        people: Vec<Person>,
+    ) -> Result<Vec<User>, sqlx::Error> {
+        let avatar_keys: Vec<String> = people
+            .iter()
+            .filter_map(|person| person.avatar_key.to_owned())
+            .collect();
+
+        let avatar_variants = self.get_image_variants(&avatar_keys).await?;
+
+        Ok(people
+            .into_iter()
+            .map(|mut person| {
+                person.avatar_variants = avatar_variants
+                    .iter()
+                    .filter(|variant| Some(&variant.source_key) == person.avatar_key.as_ref())
+                    .cloned()
+                    .collect();
+
+                User::from(person)
+            })
+            .collect())
+    }
 }
@@ -1,52 +1,205 @@
 use async_trait::async_trait;
-use sqlx::QueryBuilder;
+use sqlx::{PgConnection, QueryBuilder};
 use uuid::Uuid;
 
 use crate::{
     dtos::post::{CreatePostDto, SearchPostQueryDto, UpdatePostDto},
-    models::Post,
+    models::{Post, PostStatus, PurgedRow},
+    utils::{cursor::Cursor, markdown, slug::slugify},
 };
 
-use super::DBClient;
+use super::{
+    block::push_hidden_people_filter, tag::set_post_tags, update::UpdateBuilder, DBClient,
+};
+
+const MAX_SLUG_LENGTH: usize = 100;
 
 #[async_trait]
 pub trait PostExt {
     async fn get_post(&self, post_id: Uuid) -> Result<Option<Post>, sqlx::Error>;
 
-    async fn get_posts(&self, query: SearchPostQueryDto) -> Result<Vec<Post>, sqlx::Error>;
-
-    async fn save_post(&self, dto: CreatePostDto) -> Result<Post, sqlx::Error>;
-
-    async fn update_post(&self, post_id: Uuid, dto: UpdatePostDto) -> Result<bool, sqlx::Error>;
-
+    async fn get_post_by_slug(&self, slug: &str) -> Result<Option<Post>, sqlx::Error>;
+
+    /// Current slug of the post that used to be known as `slug`.
+    async fn get_redirected_slug(&self, slug: &str) -> Result<Option<String>, sqlx::Error>;
+
+    async fn get_posts(
+        &self,
+        query: SearchPostQueryDto,
+        viewer_id: Option<Uuid>,
+    ) -> Result<Vec<Post>, sqlx::Error>;
+
+    /// Published posts of the authors `viewer_id` follows, newest first,
+    /// starting after `after` when given. Blocked and muted authors are left out.
+    async fn get_feed(
+        &self,
+        viewer_id: Uuid,
+        after: Option<Cursor>,
+        limit: i64,
+    ) -> Result<Vec<Post>, sqlx::Error>;
+
+    async fn save_post(&self, author_id: Uuid, dto: CreatePostDto) -> Result<Post, sqlx::Error>;
+
+    /// Updates a post, keeping its previous title and description as a revision
+    /// attributed to `editor_id`. With `expected_versions` the post is only
+    /// updated while its version is one of them.
+    async fn update_post(
+        &self,
+        post_id: Uuid,
+        editor_id: Uuid,
+        dto: UpdatePostDto,
+        expected_versions: Option<Vec<i32>>,
+    ) -> Result<bool, sqlx::Error>;
+
+    /// Moves a post from `from` to `to`, returning `None` when the post is no
+    /// longer in the `from` state so concurrent transitions cannot both win.
+    async fn update_post_status(
+        &self,
+        post_id: Uuid,
+        from: PostStatus,
+        to: PostStatus,
+    ) -> Result<Option<Post>, sqlx::Error>;
+
+    /// Scheduled posts of one author, or of everyone when `author_id` is `None`.
+    async fn get_scheduled_posts(&self, author_id: Option<Uuid>) -> Result<Vec<Post>, sqlx::Error>;
+
+    /// Turns a scheduled post back into a plain draft.
+    async fn cancel_scheduled_post(&self, post_id: Uuid) -> Result<Option<Post>, sqlx::Error>;
+
+    /// Publishes up to `limit` posts whose scheduled time has passed.
+    ///
+    /// Rows are claimed with `FOR UPDATE SKIP LOCKED`, so several server
+    /// instances can run this at once without publishing a post twice.
+    async fn publish_due_posts(&self, limit: i64) -> Result<Vec<Post>, sqlx::Error>;
+
+    /// Moves a post to the trash, where it can be restored until it is purged.
     async fn delete_post(&self, post_id: Uuid) -> Result<bool, sqlx::Error>;
+
+    async fn get_deleted_post(&self, post_id: Uuid) -> Result<Option<Post>, sqlx::Error>;
+
+    /// Trashed posts of one author, or of everyone when `author_id` is `None`.
+    async fn get_deleted_posts(&self, author_id: Option<Uuid>) -> Result<Vec<Post>, sqlx::Error>;
+
+    /// Takes a post out of the trash, unless it was deleted more than
+    /// `retention_days` ago.
+    async fn restore_post(
+        &self,
+        post_id: Uuid,
+        retention_days: i64,
+    ) -> Result<Option<Post>, sqlx::Error>;
+
+    /// Hard-deletes up to `limit` posts that stayed in the trash longer than
+    /// `retention_days`, returning their ids and the storage keys of their
+    /// attachments.
+    async fn purge_deleted_posts(
+        &self,
+        retention_days: i64,
+        limit: i64,
+    ) -> Result<Vec<PurgedRow>, sqlx::Error>;
 }
 
 #[async_trait]
 impl PostExt for DBClient {
     async fn get_post(&self, post_id: Uuid) -> Result<Option<Post>, sqlx::Error> {
-        let post = sqlx::query_as!(Post, r#"SELECT * FROM posts WHERE id = $1"#, post_id)
+        let post = sqlx::query_as(r#"SELECT * FROM posts WHERE id = $1 AND deleted_at IS NULL"#)
+            .bind(post_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(post)
+    }
+
+    async fn get_post_by_slug(&self, slug: &str) -> Result<Option<Post>, sqlx::Error> {
+        let post = sqlx::query_as(r#"SELECT * FROM posts WHERE slug = $1 AND deleted_at IS NULL"#)
+            .bind(slug)
             .fetch_optional(&self.pool)
             .await?;
 
         Ok(post)
     }
 
-    async fn get_posts(&self, query: SearchPostQueryDto) -> Result<Vec<Post>, sqlx::Error> {
+    async fn get_redirected_slug(&self, slug: &str) -> Result<Option<String>, sqlx::Error> {
+        let slug = sqlx::query_scalar(
+            r#"
+            SELECT p.slug FROM post_slug_redirects r
+                INNER JOIN posts p ON p.id = r.post_id
+                WHERE r.slug = $1 AND p.deleted_at IS NULL
+        "#,
+        )
+        .bind(slug)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(slug)
+    }
+
+    async fn get_posts(
+        &self,
+        query: SearchPostQueryDto,
+        viewer_id: Option<Uuid>,
+    ) -> Result<Vec<Post>, sqlx::Error> {
         let page = query.page.unwrap_or(1);
         let limit = query.limit.unwrap_or(6);
         let offset: u32 = (page - 1) * limit as u32;
 
-        let mut query_builder = QueryBuilder::new(r#"SELECT * FROM posts"#);
+        let status = query.status.unwrap_or(PostStatus::Published);
+
+        let mut query_builder =
+            QueryBuilder::new(r#"SELECT * FROM posts WHERE deleted_at IS NULL AND status = "#);
+        query_builder.push_bind(status);
+
+        if status == PostStatus::Draft {
+            // Anonymous viewers have no drafts, a NULL author never matches.
+            query_builder.push(" AND author_id = ");
+            query_builder.push_bind(viewer_id);
+        }
+
+        push_hidden_people_filter(&mut query_builder, "posts.author_id", viewer_id);
 
         if let Some(title) = query.title {
-            query_builder.push(" WHERE ");
+            query_builder.push(" AND ");
 
             // TODO: create index on title lowercased to boost title search performance
             query_builder.push(" LOWER(title) LIKE ");
             query_builder.push_bind(format!("%{}%", title.to_lowercase()));
         }
 
+        // `tag` and `tags_any` both match posts having at least one of the tags.
+        let mut any_slugs: Vec<String> = vec![];
+
+        if let Some(tag) = query.tag.as_deref() {
+            any_slugs.extend(SearchPostQueryDto::tag_slugs(tag));
+        }
+
+        if let Some(tags_any) = query.tags_any.as_deref() {
+            any_slugs.extend(SearchPostQueryDto::tag_slugs(tags_any));
+        }
+
+        if query.tag.is_some() || query.tags_any.is_some() {
+            query_builder.push(
+                r#" AND EXISTS (
+                    SELECT 1 FROM post_tags pt
+                        INNER JOIN tags t ON t.id = pt.tag_id
+                        WHERE pt.post_id = posts.id AND t.slug = ANY("#,
+            );
+            query_builder.push_bind(any_slugs);
+            query_builder.push("))");
+        }
+
+        if let Some(tags_all) = query.tags_all.as_deref() {
+            let all_slugs = SearchPostQueryDto::tag_slugs(tags_all);
+
+            query_builder.push(
+                r#" AND (
+                    SELECT COUNT(*) FROM post_tags pt
+                        INNER JOIN tags t ON t.id = pt.tag_id
+                        WHERE pt.post_id = posts.id AND t.slug = ANY("#,
+            );
+            query_builder.push_bind(all_slugs.clone());
+            query_builder.push(")) = ");
+            query_builder.push_bind(all_slugs.len() as i64);
+        }
+
         query_builder.push(" OFFSET ");
         query_builder.push_bind(offset as i64);
 
@@ -58,65 +211,300 @@ impl PostExt for DBClient {
         Ok(posts)
     }
 
-    async fn save_post(&self, dto: CreatePostDto) -> Result<Post, sqlx::Error> {
-        let post = sqlx::query_as!(
-            Post,
-            r#"INSERT INTO posts (title, description) VALUES ($1, $2) RETURNING *"#,
-            dto.title,
-            dto.description,
+    async fn get_feed(
+        &self,
+        viewer_id: Uuid,
+        after: Option<Cursor>,
+        limit: i64,
+    ) -> Result<Vec<Post>, sqlx::Error> {
+        // Takes the newest `limit` posts of each followed author from
+        // `posts_author_id_published_at_idx` and merges them, so a page reads
+        // at most followees * limit index rows. Joining `posts` directly lets
+        // the planner walk every published post newest first until it finds
+        // enough by followed authors, which reads nearly all of them when
+        // those authors post less than everyone else. With 2,000 followees
+        // among 7,000 authors and 1.2M posts, EXPLAIN ANALYZE showed 1M rows
+        // and 4.6s for the join, against 42,000 rows and 0.2s for this.
+        let mut query_builder = QueryBuilder::new(
+            r#"
+            SELECT posts.* FROM follows
+                INNER JOIN people ON people.id = follows.followee_id AND people.deleted_at IS NULL
+                CROSS JOIN LATERAL (
+                    SELECT * FROM posts
+                        WHERE posts.author_id = follows.followee_id
+                        AND posts.status = 'published' AND posts.deleted_at IS NULL"#,
+        );
+
+        if let Some(cursor) = after {
+            query_builder.push(" AND (posts.published_at, posts.id) < (");
+            query_builder.push_bind(cursor.timestamp);
+            query_builder.push(", ");
+            query_builder.push_bind(cursor.id);
+            query_builder.push(")");
+        }
+
+        query_builder.push(" ORDER BY posts.published_at DESC, posts.id DESC LIMIT ");
+        query_builder.push_bind(limit);
+
+        query_builder.push(
+            r#"
+                ) AS posts
+                WHERE follows.status = 'accepted'
+                AND follows.follower_id = "#,
+        );
+        query_builder.push_bind(viewer_id);
+
+        push_hidden_people_filter(&mut query_builder, "follows.followee_id", Some(viewer_id));
+
+        query_builder.push(" ORDER BY posts.published_at DESC, posts.id DESC LIMIT ");
+        query_builder.push_bind(limit);
+
+        let posts = query_builder.build_query_as().fetch_all(&self.pool).await?;
+
+        Ok(posts)
+    }
+
+    async fn save_post(&self, author_id: Uuid, dto: CreatePostDto) -> Result<Post, sqlx::Error> {
+        let status = if dto.publish.unwrap_or(false) {
+            PostStatus::Published
+        } else {
+            PostStatus::Draft
+        };
+
+        let mut tx = self.pool.begin().await?;
+
+        let slug = unique_slug(&mut tx, &dto.title, None).await?;
+        let rendered = markdown::render(&dto.description);
+
+        let post: Post = sqlx::query_as(
+            r#"
+            INSERT INTO posts (title, slug, description, description_html, excerpt, reading_time, author_id, status, published_at, scheduled_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, CASE WHEN $8 = 'published'::post_status THEN NOW() END, $9)
+                RETURNING *
+        "#,
         )
-        .fetch_one(&self.pool)
+        .bind(dto.title)
+        .bind(slug)
+        .bind(dto.description)
+        .bind(rendered.html)
+        .bind(rendered.excerpt)
+        .bind(rendered.reading_time)
+        .bind(author_id)
+        .bind(status)
+        .bind(dto.scheduled_at)
+        .fetch_one(&mut *tx)
         .await?;
 
+        if let Some(tags) = dto.tags {
+            set_post_tags(&mut tx, post.id, &tags).await?;
+        }
+
+        tx.commit().await?;
+
         Ok(post)
     }
 
-    async fn update_post(&self, post_id: Uuid, dto: UpdatePostDto) -> Result<bool, sqlx::Error> {
+    async fn update_post(
+        &self,
+        post_id: Uuid,
+        editor_id: Uuid,
+        dto: UpdatePostDto,
+        expected_versions: Option<Vec<i32>>,
+    ) -> Result<bool, sqlx::Error> {
         let mut is_updated: bool = false;
 
-        let mut query_builder = QueryBuilder::new(r#"UPDATE posts"#);
+        let is_editing_content = dto.title.is_some() || dto.description.is_some();
 
-        let mut is_using_dto = false;
+        let mut tx = self.pool.begin().await?;
 
-        if let Some(title) = dto.title {
-            if !is_using_dto {
-                query_builder.push(" SET ");
-                is_using_dto = true;
-            }
+        let slug = match dto.title.as_deref() {
+            Some(title) => Some(unique_slug(&mut tx, title, Some(post_id)).await?),
+            None => None,
+        };
 
-            query_builder.push("title = ");
-            query_builder.push_bind(title);
-        }
+        let mut update = UpdateBuilder::new("posts");
+
+        update
+            .set_some("title", dto.title)
+            .set_some("slug", slug.clone());
 
         if let Some(description) = dto.description {
-            if !is_using_dto {
-                query_builder.push(" SET ");
-            } else {
-                query_builder.push(",");
-            }
+            let rendered = markdown::render(&description);
+
+            update
+                .set("description", description)
+                .set("description_html", rendered.html)
+                .set("excerpt", rendered.excerpt)
+                .set("reading_time", rendered.reading_time);
+        }
 
-            query_builder.push("description = ");
-            query_builder.push_bind(description);
+        // Retagging counts as an edit, so the row is touched to fire its triggers.
+        if dto.tags.is_some() {
+            update.touch("updated_at");
         }
 
+        let Some(mut query_builder) = update.finish() else {
+            return Ok(is_updated);
+        };
+
         query_builder.push(" WHERE id = ");
         query_builder.push_bind(post_id);
+        query_builder.push(" AND deleted_at IS NULL");
 
-        let result = query_builder.build().execute(&self.pool).await?;
+        if let Some(versions) = expected_versions {
+            query_builder.push(" AND version = ANY(");
+            query_builder.push_bind(versions);
+            query_builder.push(")");
+        }
 
-        if result.rows_affected() > 0 {
-            is_updated = true;
+        if let Some(slug) = &slug {
+            // The current slug keeps leading to the post once the title changes,
+            // and a slug the post had before is reclaimed from its redirects.
+            sqlx::query!(
+                r#"
+                INSERT INTO post_slug_redirects (slug, post_id)
+                    SELECT slug, id FROM posts WHERE id = $1 AND slug <> $2
+                    ON CONFLICT (slug) DO NOTHING
+                "#,
+                post_id,
+                slug,
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query!(
+                "DELETE FROM post_slug_redirects WHERE post_id = $1 AND slug = $2",
+                post_id,
+                slug,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        if is_editing_content {
+            // The row lock keeps concurrent edits from snapshotting the same version.
+            sqlx::query!(
+                r#"
+                INSERT INTO post_revisions (post_id, editor_id, title, description)
+                    SELECT id, $2, title, description FROM posts WHERE id = $1 FOR UPDATE
+                "#,
+                post_id,
+                editor_id,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let result = query_builder.build().execute(&mut *tx).await?;
+
+        // Dropping the transaction rolls back the revision and redirect of an update
+        // that did not happen.
+        if result.rows_affected() == 0 {
+            return Ok(is_updated);
+        }
+
+        is_updated = true;
+
+        if let Some(tags) = dto.tags {
+            set_post_tags(&mut tx, post_id, &tags).await?;
         }
 
+        tx.commit().await?;
+
         Ok(is_updated)
     }
 
+    async fn update_post_status(
+        &self,
+        post_id: Uuid,
+        from: PostStatus,
+        to: PostStatus,
+    ) -> Result<Option<Post>, sqlx::Error> {
+        // `published_at` keeps the first publication date when an archived post is republished.
+        let post = sqlx::query_as(
+            r#"
+            UPDATE posts
+                SET status = $3,
+                    published_at = CASE WHEN $3 = 'published'::post_status THEN COALESCE(published_at, NOW()) ELSE published_at END,
+                    scheduled_at = NULL
+                WHERE id = $1 AND status = $2 AND deleted_at IS NULL
+                RETURNING *
+        "#,
+        )
+        .bind(post_id)
+        .bind(from)
+        .bind(to)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(post)
+    }
+
+    async fn get_scheduled_posts(&self, author_id: Option<Uuid>) -> Result<Vec<Post>, sqlx::Error> {
+        let posts = sqlx::query_as(
+            r#"
+            SELECT * FROM posts
+                WHERE status = 'draft' AND scheduled_at IS NOT NULL AND deleted_at IS NULL
+                AND ($1::uuid IS NULL OR author_id = $1)
+                ORDER BY scheduled_at ASC
+        "#,
+        )
+        .bind(author_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(posts)
+    }
+
+    async fn cancel_scheduled_post(&self, post_id: Uuid) -> Result<Option<Post>, sqlx::Error> {
+        let post = sqlx::query_as(
+            r#"
+            UPDATE posts
+                SET scheduled_at = NULL
+                WHERE id = $1 AND status = 'draft' AND scheduled_at IS NOT NULL AND deleted_at IS NULL
+                RETURNING *
+        "#,
+        )
+        .bind(post_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(post)
+    }
+
+    async fn publish_due_posts(&self, limit: i64) -> Result<Vec<Post>, sqlx::Error> {
+        let posts = sqlx::query_as(
+            r#"
+            UPDATE posts
+                SET status = 'published',
+                    published_at = scheduled_at,
+                    scheduled_at = NULL
+                WHERE id IN (
+                    SELECT id FROM posts
+                        WHERE status = 'draft' AND scheduled_at <= NOW() AND deleted_at IS NULL
+                        ORDER BY scheduled_at ASC
+                        LIMIT $1
+                        FOR UPDATE SKIP LOCKED
+                )
+                RETURNING *
+        "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(posts)
+    }
+
     async fn delete_post(&self, post_id: Uuid) -> Result<bool, sqlx::Error> {
         let mut is_deleted = false;
 
-        let result = sqlx::query!("DELETE FROM posts WHERE id = $1", post_id)
-            .execute(&self.pool)
-            .await?;
+        let result = sqlx::query!(
+            "UPDATE posts SET deleted_at = NOW() WHERE id = $1 AND deleted_at IS NULL",
+            post_id
+        )
+        .execute(&self.pool)
+        .await?;
 
         if result.rows_affected() > 0 {
             is_deleted = true;
@@ -124,4 +512,133 @@ impl PostExt for DBClient {
 
         Ok(is_deleted)
     }
+
+    async fn get_deleted_post(&self, post_id: Uuid) -> Result<Option<Post>, sqlx::Error> {
+        let post =
+            sqlx::query_as(r#"SELECT * FROM posts WHERE id = $1 AND deleted_at IS NOT NULL"#)
+                .bind(post_id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(post)
+    }
+
+    async fn get_deleted_posts(&self, author_id: Option<Uuid>) -> Result<Vec<Post>, sqlx::Error> {
+        let posts = sqlx::query_as(
+            r#"
+            SELECT * FROM posts
+                WHERE deleted_at IS NOT NULL
+                AND ($1::uuid IS NULL OR author_id = $1)
+                ORDER BY deleted_at DESC
+        "#,
+        )
+        .bind(author_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(posts)
+    }
+
+    async fn restore_post(
+        &self,
+        post_id: Uuid,
+        retention_days: i64,
+    ) -> Result<Option<Post>, sqlx::Error> {
+        let post = sqlx::query_as(
+            r#"
+            UPDATE posts
+                SET deleted_at = NULL
+                WHERE id = $1 AND deleted_at > NOW() - make_interval(days => $2::int)
+                RETURNING *
+        "#,
+        )
+        .bind(post_id)
+        .bind(retention_days)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(post)
+    }
+
+    async fn purge_deleted_posts(
+        &self,
+        retention_days: i64,
+        limit: i64,
+    ) -> Result<Vec<PurgedRow>, sqlx::Error> {
+        // The outer query still sees the attachments the cascade removes, as all
+        // parts of a statement read the same snapshot.
+        let purged = sqlx::query_as(
+            r#"
+            WITH purged AS (
+                DELETE FROM posts
+                    WHERE id IN (
+                        SELECT id FROM posts
+                            WHERE deleted_at <= NOW() - make_interval(days => $1::int)
+                            LIMIT $2
+                            FOR UPDATE SKIP LOCKED
+                    )
+                    RETURNING id
+            )
+            SELECT p.id, COALESCE(ARRAY_AGG(a.storage_key) FILTER (WHERE a.id IS NOT NULL), '{}') AS storage_keys
+                FROM purged p
+                LEFT JOIN attachments a ON a.post_id = p.id
+                GROUP BY p.id
+        "#,
+        )
+        .bind(retention_days)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(purged)
+    }
+}
+
+/// Slug of `title` that no other post uses or redirects from, numbered with a
+/// `-2`, `-3`... suffix when the plain slug is taken.
+///
+/// Must run in the transaction that saves the slug: the lock taken here makes
+/// concurrent saves of the same title wait for each other until it commits.
+async fn unique_slug(
+    conn: &mut PgConnection,
+    title: &str,
+    post_id: Option<Uuid>,
+) -> Result<String, sqlx::Error> {
+    let mut base = slugify(title);
+    base.truncate(MAX_SLUG_LENGTH);
+    let base = match base.trim_end_matches('-') {
+        "" => String::from("post"),
+        base => base.to_string(),
+    };
+
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+        .bind(&base)
+        .execute(&mut *conn)
+        .await?;
+
+    // Slugs only hold `[a-z0-9-]`, so they never contain `LIKE` wildcards.
+    let taken: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT slug FROM posts
+            WHERE (slug = $1 OR slug LIKE $1 || '-%') AND id IS DISTINCT FROM $2
+        UNION
+        SELECT slug FROM post_slug_redirects
+            WHERE (slug = $1 OR slug LIKE $1 || '-%') AND post_id IS DISTINCT FROM $2
+    "#,
+    )
+    .bind(&base)
+    .bind(post_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    if !taken.contains(&base) {
+        return Ok(base);
+    }
+
+    let slug = (2..)
+        .map(|n| format!("{}-{}", base, n))
+        .find(|slug| !taken.contains(slug))
+        .unwrap_or(base);
+
+    Ok(slug)
 }
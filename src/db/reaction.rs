@@ -0,0 +1,108 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::models::{PostReactionCount, ReactionKind};
+
+use super::DBClient;
+
+#[async_trait]
+pub trait ReactionExt {
+    /// Aggregated reaction counts for every given post in a single query, so
+    /// listing posts does not cost one query per post.
+    async fn get_reaction_counts(
+        &self,
+        post_ids: &[Uuid],
+        viewer_id: Option<Uuid>,
+    ) -> Result<Vec<PostReactionCount>, sqlx::Error>;
+
+    async fn save_reaction(
+        &self,
+        post_id: Uuid,
+        person_id: Uuid,
+        kind: ReactionKind,
+    ) -> Result<bool, sqlx::Error>;
+
+    async fn delete_reaction(
+        &self,
+        post_id: Uuid,
+        person_id: Uuid,
+        kind: ReactionKind,
+    ) -> Result<bool, sqlx::Error>;
+}
+
+#[async_trait]
+impl ReactionExt for DBClient {
+    async fn get_reaction_counts(
+        &self,
+        post_ids: &[Uuid],
+        viewer_id: Option<Uuid>,
+    ) -> Result<Vec<PostReactionCount>, sqlx::Error> {
+        let counts = sqlx::query_as(
+            r#"
+            SELECT post_id, kind, COUNT(*) AS count, COALESCE(BOOL_OR(person_id = $2), false) AS viewer_reacted
+                FROM post_reactions
+                WHERE post_id = ANY($1)
+                GROUP BY post_id, kind
+                ORDER BY post_id, kind
+        "#,
+        )
+        .bind(post_ids)
+        .bind(viewer_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(counts)
+    }
+
+    async fn save_reaction(
+        &self,
+        post_id: Uuid,
+        person_id: Uuid,
+        kind: ReactionKind,
+    ) -> Result<bool, sqlx::Error> {
+        let mut is_saved = false;
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO post_reactions (post_id, person_id, kind)
+                VALUES ($1, $2, $3)
+                ON CONFLICT DO NOTHING
+        "#,
+        )
+        .bind(post_id)
+        .bind(person_id)
+        .bind(kind)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            is_saved = true;
+        }
+
+        Ok(is_saved)
+    }
+
+    async fn delete_reaction(
+        &self,
+        post_id: Uuid,
+        person_id: Uuid,
+        kind: ReactionKind,
+    ) -> Result<bool, sqlx::Error> {
+        let mut is_deleted = false;
+
+        let result = sqlx::query(
+            "DELETE FROM post_reactions WHERE post_id = $1 AND person_id = $2 AND kind = $3",
+        )
+        .bind(post_id)
+        .bind(person_id)
+        .bind(kind)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            is_deleted = true;
+        }
+
+        Ok(is_deleted)
+    }
+}
@@ -0,0 +1,40 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::models::PostRevision;
+
+use super::DBClient;
+
+#[async_trait]
+pub trait RevisionExt {
+    async fn get_revision(&self, revision_id: Uuid) -> Result<Option<PostRevision>, sqlx::Error>;
+
+    async fn get_revisions(&self, post_id: Uuid) -> Result<Vec<PostRevision>, sqlx::Error>;
+}
+
+#[async_trait]
+impl RevisionExt for DBClient {
+    async fn get_revision(&self, revision_id: Uuid) -> Result<Option<PostRevision>, sqlx::Error> {
+        let revision = sqlx::query_as!(
+            PostRevision,
+            r#"SELECT * FROM post_revisions WHERE id = $1"#,
+            revision_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(revision)
+    }
+
+    async fn get_revisions(&self, post_id: Uuid) -> Result<Vec<PostRevision>, sqlx::Error> {
+        let revisions = sqlx::query_as!(
+            PostRevision,
+            r#"SELECT * FROM post_revisions WHERE post_id = $1 ORDER BY created_at DESC, id DESC"#,
+            post_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(revisions)
+    }
+}
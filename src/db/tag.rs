@@ -0,0 +1,130 @@
+use async_trait::async_trait;
+use sqlx::{PgConnection, QueryBuilder};
+use uuid::Uuid;
+
+use crate::{
+    dtos::tag::SearchTagQueryDto,
+    models::{PostTag, Tag},
+    utils::slug::slugify,
+};
+
+use super::DBClient;
+
+#[async_trait]
+pub trait TagExt {
+    /// Tags with how many published posts use them. Drafts, archived and
+    /// deleted posts are not counted.
+    async fn get_tags(&self, query: SearchTagQueryDto) -> Result<Vec<Tag>, sqlx::Error>;
+
+    /// Tags of every given post in a single query, so listing posts does not
+    /// cost one query per post.
+    async fn get_posts_tags(&self, post_ids: &[Uuid]) -> Result<Vec<PostTag>, sqlx::Error>;
+}
+
+#[async_trait]
+impl TagExt for DBClient {
+    async fn get_tags(&self, query: SearchTagQueryDto) -> Result<Vec<Tag>, sqlx::Error> {
+        let page = query.page.unwrap_or(1);
+        let limit = query.limit.unwrap_or(20);
+        let offset: u32 = (page - 1) * limit as u32;
+
+        let mut query_builder = QueryBuilder::new(
+            r#"
+            SELECT t.*, COUNT(p.id) AS posts_count
+                FROM tags t
+                LEFT JOIN post_tags pt ON pt.tag_id = t.id
+                LEFT JOIN posts p ON p.id = pt.post_id
+                    AND p.status = 'published' AND p.deleted_at IS NULL
+        "#,
+        );
+
+        if let Some(name) = query.name {
+            query_builder.push(" WHERE t.slug LIKE ");
+            query_builder.push_bind(format!("%{}%", slugify(&name)));
+        }
+
+        query_builder.push(" GROUP BY t.id ORDER BY posts_count DESC, t.slug ASC ");
+
+        query_builder.push(" OFFSET ");
+        query_builder.push_bind(offset as i64);
+
+        query_builder.push(" LIMIT ");
+        query_builder.push_bind(limit as i64);
+
+        let tags = query_builder.build_query_as().fetch_all(&self.pool).await?;
+
+        Ok(tags)
+    }
+
+    async fn get_posts_tags(&self, post_ids: &[Uuid]) -> Result<Vec<PostTag>, sqlx::Error> {
+        let tags = sqlx::query_as(
+            r#"
+            SELECT pt.post_id, t.name, t.slug
+                FROM post_tags pt
+                INNER JOIN tags t ON t.id = pt.tag_id
+                WHERE pt.post_id = ANY($1)
+                ORDER BY t.slug
+        "#,
+        )
+        .bind(post_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tags)
+    }
+}
+
+/// Replaces the tags of a post, creating the tags that do not exist yet.
+///
+/// Tags are matched by slug, so `Rust` and ` rust ` end up as the same tag and
+/// keep the name they were first created with.
+pub(super) async fn set_post_tags(
+    conn: &mut PgConnection,
+    post_id: Uuid,
+    tags: &[String],
+) -> Result<(), sqlx::Error> {
+    let mut names: Vec<String> = vec![];
+    let mut slugs: Vec<String> = vec![];
+
+    for tag in tags {
+        let slug = slugify(tag);
+
+        if !slug.is_empty() && !slugs.contains(&slug) {
+            names.push(tag.trim().to_string());
+            slugs.push(slug);
+        }
+    }
+
+    sqlx::query!("DELETE FROM post_tags WHERE post_id = $1", post_id)
+        .execute(&mut *conn)
+        .await?;
+
+    if slugs.is_empty() {
+        return Ok(());
+    }
+
+    sqlx::query!(
+        r#"
+        INSERT INTO tags (name, slug)
+            SELECT * FROM UNNEST($1::text[], $2::text[])
+            ON CONFLICT (slug) DO NOTHING
+        "#,
+        &names,
+        &slugs,
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO post_tags (post_id, tag_id)
+            SELECT $1, id FROM tags WHERE slug = ANY($2)
+        "#,
+        post_id,
+        &slugs,
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
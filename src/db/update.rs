@@ -0,0 +1,64 @@
+use sqlx::{Encode, Postgres, QueryBuilder, Type};
+
+/// Builds the `SET` list of an `UPDATE` from the fields a client actually sent.
+pub struct UpdateBuilder<'args> {
+    query_builder: QueryBuilder<'args, Postgres>,
+    has_fields: bool,
+}
+
+impl<'args> UpdateBuilder<'args> {
+    pub fn new(table: &str) -> Self {
+        UpdateBuilder {
+            query_builder: QueryBuilder::new(format!("UPDATE {} SET ", table)),
+            has_fields: false,
+        }
+    }
+
+    pub fn set<T>(&mut self, column: &str, value: T) -> &mut Self
+    where
+        T: 'args + Encode<'args, Postgres> + Send + Type<Postgres>,
+    {
+        self.push_column(column);
+        self.query_builder.push_bind(value);
+        self
+    }
+
+    /// Sets the column only when the client sent a value for it.
+    pub fn set_some<T>(&mut self, column: &str, value: Option<T>) -> &mut Self
+    where
+        T: 'args + Encode<'args, Postgres> + Send + Type<Postgres>,
+    {
+        if let Some(value) = value {
+            self.set(column, value);
+        }
+
+        self
+    }
+
+    /// Sets the column to `NOW()`, which also counts as a change of the row.
+    pub fn touch(&mut self, column: &str) -> &mut Self {
+        self.push_column(column);
+        self.query_builder.push("NOW()");
+        self
+    }
+
+    /// Returns the query ready for its `WHERE` clause, or `None` when no field
+    /// was set since `UPDATE ... SET` without a column is not valid SQL.
+    pub fn finish(self) -> Option<QueryBuilder<'args, Postgres>> {
+        if !self.has_fields {
+            return None;
+        }
+
+        Some(self.query_builder)
+    }
+
+    fn push_column(&mut self, column: &str) {
+        if self.has_fields {
+            self.query_builder.push(", ");
+        }
+
+        self.query_builder.push(column);
+        self.query_builder.push(" = ");
+        self.has_fields = true;
+    }
+}
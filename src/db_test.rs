@@ -1,17 +1,80 @@
 #![cfg(test)]
+// The older tests compare booleans with `assert_eq!`.
+#![allow(clippy::bool_assert_comparison)]
 
+use std::collections::HashSet;
+
+use chrono::{Duration, Months, NaiveDate, Utc};
 use sqlx::{Pool, Postgres};
+use validator::{Validate, ValidateArgs};
 
 use super::*;
 use crate::{
+    config::ThumbnailSpec,
+    db::attachment::AttachmentExt,
+    db::block::BlockExt,
+    db::comment::CommentExt,
+    db::email::EmailExt,
+    db::follow::FollowExt,
+    db::image_variant::ImageVariantExt,
+    db::notification::NotificationExt,
     db::person::PersonExt,
     db::post::PostExt,
-    dtos::person::{CreateUserDto, UpdateUserDto},
+    db::reaction::ReactionExt,
+    db::revision::RevisionExt,
+    db::tag::TagExt,
+    dtos::person::{
+        validate_birthdate, CreateAdminDto, CreateUserDto, GetUserParamsDto, UpdateUserDto,
+        UpdateUserProfileStatusDto, UpdateUserPublicInfoDto,
+    },
     dtos::{
+        attachment::{variant_urls, CreateAttachmentDto},
+        comment::{
+            CommentSort, CreateCommentDto, GetCommentParamsDto, SearchCommentQueryDto,
+            UpdateCommentDto,
+        },
+        email::{
+            CreateEmailDto, GetEmailByIdParamsDto, GetEmailsByOwnerIdParamsDto,
+            UpdateEmailPrivacyDto,
+        },
+        follow::GetFollowerParamsDto,
+        notification::{NotificationDto, UpdateNotificationPreferencesDto},
         person::SearchUserQueryDto,
-        post::{CreatePostDto, SearchPostQueryDto, UpdatePostDto},
+        post::{
+            validate_scheduled_at, validate_tags, CreatePostDto, FeedQueryDto, GetPostParamsDto,
+            SearchPostQueryDto, UpdatePostDto,
+        },
+        revision::{GetRevisionParamsDto, RevisionDiffQueryDto},
+        tag::SearchTagQueryDto,
+    },
+    events::{local::LocalEventHub, Event, EventHub, EventId, Topic},
+    extractors::{
+        id_path::IdPath,
+        if_match::IfMatch,
+        upload::{clean_filename, sniff_content_type},
+    },
+    middlewares::{
+        logger::redact_token,
+        request_id::{RequestIdMiddleware, REQUEST_ID_HEADER},
+    },
+    models::{
+        FollowStatus, Gender, ImageVariantStatus, NotificationKind, NotificationPayload,
+        PostStatus, ReactionKind,
+    },
+    response::{DefaultHttpError, ErrorCode, ProblemDetails, PROBLEM_JSON_CONTENT_TYPE},
+    storage::{local::LocalStorage, s3::S3Storage, Storage},
+    tasks::thumbnails::{render_pending_variants, variant_key},
+    utils::{
+        cursor::Cursor,
+        diff::{diff_lines, DiffOp},
+        markdown,
+        slug::slugify,
+        test::{
+            authenticated, init_test_admin, init_test_posts, init_test_users, test_app_state,
+            test_app_state_with_events,
+        },
+        thumbnail,
     },
-    utils::test::{init_test_posts, init_test_users},
 };
 
 #[sqlx::test]
@@ -34,11 +97,15 @@ async fn test_get_all_posts(pool: Pool<Postgres>) {
     let db_client = DBClient::new(pool);
 
     let posts = db_client
-        .get_posts(SearchPostQueryDto {
-            limit: Some(6),
-            page: Some(1),
-            title: None,
-        })
+        .get_posts(
+            SearchPostQueryDto {
+                limit: Some(6),
+                page: Some(1),
+                title: None,
+                ..Default::default()
+            },
+            None,
+        )
         .await
         .unwrap();
 
@@ -51,11 +118,15 @@ async fn test_get_posts_with_title_search(pool: Pool<Postgres>) {
     let db_client = DBClient::new(pool);
 
     let posts = db_client
-        .get_posts(SearchPostQueryDto {
-            limit: Some(6),
-            page: Some(1),
-            title: Some("web".to_string()),
-        })
+        .get_posts(
+            SearchPostQueryDto {
+                limit: Some(6),
+                page: Some(1),
+                title: Some("web".to_string()),
+                ..Default::default()
+            },
+            None,
+        )
         .await
         .unwrap();
 
@@ -68,11 +139,15 @@ async fn test_get_posts_with_title_search_2(pool: Pool<Postgres>) {
     let db_client = DBClient::new(pool);
 
     let posts = db_client
-        .get_posts(SearchPostQueryDto {
-            limit: Some(6),
-            page: Some(1),
-            title: Some("ai".to_string()),
-        })
+        .get_posts(
+            SearchPostQueryDto {
+                limit: Some(6),
+                page: Some(1),
+                title: Some("ai".to_string()),
+                ..Default::default()
+            },
+            None,
+        )
         .await
         .unwrap();
 
@@ -81,14 +156,18 @@ async fn test_get_posts_with_title_search_2(pool: Pool<Postgres>) {
 
 #[sqlx::test]
 async fn test_save_post(pool: Pool<Postgres>) {
+    let (user_one, _, _, _) = init_test_users(&pool).await;
     let db_client = DBClient::new(pool);
 
     let dto = CreatePostDto {
         title: "New Post".to_string(),
         description: "New Post Description".to_string(),
+        tags: None,
+        publish: None,
+        scheduled_at: None,
     };
 
-    let new_post = db_client.save_post(dto.clone()).await.unwrap();
+    let new_post = db_client.save_post(user_one.id, dto.clone()).await.unwrap();
 
     assert_eq!(new_post.title, dto.title);
     assert_eq!(new_post.description, dto.description);
@@ -123,10 +202,11 @@ async fn test_update_post_title_only(pool: Pool<Postgres>) {
     let dto = UpdatePostDto {
         title: Some("New Title".to_string()),
         description: None,
+        tags: None,
     };
 
     let is_updated = db_client
-        .update_post(post_one.id, dto.clone())
+        .update_post(post_one.id, post_one.author_id.unwrap(), dto.clone(), None)
         .await
         .unwrap();
 
@@ -150,10 +230,11 @@ async fn test_update_post_description_only(pool: Pool<Postgres>) {
     let dto = UpdatePostDto {
         title: None,
         description: Some("New Description".to_string()),
+        tags: None,
     };
 
     let is_updated = db_client
-        .update_post(post_one.id, dto.clone())
+        .update_post(post_one.id, post_one.author_id.unwrap(), dto.clone(), None)
         .await
         .unwrap();
 
@@ -177,10 +258,11 @@ async fn test_update_post_title_and_desc(pool: Pool<Postgres>) {
     let dto = UpdatePostDto {
         title: Some("New Title".to_string()),
         description: Some("New Description".to_string()),
+        tags: None,
     };
 
     let is_updated = db_client
-        .update_post(post_one.id, dto.clone())
+        .update_post(post_one.id, post_one.author_id.unwrap(), dto.clone(), None)
         .await
         .unwrap();
 
@@ -225,6 +307,7 @@ async fn test_get_all_users_without_emails(pool: Pool<Postgres>) {
                 lastname: None,
             },
             false,
+            None,
         )
         .await
         .unwrap();
@@ -247,6 +330,7 @@ async fn test_get_all_users_with_emails(pool: Pool<Postgres>) {
                 lastname: None,
             },
             true,
+            None,
         )
         .await
         .unwrap();
@@ -270,6 +354,7 @@ async fn test_get_users_with_firstname_only(pool: Pool<Postgres>) {
                 username: None,
             },
             false,
+            None,
         )
         .await
         .unwrap();
@@ -292,6 +377,7 @@ async fn test_get_users_with_lastname_only(pool: Pool<Postgres>) {
                 username: None,
             },
             false,
+            None,
         )
         .await
         .unwrap();
@@ -314,6 +400,7 @@ async fn test_get_users_with_username_only(pool: Pool<Postgres>) {
                 lastname: None,
             },
             false,
+            None,
         )
         .await
         .unwrap();
@@ -336,6 +423,7 @@ async fn test_get_users_with_full_search(pool: Pool<Postgres>) {
                 lastname: Some("D".to_string()),
             },
             false,
+            None,
         )
         .await
         .unwrap();
@@ -354,8 +442,10 @@ async fn test_save_user(pool: Pool<Postgres>) {
         lastname: "User".to_string(),
         email: "new_user@example.com".to_string(),
         password: "abc12345".to_string(),
-        birthdate: "1999-10-10".to_string(),
-        gender: "male".to_string(),
+        birthdate: NaiveDate::from_ymd_opt(1999, 10, 10).unwrap(),
+        gender: Some(Gender::Male),
+        gender_description: None,
+        pronouns: None,
         is_profile_private: Some(false),
     };
 
@@ -364,8 +454,8 @@ async fn test_save_user(pool: Pool<Postgres>) {
     assert_eq!(new_user.username, dto.username);
     assert_eq!(new_user.firstname, dto.firstname);
     assert_eq!(new_user.lastname, dto.lastname);
-    assert_eq!(new_user.gender.to_string(), dto.gender);
-    assert_eq!(new_user.birthdate.to_string(), dto.birthdate);
+    assert_eq!(new_user.gender, dto.gender);
+    assert_eq!(new_user.birthdate, dto.birthdate);
     assert_eq!(new_user.is_profile_private, dto.is_profile_private.unwrap());
 }
 
@@ -380,23 +470,186 @@ async fn test_save_user_with_existent_username(pool: Pool<Postgres>) {
         lastname: "User".to_string(),
         email: "new_user@example.com".to_string(),
         password: "abc12345".to_string(),
-        birthdate: "1999-10-10".to_string(),
-        gender: "male".to_string(),
+        birthdate: NaiveDate::from_ymd_opt(1999, 10, 10).unwrap(),
+        gender: Some(Gender::Male),
+        gender_description: None,
+        pronouns: None,
         is_profile_private: Some(false),
     };
 
     let res = db_client.save_user(dto.clone()).await;
 
     match res {
-        Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
-            assert!(true);
-        }
-        _ => {
-            assert!(false, "Expected unique constraint violation error");
-        }
+        Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {}
+        _ => panic!("Expected unique constraint violation error"),
     }
 }
 
+#[sqlx::test]
+async fn test_save_user_with_existent_username_http_error(pool: Pool<Postgres>) {
+    init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let dto = CreateUserDto {
+        username: "alice_smith".to_string(),
+        firstname: "New".to_string(),
+        lastname: "User".to_string(),
+        email: "new_user@example.com".to_string(),
+        password: "abc12345".to_string(),
+        birthdate: NaiveDate::from_ymd_opt(1999, 10, 10).unwrap(),
+        gender: Some(Gender::Male),
+        gender_description: None,
+        pronouns: None,
+        is_profile_private: Some(false),
+    };
+
+    let err = DefaultHttpError::from(db_client.save_user(dto).await.unwrap_err());
+
+    assert_eq!(err.status, 409);
+    assert_eq!(err.code, ErrorCode::UsernameTaken);
+    assert_eq!(err.errors[0].field, "username");
+}
+
+#[test]
+fn test_validation_errors_http_error() {
+    let dto = CreateUserDto {
+        username: "".to_string(),
+        firstname: "New".to_string(),
+        lastname: "User".to_string(),
+        email: "not-an-email".to_string(),
+        password: "abc12345".to_string(),
+        birthdate: NaiveDate::from_ymd_opt(1999, 10, 10).unwrap(),
+        gender: Some(Gender::Male),
+        gender_description: None,
+        pronouns: None,
+        is_profile_private: Some(false),
+    };
+
+    let err = DefaultHttpError::from(dto.validate_args(13).unwrap_err());
+
+    assert_eq!(err.status, 400);
+    assert_eq!(err.code, ErrorCode::ValidationFailed);
+    assert_eq!(err.errors.len(), 2);
+    assert_eq!(err.errors[0].field, "email");
+    assert_eq!(err.errors[0].code, "email");
+    assert_eq!(err.errors[0].message, "Email is invalid");
+    assert_eq!(err.errors[1].field, "username");
+    assert_eq!(err.errors[1].code, "length");
+}
+
+#[actix_web::test]
+async fn test_request_id_middleware() {
+    let app = actix_web::test::init_service(
+        App::new()
+            .wrap(RequestIdMiddleware)
+            .route(
+                "/ok",
+                web::get().to(|| async { actix_web::HttpResponse::Ok().finish() }),
+            )
+            .route(
+                "/fail",
+                web::get().to(|| async {
+                    Err::<actix_web::HttpResponse, _>(
+                        DefaultHttpError::not_found("Post not found")
+                            .with_code(ErrorCode::PostNotFound),
+                    )
+                }),
+            ),
+    )
+    .await;
+
+    // A sane id sent by the client is echoed and added to error bodies.
+    let req = actix_web::test::TestRequest::get()
+        .uri("/fail")
+        .insert_header((REQUEST_ID_HEADER, "client-id_1.2"))
+        .to_request();
+    let res = actix_web::test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), 404);
+    assert_eq!(
+        res.headers().get(REQUEST_ID_HEADER).unwrap(),
+        "client-id_1.2"
+    );
+    assert_eq!(
+        res.headers().get("content-type").unwrap(),
+        PROBLEM_JSON_CONTENT_TYPE
+    );
+
+    let problem: ProblemDetails = actix_web::test::read_body_json(res).await;
+
+    assert_eq!(problem.status, 404);
+    assert_eq!(problem.code, ErrorCode::PostNotFound);
+    assert_eq!(problem.detail, "Post not found");
+    assert_eq!(problem.request_id.as_deref(), Some("client-id_1.2"));
+    assert_eq!(problem.instance.as_deref(), Some("/fail"));
+
+    // Anything else is replaced by a generated id.
+    let req = actix_web::test::TestRequest::get()
+        .uri("/fail")
+        .insert_header((REQUEST_ID_HEADER, "not a valid id!"))
+        .to_request();
+    let res = actix_web::test::call_service(&app, req).await;
+
+    let request_id = res
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .unwrap()
+        .to_str()
+        .unwrap();
+    assert!(uuid::Uuid::parse_str(request_id).is_ok());
+
+    let request_id = request_id.to_string();
+    let problem: ProblemDetails = actix_web::test::read_body_json(res).await;
+    assert_eq!(problem.request_id, Some(request_id));
+
+    // Successful responses get the header and keep their body.
+    let req = actix_web::test::TestRequest::get().uri("/ok").to_request();
+    let res = actix_web::test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), 200);
+    assert!(res.headers().contains_key(REQUEST_ID_HEADER));
+}
+
+#[actix_web::test]
+async fn test_malformed_id_http_error() {
+    let app = actix_web::test::init_service(App::new().route(
+        "/posts/{post_id}/comments/{comment_id}",
+        web::get().to(|path: IdPath<GetCommentParamsDto>| async move {
+            actix_web::HttpResponse::Ok().body(path.comment_id.to_string())
+        }),
+    ))
+    .await;
+
+    let post_id = uuid::Uuid::new_v4();
+
+    let req = actix_web::test::TestRequest::get()
+        .uri(&format!("/posts/{}/comments/not-a-uuid", post_id))
+        .to_request();
+    let res = actix_web::test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), 400);
+
+    let problem: ProblemDetails = actix_web::test::read_body_json(res).await;
+
+    assert_eq!(problem.code, ErrorCode::InvalidId);
+    assert_eq!(problem.errors.len(), 1);
+    assert_eq!(problem.errors[0].field, "comment_id");
+    assert_eq!(problem.errors[0].code, "uuid");
+
+    let comment_id = uuid::Uuid::new_v4();
+
+    let req = actix_web::test::TestRequest::get()
+        .uri(&format!("/posts/{}/comments/{}", post_id, comment_id))
+        .to_request();
+    let res = actix_web::test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), 200);
+    assert_eq!(
+        actix_web::test::read_body(res).await,
+        comment_id.to_string()
+    );
+}
+
 #[sqlx::test]
 async fn test_save_user_with_existent_email(pool: Pool<Postgres>) {
     init_test_users(&pool).await;
@@ -408,49 +661,208 @@ async fn test_save_user_with_existent_email(pool: Pool<Postgres>) {
         lastname: "User".to_string(),
         email: "alice@example.com".to_string(),
         password: "abc12345".to_string(),
-        birthdate: "1999-10-10".to_string(),
-        gender: "male".to_string(),
+        birthdate: NaiveDate::from_ymd_opt(1999, 10, 10).unwrap(),
+        gender: Some(Gender::Male),
+        gender_description: None,
+        pronouns: None,
         is_profile_private: Some(false),
     };
 
     let res = db_client.save_user(dto.clone()).await;
 
     match res {
-        Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
-            assert!(true);
-        }
-        _ => {
-            assert!(false, "Expected unique constraint violation error");
-        }
+        Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {}
+        _ => panic!("Expected unique constraint violation error"),
     }
 }
 
 #[sqlx::test]
-async fn test_save_user_with_wrong_gender(pool: Pool<Postgres>) {
-    init_test_users(&pool).await;
+async fn test_invalid_enum_value_http_error(pool: Pool<Postgres>) {
+    let err = sqlx::query("SELECT 'robot'::gender")
+        .execute(&pool)
+        .await
+        .unwrap_err();
+
+    let err = DefaultHttpError::from(err);
+
+    assert_eq!(err.status, 400);
+    assert_eq!(err.code, ErrorCode::InvalidValue);
+    assert_eq!(err.errors[0].field, "gender");
+
+    for (enum_name, field) in [
+        ("reaction_kind", "kind"),
+        ("post_status", "status"),
+        ("image_variant_status", "status"),
+        ("follow_status", "status"),
+        ("notification_kind", "kind"),
+    ] {
+        let err = sqlx::query(&format!("SELECT 'robot'::{}", enum_name))
+            .execute(&pool)
+            .await
+            .unwrap_err();
+
+        let err = DefaultHttpError::from(err);
+
+        assert_eq!(err.status, 400);
+        assert_eq!(err.errors[0].field, field);
+    }
+}
+
+#[test]
+fn test_create_user_dto_with_wrong_gender() {
+    let res = serde_json::from_str::<CreateUserDto>(
+        r#"{
+            "username": "new_username",
+            "firstname": "New",
+            "lastname": "User",
+            "email": "new_user@example.com",
+            "password": "abc12345",
+            "birthdate": "1999-10-10",
+            "gender": "maleee"
+        }"#,
+    );
+
+    assert!(res.is_err(), "Expected an error for gender enum");
+}
+
+#[test]
+fn test_create_user_dto_with_malformed_birthdate() {
+    let res = serde_json::from_str::<CreateUserDto>(
+        r#"{
+            "username": "new_username",
+            "firstname": "New",
+            "lastname": "User",
+            "email": "new_user@example.com",
+            "password": "abc12345",
+            "birthdate": "10/10/1999",
+            "gender": "male"
+        }"#,
+    );
+
+    assert!(res.is_err(), "Expected an error for birthdate format");
+}
+
+#[test]
+fn test_validate_birthdate_in_future() {
+    let tomorrow = Utc::now().date_naive() + Duration::days(1);
+
+    let res = validate_birthdate(&tomorrow, 0);
+
+    assert_eq!(res.unwrap_err().code, "future_date");
+}
+
+#[test]
+fn test_validate_birthdate_under_min_age() {
+    let today = Utc::now().date_naive();
+    // Months instead of years, so that Feb 29 becomes Feb 28 rather than failing.
+    let twelve_years_ago = today.checked_sub_months(Months::new(12 * 12)).unwrap();
+
+    assert_eq!(
+        validate_birthdate(&twelve_years_ago, 13).unwrap_err().code,
+        "min_age"
+    );
+    assert!(validate_birthdate(&twelve_years_ago, 12).is_ok());
+}
+
+#[sqlx::test]
+async fn test_save_user_with_self_described_gender(pool: Pool<Postgres>) {
     let db_client = DBClient::new(pool);
 
     let dto = CreateUserDto {
-        username: "new_username".to_string(),
+        username: "new_user123".to_string(),
         firstname: "New".to_string(),
         lastname: "User".to_string(),
         email: "new_user@example.com".to_string(),
         password: "abc12345".to_string(),
-        birthdate: "1999-10-10".to_string(),
-        gender: "maleee".to_string(),
+        birthdate: NaiveDate::from_ymd_opt(1999, 10, 10).unwrap(),
+        gender: Some(Gender::SelfDescribed),
+        gender_description: Some("Genderfluid".to_string()),
+        pronouns: Some("they/them".to_string()),
         is_profile_private: Some(false),
     };
 
-    let res = db_client.save_user(dto.clone()).await;
+    let new_user = db_client.save_user(dto.clone()).await.unwrap();
 
-    match res {
-        Err(sqlx::Error::Database(db_err)) if db_err.is_check_violation() => {
-            assert!(true);
-        }
-        _ => {
-            assert!(false, "Expected an error for gender enum");
-        }
-    }
+    assert_eq!(new_user.gender, dto.gender);
+    assert_eq!(new_user.gender_description, dto.gender_description);
+    assert_eq!(new_user.pronouns, dto.pronouns);
+}
+
+#[sqlx::test]
+async fn test_save_user_with_self_described_gender_without_description(pool: Pool<Postgres>) {
+    let db_client = DBClient::new(pool);
+
+    let dto = CreateUserDto {
+        username: "new_user123".to_string(),
+        firstname: "New".to_string(),
+        lastname: "User".to_string(),
+        email: "new_user@example.com".to_string(),
+        password: "abc12345".to_string(),
+        birthdate: NaiveDate::from_ymd_opt(1999, 10, 10).unwrap(),
+        gender: Some(Gender::SelfDescribed),
+        gender_description: None,
+        pronouns: None,
+        is_profile_private: Some(false),
+    };
+
+    let err = DefaultHttpError::from(db_client.save_user(dto).await.unwrap_err());
+
+    assert_eq!(err.status, 422);
+    assert_eq!(err.errors[0].field, "gender_description");
+}
+
+#[sqlx::test]
+async fn test_save_user_without_gender(pool: Pool<Postgres>) {
+    let db_client = DBClient::new(pool);
+
+    let dto = CreateUserDto {
+        username: "new_user123".to_string(),
+        firstname: "New".to_string(),
+        lastname: "User".to_string(),
+        email: "new_user@example.com".to_string(),
+        password: "abc12345".to_string(),
+        birthdate: NaiveDate::from_ymd_opt(1999, 10, 10).unwrap(),
+        gender: None,
+        gender_description: None,
+        pronouns: None,
+        is_profile_private: None,
+    };
+
+    let new_user = db_client.save_user(dto).await.unwrap();
+
+    assert_eq!(new_user.gender, None);
+}
+
+#[sqlx::test]
+async fn test_update_user_gender_clears_description(pool: Pool<Postgres>) {
+    let (user_one, _, _, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let dto = UpdateUserDto {
+        gender: Some(Gender::SelfDescribed),
+        gender_description: Some("Agender".to_string()),
+        ..Default::default()
+    };
+
+    db_client.update_user(user_one.id, dto, None).await.unwrap();
+
+    let dto = UpdateUserDto {
+        gender: Some(Gender::NonBinary),
+        ..Default::default()
+    };
+
+    let is_updated = db_client.update_user(user_one.id, dto, None).await.unwrap();
+
+    assert!(is_updated);
+
+    let updated_user = db_client
+        .get_user(user_one.id)
+        .await
+        .unwrap_or_else(|err| panic!("Failed to get user by id: {}", err))
+        .expect("User not found");
+
+    assert_eq!(updated_user.gender, Some(Gender::NonBinary));
+    assert_eq!(updated_user.gender_description, None);
 }
 
 #[sqlx::test]
@@ -484,13 +896,15 @@ async fn test_update_user_username_only(pool: Pool<Postgres>) {
         firstname: None,
         lastname: None,
         gender: None,
+        gender_description: None,
+        pronouns: None,
         biography: None,
         birthdate: None,
         is_profile_private: None,
     };
 
     let is_updated = db_client
-        .update_user(user_one.id, dto.clone())
+        .update_user(user_one.id, dto.clone(), None)
         .await
         .unwrap();
 
@@ -517,13 +931,15 @@ async fn test_update_user_firstname_only(pool: Pool<Postgres>) {
         username: None,
         lastname: None,
         gender: None,
+        gender_description: None,
+        pronouns: None,
         biography: None,
         birthdate: None,
         is_profile_private: None,
     };
 
     let is_updated = db_client
-        .update_user(user_one.id, dto.clone())
+        .update_user(user_one.id, dto.clone(), None)
         .await
         .unwrap();
 
@@ -550,13 +966,15 @@ async fn test_update_user_lastname_only(pool: Pool<Postgres>) {
         username: None,
         firstname: None,
         gender: None,
+        gender_description: None,
+        pronouns: None,
         biography: None,
         birthdate: None,
         is_profile_private: None,
     };
 
     let is_updated = db_client
-        .update_user(user_one.id, dto.clone())
+        .update_user(user_one.id, dto.clone(), None)
         .await
         .unwrap();
 
@@ -583,13 +1001,15 @@ async fn test_update_user_username_and_firstname(pool: Pool<Postgres>) {
         username: Some("new_username".to_string()),
         lastname: None,
         gender: None,
+        gender_description: None,
+        pronouns: None,
         biography: None,
         birthdate: None,
         is_profile_private: None,
     };
 
     let is_updated = db_client
-        .update_user(user_one.id, dto.clone())
+        .update_user(user_one.id, dto.clone(), None)
         .await
         .unwrap();
 
@@ -616,13 +1036,15 @@ async fn test_update_user_username_and_lastname(pool: Pool<Postgres>) {
         username: Some("new_username".to_string()),
         firstname: None,
         gender: None,
+        gender_description: None,
+        pronouns: None,
         biography: None,
         birthdate: None,
         is_profile_private: None,
     };
 
     let is_updated = db_client
-        .update_user(user_one.id, dto.clone())
+        .update_user(user_one.id, dto.clone(), None)
         .await
         .unwrap();
 
@@ -649,13 +1071,15 @@ async fn test_update_user_firstname_and_lastname(pool: Pool<Postgres>) {
         firstname: Some("New Firstname".to_string()),
         username: None,
         gender: None,
+        gender_description: None,
+        pronouns: None,
         biography: None,
         birthdate: None,
         is_profile_private: None,
     };
 
     let is_updated = db_client
-        .update_user(user_one.id, dto.clone())
+        .update_user(user_one.id, dto.clone(), None)
         .await
         .unwrap();
 
@@ -682,13 +1106,15 @@ async fn test_update_user_firstname_and_lastname_and_username(pool: Pool<Postgre
         firstname: Some("New Firstname".to_string()),
         username: Some("new_username".to_string()),
         gender: None,
+        gender_description: None,
+        pronouns: None,
         biography: None,
         birthdate: None,
         is_profile_private: None,
     };
 
     let is_updated = db_client
-        .update_user(user_one.id, dto.clone())
+        .update_user(user_one.id, dto.clone(), None)
         .await
         .unwrap();
 
@@ -715,19 +1141,3480 @@ async fn test_update_user_with_existent_username(pool: Pool<Postgres>) {
         firstname: None,
         lastname: None,
         gender: None,
+        gender_description: None,
+        pronouns: None,
         biography: None,
         birthdate: None,
         is_profile_private: None,
     };
 
-    let res = db_client.update_user(user_one.id, dto.clone()).await;
+    let res = db_client.update_user(user_one.id, dto.clone(), None).await;
 
     match res {
-        Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
-            assert!(true);
-        }
-        _ => {
-            assert!(false, "Expected unique constraint violation error");
+        Err(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {}
+        _ => panic!("Expected unique constraint violation error"),
+    }
+}
+
+#[sqlx::test]
+async fn test_save_comment(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let (user_one, _, _, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let comment = db_client
+        .save_comment(
+            post_one.id,
+            user_one.id,
+            CreateCommentDto {
+                body: "Great read!".to_string(),
+                parent_id: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(comment.post_id, post_one.id);
+    assert_eq!(comment.author_id, user_one.id);
+    assert_eq!(comment.parent_id, None);
+    assert_eq!(comment.body, "Great read!");
+}
+
+#[sqlx::test]
+async fn test_save_comment_reply(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let (user_one, user_two, _, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let comment = db_client
+        .save_comment(
+            post_one.id,
+            user_one.id,
+            CreateCommentDto {
+                body: "Great read!".to_string(),
+                parent_id: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    db_client
+        .save_comment(
+            post_one.id,
+            user_two.id,
+            CreateCommentDto {
+                body: "Agreed".to_string(),
+                parent_id: Some(comment.id),
+            },
+        )
+        .await
+        .unwrap();
+
+    let top_level = db_client
+        .get_comments(post_one.id, None, SearchCommentQueryDto::default(), None)
+        .await
+        .unwrap();
+
+    let replies = db_client
+        .get_comments(
+            post_one.id,
+            Some(comment.id),
+            SearchCommentQueryDto::default(),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(top_level.len(), 1);
+    assert_eq!(top_level[0].replies_count, 1);
+    assert_eq!(replies.len(), 1);
+    assert_eq!(replies[0].body, "Agreed");
+}
+
+#[sqlx::test]
+async fn test_save_comment_reply_to_another_post(pool: Pool<Postgres>) {
+    let (post_one, post_two, _, _, _) = init_test_posts(&pool).await;
+    let (user_one, _, _, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let comment = db_client
+        .save_comment(
+            post_one.id,
+            user_one.id,
+            CreateCommentDto {
+                body: "Great read!".to_string(),
+                parent_id: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let result = db_client
+        .save_comment(
+            post_two.id,
+            user_one.id,
+            CreateCommentDto {
+                body: "Wrong thread".to_string(),
+                parent_id: Some(comment.id),
+            },
+        )
+        .await;
+
+    match result {
+        Ok(_) => panic!("Expected the reply to be rejected"),
+        Err(e) => {
+            let error = DefaultHttpError::from(e);
+
+            assert_eq!(error.status, 422);
+            assert_eq!(error.code, ErrorCode::InvalidReference);
+            assert_eq!(error.errors[0].field, "parent_id");
         }
     }
 }
+
+#[sqlx::test]
+async fn test_get_comments_sorted(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let (user_one, _, _, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    for body in ["First", "Second", "Third"] {
+        db_client
+            .save_comment(
+                post_one.id,
+                user_one.id,
+                CreateCommentDto {
+                    body: body.to_string(),
+                    parent_id: None,
+                },
+            )
+            .await
+            .unwrap();
+    }
+
+    let oldest = db_client
+        .get_comments(
+            post_one.id,
+            None,
+            SearchCommentQueryDto {
+                sort: Some(CommentSort::Oldest),
+                page: Some(1),
+                limit: Some(2),
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let newest = db_client
+        .get_comments(
+            post_one.id,
+            None,
+            SearchCommentQueryDto {
+                sort: Some(CommentSort::Newest),
+                page: Some(1),
+                limit: Some(10),
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(oldest.len(), 2);
+    assert_eq!(oldest[0].body, "First");
+    assert_eq!(newest.len(), 3);
+    assert_eq!(newest[0].body, "Third");
+}
+
+#[sqlx::test]
+async fn test_update_comment(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let (user_one, _, _, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let comment = db_client
+        .save_comment(
+            post_one.id,
+            user_one.id,
+            CreateCommentDto {
+                body: "Great read!".to_string(),
+                parent_id: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let is_updated = db_client
+        .update_comment(
+            comment.id,
+            UpdateCommentDto {
+                body: "Great read, thanks!".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+
+    let comment = db_client.get_comment(comment.id).await.unwrap().unwrap();
+
+    assert!(is_updated);
+    assert_eq!(comment.body, "Great read, thanks!");
+}
+
+#[sqlx::test]
+async fn test_comments_of_hidden_posts_cannot_be_changed(pool: Pool<Postgres>) {
+    let (post_one, post_two, _, _, _) = init_test_posts(&pool).await;
+    let (alice, _, _, _) = init_test_users(&pool).await;
+    let app_state = test_app_state(&pool);
+    let author_id = post_one.author_id.unwrap();
+
+    let mut comments = vec![];
+
+    for post_id in [post_one.id, post_two.id] {
+        let comment = app_state
+            .db_client
+            .save_comment(
+                post_id,
+                alice.id,
+                CreateCommentDto {
+                    body: "Great read!".to_string(),
+                    parent_id: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        comments.push(comment);
+    }
+
+    let path = |comment: &models::Comment| {
+        IdPath(GetCommentParamsDto {
+            post_id: comment.post_id,
+            comment_id: comment.id,
+        })
+    };
+
+    // The post was trashed.
+    app_state.db_client.delete_post(post_one.id).await.unwrap();
+
+    let err = scopes::comments::update_comment(
+        app_state.clone(),
+        path(&comments[0]),
+        authenticated(&pool, alice.id).await,
+        web::Json(UpdateCommentDto {
+            body: "Great read, thanks!".to_string(),
+        }),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.status, 404);
+
+    // The author blocked the commenter.
+    app_state
+        .db_client
+        .save_block(author_id, alice.id)
+        .await
+        .unwrap();
+
+    let err = scopes::comments::delete_comment(
+        app_state.clone(),
+        path(&comments[1]),
+        authenticated(&pool, alice.id).await,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.status, 404);
+
+    for comment in &comments {
+        let saved = app_state
+            .db_client
+            .get_comment(comment.id)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(saved.body, "Great read!");
+    }
+}
+
+#[sqlx::test]
+async fn test_delete_comment_removes_replies(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let (user_one, user_two, _, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let comment = db_client
+        .save_comment(
+            post_one.id,
+            user_one.id,
+            CreateCommentDto {
+                body: "Great read!".to_string(),
+                parent_id: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let reply = db_client
+        .save_comment(
+            post_one.id,
+            user_two.id,
+            CreateCommentDto {
+                body: "Agreed".to_string(),
+                parent_id: Some(comment.id),
+            },
+        )
+        .await
+        .unwrap();
+
+    let is_deleted = db_client.delete_comment(comment.id).await.unwrap();
+    let reply = db_client.get_comment(reply.id).await.unwrap();
+
+    assert!(is_deleted);
+    assert!(reply.is_none());
+}
+
+#[sqlx::test]
+async fn test_save_reaction_is_idempotent(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let (user_one, _, _, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let first = db_client
+        .save_reaction(post_one.id, user_one.id, ReactionKind::Like)
+        .await
+        .unwrap();
+
+    let second = db_client
+        .save_reaction(post_one.id, user_one.id, ReactionKind::Like)
+        .await
+        .unwrap();
+
+    let counts = db_client
+        .get_reaction_counts(&[post_one.id], None)
+        .await
+        .unwrap();
+
+    assert!(first);
+    assert!(!second);
+    assert_eq!(counts.len(), 1);
+    assert_eq!(counts[0].count, 1);
+}
+
+#[sqlx::test]
+async fn test_get_reaction_counts_for_many_posts(pool: Pool<Postgres>) {
+    let (post_one, post_two, post_three, _, _) = init_test_posts(&pool).await;
+    let (user_one, user_two, _, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    for (post_id, person_id, kind) in [
+        (post_one.id, user_one.id, ReactionKind::Like),
+        (post_one.id, user_two.id, ReactionKind::Like),
+        (post_one.id, user_two.id, ReactionKind::Love),
+        (post_two.id, user_two.id, ReactionKind::Funny),
+    ] {
+        db_client
+            .save_reaction(post_id, person_id, kind)
+            .await
+            .unwrap();
+    }
+
+    let counts = db_client
+        .get_reaction_counts(
+            &[post_one.id, post_two.id, post_three.id],
+            Some(user_one.id),
+        )
+        .await
+        .unwrap();
+
+    let post_one_like = counts
+        .iter()
+        .find(|c| c.post_id == post_one.id && c.kind == ReactionKind::Like)
+        .unwrap();
+
+    let post_one_love = counts
+        .iter()
+        .find(|c| c.post_id == post_one.id && c.kind == ReactionKind::Love)
+        .unwrap();
+
+    assert_eq!(counts.len(), 3);
+    assert_eq!(post_one_like.count, 2);
+    assert!(post_one_like.viewer_reacted);
+    assert!(!post_one_love.viewer_reacted);
+    assert!(!counts.iter().any(|c| c.post_id == post_three.id));
+}
+
+#[sqlx::test]
+async fn test_delete_reaction(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let (user_one, _, _, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    db_client
+        .save_reaction(post_one.id, user_one.id, ReactionKind::Like)
+        .await
+        .unwrap();
+
+    let first = db_client
+        .delete_reaction(post_one.id, user_one.id, ReactionKind::Like)
+        .await
+        .unwrap();
+
+    let second = db_client
+        .delete_reaction(post_one.id, user_one.id, ReactionKind::Like)
+        .await
+        .unwrap();
+
+    let counts = db_client
+        .get_reaction_counts(&[post_one.id], None)
+        .await
+        .unwrap();
+
+    assert!(first);
+    assert!(!second);
+    assert!(counts.is_empty());
+}
+
+#[test]
+fn test_slugify() {
+    assert_eq!(slugify("  Machine Learning "), "machine-learning");
+    assert_eq!(slugify("C++ & Rust!"), "c-rust");
+    assert_eq!(slugify("---"), "");
+    assert_eq!(slugify("Crème Brûlée"), "creme-brulee");
+    assert_eq!(slugify("Привет, мир"), "privet-mir");
+}
+
+#[test]
+fn test_validate_tags() {
+    assert!(validate_tags(&["Rust".to_string(), "Web".to_string()]).is_ok());
+    assert!(validate_tags(&["!!!".to_string()]).is_err());
+    assert!(validate_tags(&["a".repeat(51)]).is_err());
+    assert!(validate_tags(&vec!["tag".to_string(); 11]).is_err());
+
+    let dto = UpdatePostDto {
+        tags: Some(vec!["!!!".to_string()]),
+        ..Default::default()
+    };
+
+    assert!(dto.validate().is_err());
+}
+
+#[test]
+fn test_update_post_dto_requires_a_title() {
+    let dto = UpdatePostDto {
+        title: Some(String::new()),
+        ..Default::default()
+    };
+    let err = DefaultHttpError::from(dto.validate().unwrap_err());
+
+    assert_eq!(err.status, 400);
+    assert_eq!(err.errors[0].field, "title");
+
+    let dto = UpdatePostDto {
+        title: Some("New Title".to_string()),
+        ..Default::default()
+    };
+
+    assert!(dto.validate().is_ok());
+}
+
+#[sqlx::test]
+async fn test_save_post_with_tags_normalizes_slugs(pool: Pool<Postgres>) {
+    let (user_one, _, _, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let post = db_client
+        .save_post(
+            user_one.id,
+            CreatePostDto {
+                title: "New Post".to_string(),
+                description: "New Post Description".to_string(),
+                tags: Some(vec![
+                    "Rust".to_string(),
+                    " rust ".to_string(),
+                    "Web Development".to_string(),
+                ]),
+                publish: None,
+                scheduled_at: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let tags = db_client.get_posts_tags(&[post.id]).await.unwrap();
+    let slugs: Vec<&str> = tags.iter().map(|t| t.slug.as_str()).collect();
+
+    assert_eq!(slugs, vec!["rust", "web-development"]);
+    assert_eq!(tags[0].name, "Rust");
+}
+
+#[sqlx::test]
+async fn test_update_post_replaces_tags(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let is_updated = db_client
+        .update_post(
+            post_one.id,
+            post_one.author_id.unwrap(),
+            UpdatePostDto {
+                title: None,
+                description: None,
+                tags: Some(vec!["Rust".to_string()]),
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let tags = db_client.get_posts_tags(&[post_one.id]).await.unwrap();
+
+    assert!(is_updated);
+    assert_eq!(tags.len(), 1);
+    assert_eq!(tags[0].slug, "rust");
+}
+
+#[sqlx::test]
+async fn test_get_posts_by_tag(pool: Pool<Postgres>) {
+    init_test_posts(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let posts = db_client
+        .get_posts(
+            SearchPostQueryDto {
+                tag: Some("web".to_string()),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(posts.len(), 2)
+}
+
+#[sqlx::test]
+async fn test_get_posts_by_tags_any(pool: Pool<Postgres>) {
+    init_test_posts(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let posts = db_client
+        .get_posts(
+            SearchPostQueryDto {
+                tags_any: Some("debugging,Machine Learning".to_string()),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(posts.len(), 2)
+}
+
+#[sqlx::test]
+async fn test_get_posts_by_tags_all(pool: Pool<Postgres>) {
+    init_test_posts(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let posts = db_client
+        .get_posts(
+            SearchPostQueryDto {
+                tags_all: Some("web,accessibility".to_string()),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(posts.len(), 1)
+}
+
+#[sqlx::test]
+async fn test_get_tags_with_usage_counts(pool: Pool<Postgres>) {
+    init_test_posts(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let tags = db_client
+        .get_tags(SearchTagQueryDto::default())
+        .await
+        .unwrap();
+
+    assert_eq!(tags.len(), 8);
+    assert_eq!(tags[0].slug, "programming");
+    assert_eq!(tags[0].posts_count, 2);
+    assert_eq!(tags[1].slug, "web");
+    assert_eq!(tags[1].posts_count, 2);
+}
+
+#[sqlx::test]
+async fn test_get_tags_only_counts_published_posts(pool: Pool<Postgres>) {
+    let (_, _, _, post_four, _) = init_test_posts(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    db_client
+        .save_post(
+            post_four.author_id.unwrap(),
+            CreatePostDto {
+                title: "Draft".to_string(),
+                description: "Draft Description".to_string(),
+                tags: Some(vec!["Programming".to_string(), "Secret".to_string()]),
+                publish: None,
+                scheduled_at: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    db_client.delete_post(post_four.id).await.unwrap();
+
+    let tags = db_client
+        .get_tags(SearchTagQueryDto::default())
+        .await
+        .unwrap();
+
+    let count = |slug: &str| {
+        tags.iter()
+            .find(|tag| tag.slug == slug)
+            .map(|tag| tag.posts_count)
+    };
+
+    assert_eq!(count("programming"), Some(2));
+    assert_eq!(count("web"), Some(1));
+    assert_eq!(count("architecture"), Some(0));
+    assert_eq!(count("secret"), Some(0));
+}
+
+#[test]
+fn test_post_status_transitions() {
+    assert!(PostStatus::Draft.can_transition_to(PostStatus::Published));
+    assert!(PostStatus::Published.can_transition_to(PostStatus::Archived));
+    assert!(PostStatus::Archived.can_transition_to(PostStatus::Published));
+    assert!(!PostStatus::Draft.can_transition_to(PostStatus::Archived));
+    assert!(!PostStatus::Published.can_transition_to(PostStatus::Draft));
+    assert!(!PostStatus::Published.can_transition_to(PostStatus::Published));
+}
+
+#[sqlx::test]
+async fn test_save_post_defaults_to_draft(pool: Pool<Postgres>) {
+    let (user_one, user_two, _, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let post = db_client
+        .save_post(
+            user_one.id,
+            CreatePostDto {
+                title: "Draft".to_string(),
+                description: "Draft Description".to_string(),
+                tags: None,
+                publish: None,
+                scheduled_at: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(post.status, PostStatus::Draft);
+    assert_eq!(post.author_id, Some(user_one.id));
+    assert!(post.published_at.is_none());
+    assert!(post.is_visible_to(Some(user_one.id)));
+    assert!(!post.is_visible_to(Some(user_two.id)));
+    assert!(!post.is_visible_to(None));
+}
+
+#[sqlx::test]
+async fn test_get_posts_hides_drafts(pool: Pool<Postgres>) {
+    init_test_posts(&pool).await;
+    let (user_one, user_two, _, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    db_client
+        .save_post(
+            user_one.id,
+            CreatePostDto {
+                title: "Draft".to_string(),
+                description: "Draft Description".to_string(),
+                tags: None,
+                publish: None,
+                scheduled_at: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let published = db_client
+        .get_posts(SearchPostQueryDto::default(), Some(user_one.id))
+        .await
+        .unwrap();
+
+    let drafts_query = SearchPostQueryDto {
+        status: Some(PostStatus::Draft),
+        ..Default::default()
+    };
+
+    let own_drafts = db_client
+        .get_posts(drafts_query.clone(), Some(user_one.id))
+        .await
+        .unwrap();
+
+    let other_drafts = db_client
+        .get_posts(drafts_query.clone(), Some(user_two.id))
+        .await
+        .unwrap();
+
+    let anonymous_drafts = db_client.get_posts(drafts_query, None).await.unwrap();
+
+    assert_eq!(published.len(), 5);
+    assert_eq!(own_drafts.len(), 1);
+    assert!(other_drafts.is_empty());
+    assert!(anonymous_drafts.is_empty());
+}
+
+#[sqlx::test]
+async fn test_update_post_status(pool: Pool<Postgres>) {
+    let (user_one, _, _, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let draft = db_client
+        .save_post(
+            user_one.id,
+            CreatePostDto {
+                title: "Draft".to_string(),
+                description: "Draft Description".to_string(),
+                tags: None,
+                publish: None,
+                scheduled_at: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let published = db_client
+        .update_post_status(draft.id, PostStatus::Draft, PostStatus::Published)
+        .await
+        .unwrap()
+        .expect("Post was not published");
+
+    let archived = db_client
+        .update_post_status(draft.id, PostStatus::Published, PostStatus::Archived)
+        .await
+        .unwrap()
+        .expect("Post was not archived");
+
+    // The post is no longer a draft, so a stale transition does nothing.
+    let stale = db_client
+        .update_post_status(draft.id, PostStatus::Draft, PostStatus::Published)
+        .await
+        .unwrap();
+
+    assert_eq!(published.status, PostStatus::Published);
+    assert!(published.published_at.is_some());
+    assert_eq!(archived.status, PostStatus::Archived);
+    assert_eq!(archived.published_at, published.published_at);
+    assert!(stale.is_none());
+}
+
+fn scheduled_post_dto(scheduled_at: chrono::DateTime<Utc>) -> CreatePostDto {
+    CreatePostDto {
+        title: "Scheduled".to_string(),
+        description: "Scheduled Description".to_string(),
+        tags: None,
+        publish: None,
+        scheduled_at: Some(scheduled_at),
+    }
+}
+
+#[test]
+fn test_validate_scheduled_at() {
+    assert!(validate_scheduled_at(&(Utc::now() + Duration::hours(1))).is_ok());
+    assert!(validate_scheduled_at(&(Utc::now() - Duration::hours(1))).is_err());
+}
+
+#[sqlx::test]
+async fn test_publish_due_posts(pool: Pool<Postgres>) {
+    let (user_one, _, _, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let due_at = Utc::now() - Duration::minutes(5);
+
+    let due = db_client
+        .save_post(user_one.id, scheduled_post_dto(due_at))
+        .await
+        .unwrap();
+
+    let later = db_client
+        .save_post(
+            user_one.id,
+            scheduled_post_dto(Utc::now() + Duration::hours(1)),
+        )
+        .await
+        .unwrap();
+
+    let published = db_client.publish_due_posts(10).await.unwrap();
+    let published_again = db_client.publish_due_posts(10).await.unwrap();
+    let later = db_client.get_post(later.id).await.unwrap().unwrap();
+
+    assert_eq!(published.len(), 1);
+    assert_eq!(published[0].id, due.id);
+    assert_eq!(published[0].status, PostStatus::Published);
+    assert_eq!(
+        published[0].published_at.map(|d| d.timestamp_micros()),
+        Some(due_at.timestamp_micros())
+    );
+    assert!(published[0].scheduled_at.is_none());
+    assert!(published_again.is_empty());
+    assert_eq!(later.status, PostStatus::Draft);
+}
+
+#[sqlx::test]
+async fn test_publish_due_posts_concurrently(pool: Pool<Postgres>) {
+    let (user_one, _, _, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    for _ in 0..5 {
+        db_client
+            .save_post(
+                user_one.id,
+                scheduled_post_dto(Utc::now() - Duration::minutes(1)),
+            )
+            .await
+            .unwrap();
+    }
+
+    let (first, second) = futures_util::join!(
+        db_client.publish_due_posts(10),
+        db_client.publish_due_posts(10)
+    );
+
+    assert_eq!(first.unwrap().len() + second.unwrap().len(), 5);
+}
+
+#[sqlx::test]
+async fn test_cancel_scheduled_post(pool: Pool<Postgres>) {
+    let (user_one, user_two, _, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let post = db_client
+        .save_post(
+            user_one.id,
+            scheduled_post_dto(Utc::now() + Duration::hours(1)),
+        )
+        .await
+        .unwrap();
+
+    let own = db_client
+        .get_scheduled_posts(Some(user_one.id))
+        .await
+        .unwrap();
+    let others = db_client
+        .get_scheduled_posts(Some(user_two.id))
+        .await
+        .unwrap();
+
+    let cancelled = db_client
+        .cancel_scheduled_post(post.id)
+        .await
+        .unwrap()
+        .expect("Post was not scheduled");
+
+    let cancelled_again = db_client.cancel_scheduled_post(post.id).await.unwrap();
+    let all = db_client.get_scheduled_posts(None).await.unwrap();
+
+    assert_eq!(own.len(), 1);
+    assert!(others.is_empty());
+    assert_eq!(cancelled.status, PostStatus::Draft);
+    assert!(cancelled.scheduled_at.is_none());
+    assert!(cancelled_again.is_none());
+    assert!(all.is_empty());
+}
+
+#[test]
+fn test_diff_lines() {
+    let changes = diff_lines("first\nsecond\n", "first\nthird\n");
+
+    assert_eq!(changes.len(), 3);
+    assert_eq!(changes[0].op, DiffOp::Equal);
+    assert_eq!(changes[0].value, "first\n");
+    assert_eq!(changes[1].op, DiffOp::Delete);
+    assert_eq!(changes[1].value, "second\n");
+    assert_eq!(changes[2].op, DiffOp::Insert);
+    assert_eq!(changes[2].value, "third\n");
+}
+
+#[sqlx::test]
+async fn test_update_post_saves_revision(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let (user_one, _, _, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    for title in ["Second Title", "Third Title"] {
+        db_client
+            .update_post(
+                post_one.id,
+                user_one.id,
+                UpdatePostDto {
+                    title: Some(title.to_string()),
+                    ..Default::default()
+                },
+                None,
+            )
+            .await
+            .unwrap();
+    }
+
+    let revisions = db_client.get_revisions(post_one.id).await.unwrap();
+
+    assert_eq!(revisions.len(), 2);
+    assert_eq!(revisions[0].title, "Second Title");
+    assert_eq!(revisions[1].title, post_one.title);
+    assert_eq!(revisions[1].description, post_one.description);
+    assert_eq!(revisions[1].editor_id, Some(user_one.id));
+}
+
+#[sqlx::test]
+async fn test_update_post_tags_only_skips_revision(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    db_client
+        .update_post(
+            post_one.id,
+            post_one.author_id.unwrap(),
+            UpdatePostDto {
+                tags: Some(vec!["Rust".to_string()]),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let revisions = db_client.get_revisions(post_one.id).await.unwrap();
+
+    assert!(revisions.is_empty());
+}
+
+#[sqlx::test]
+async fn test_get_revision(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    db_client
+        .update_post(
+            post_one.id,
+            post_one.author_id.unwrap(),
+            UpdatePostDto {
+                description: Some("New Description".to_string()),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let revisions = db_client.get_revisions(post_one.id).await.unwrap();
+
+    let revision = db_client
+        .get_revision(revisions[0].id)
+        .await
+        .unwrap()
+        .expect("Revision not found");
+
+    assert_eq!(revision.post_id, post_one.id);
+    assert_eq!(revision.description, post_one.description);
+}
+
+#[sqlx::test]
+async fn test_update_post_bumps_version(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let is_updated = db_client
+        .update_post(
+            post_one.id,
+            post_one.author_id.unwrap(),
+            UpdatePostDto {
+                title: Some("New Title".to_string()),
+                ..Default::default()
+            },
+            Some(vec![post_one.version]),
+        )
+        .await
+        .unwrap();
+
+    assert!(is_updated);
+
+    let updated_post = db_client
+        .get_post(post_one.id)
+        .await
+        .unwrap()
+        .expect("Post not found");
+
+    assert_eq!(updated_post.version, post_one.version + 1);
+}
+
+#[sqlx::test]
+async fn test_update_post_with_stale_version(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let is_updated = db_client
+        .update_post(
+            post_one.id,
+            post_one.author_id.unwrap(),
+            UpdatePostDto {
+                title: Some("New Title".to_string()),
+                ..Default::default()
+            },
+            Some(vec![post_one.version + 1]),
+        )
+        .await
+        .unwrap();
+
+    assert!(!is_updated);
+
+    let post = db_client
+        .get_post(post_one.id)
+        .await
+        .unwrap()
+        .expect("Post not found");
+    let revisions = db_client.get_revisions(post_one.id).await.unwrap();
+
+    assert_eq!(post.title, post_one.title);
+    assert_eq!(post.version, post_one.version);
+    assert!(revisions.is_empty());
+}
+
+#[sqlx::test]
+async fn test_update_user_with_stale_version(pool: Pool<Postgres>) {
+    let (user_one, _, _, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let dto = UpdateUserDto {
+        username: Some("new_username".to_string()),
+        firstname: None,
+        lastname: None,
+        gender: None,
+        gender_description: None,
+        pronouns: None,
+        biography: None,
+        birthdate: None,
+        is_profile_private: None,
+    };
+
+    let is_updated = db_client
+        .update_user(user_one.id, dto.clone(), Some(vec![user_one.version + 1]))
+        .await
+        .unwrap();
+
+    assert!(!is_updated);
+
+    let is_updated = db_client
+        .update_user(user_one.id, dto, Some(vec![user_one.version]))
+        .await
+        .unwrap();
+
+    assert!(is_updated);
+
+    let updated_user = db_client
+        .get_user(user_one.id)
+        .await
+        .unwrap()
+        .expect("User not found");
+
+    assert_eq!(updated_user.username, "new_username");
+    assert_eq!(updated_user.version, user_one.version + 1);
+}
+
+#[sqlx::test]
+async fn test_update_post_moves_updated_at(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    db_client
+        .update_post(
+            post_one.id,
+            post_one.author_id.unwrap(),
+            UpdatePostDto {
+                title: Some("New Title".to_string()),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let updated_post = db_client
+        .get_post(post_one.id)
+        .await
+        .unwrap()
+        .expect("Post not found");
+
+    assert!(updated_post.updated_at > post_one.updated_at);
+    assert_eq!(updated_post.created_at, post_one.created_at);
+}
+
+#[sqlx::test]
+async fn test_update_user_moves_updated_at(pool: Pool<Postgres>) {
+    let (user_one, _, _, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    db_client
+        .update_user(
+            user_one.id,
+            UpdateUserDto {
+                biography: Some("New biography".to_string()),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let updated_user = db_client
+        .get_user(user_one.id)
+        .await
+        .unwrap()
+        .expect("User not found");
+
+    assert!(updated_user.updated_at > user_one.updated_at);
+}
+
+#[test]
+fn test_empty_patch_body_is_rejected() {
+    let post_err = DefaultHttpError::from(UpdatePostDto::default().validate().unwrap_err());
+
+    assert_eq!(post_err.status, 400);
+    assert_eq!(post_err.code, ErrorCode::ValidationFailed);
+    assert_eq!(post_err.errors[0].field, "body");
+    assert_eq!(post_err.errors[0].code, "empty");
+
+    let user_err = DefaultHttpError::from(
+        UpdateUserPublicInfoDto::default()
+            .validate_args(18)
+            .unwrap_err(),
+    );
+
+    assert_eq!(user_err.status, 400);
+    assert_eq!(user_err.errors.len(), 1);
+    assert_eq!(user_err.errors[0].field, "body");
+    assert_eq!(user_err.errors[0].code, "empty");
+
+    let email_err =
+        DefaultHttpError::from(UpdateEmailPrivacyDto::default().validate().unwrap_err());
+
+    assert_eq!(email_err.errors[0].field, "body");
+    assert_eq!(email_err.errors[0].code, "empty");
+
+    let tags_only = UpdatePostDto {
+        tags: Some(vec![]),
+        ..Default::default()
+    };
+
+    assert!(tags_only.validate().is_ok());
+}
+
+#[sqlx::test]
+async fn test_db_update_with_no_fields_changes_nothing(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let (user_one, _, _, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let is_post_updated = db_client
+        .update_post(
+            post_one.id,
+            post_one.author_id.unwrap(),
+            UpdatePostDto::default(),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let is_user_updated = db_client
+        .update_user(user_one.id, UpdateUserDto::default(), None)
+        .await
+        .unwrap();
+
+    assert!(!is_post_updated);
+    assert!(!is_user_updated);
+}
+
+#[sqlx::test]
+async fn test_delete_post_requires_author_or_admin(pool: Pool<Postgres>) {
+    let (post_one, post_two, _, _, _) = init_test_posts(&pool).await;
+    let (alice, _, _, _) = init_test_users(&pool).await;
+    let admin = init_test_admin(&pool).await;
+    let app_state = test_app_state(&pool);
+    let author_id = post_one.author_id.unwrap();
+
+    let draft = app_state
+        .db_client
+        .save_post(
+            author_id,
+            CreatePostDto {
+                title: "Draft".to_string(),
+                description: "Draft Description".to_string(),
+                tags: None,
+                publish: None,
+                scheduled_at: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    let delete = |post_id, person_id| {
+        let app_state = app_state.clone();
+        let pool = pool.clone();
+
+        async move {
+            scopes::posts::delete_post(
+                app_state,
+                IdPath(GetPostParamsDto { post_id }),
+                authenticated(&pool, person_id).await,
+            )
+            .await
+        }
+    };
+
+    assert_eq!(delete(post_one.id, alice.id).await.unwrap_err().status, 403);
+    // Drafts of others don't exist as far as anyone else can tell.
+    assert_eq!(delete(draft.id, alice.id).await.unwrap_err().status, 404);
+
+    assert_eq!(delete(draft.id, author_id).await.unwrap().status(), 200);
+    assert_eq!(delete(post_two.id, admin.id).await.unwrap().status(), 200);
+
+    let trash = app_state.db_client.get_deleted_posts(None).await.unwrap();
+
+    assert_eq!(trash.len(), 2);
+}
+
+#[sqlx::test]
+async fn test_revision_routes_require_author_or_admin(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let (alice, _, _, _) = init_test_users(&pool).await;
+    let app_state = test_app_state(&pool);
+    let author_id = post_one.author_id.unwrap();
+
+    app_state
+        .db_client
+        .update_post(
+            post_one.id,
+            author_id,
+            UpdatePostDto {
+                title: Some("New Title".to_string()),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let revision = app_state
+        .db_client
+        .get_revisions(post_one.id)
+        .await
+        .unwrap()
+        .remove(0);
+    let post_path = || {
+        IdPath(GetPostParamsDto {
+            post_id: post_one.id,
+        })
+    };
+    let revision_path = || {
+        IdPath(GetRevisionParamsDto {
+            post_id: post_one.id,
+            revision_id: revision.id,
+        })
+    };
+
+    let err = scopes::revisions::get_revisions(
+        app_state.clone(),
+        post_path(),
+        authenticated(&pool, alice.id).await,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.status, 403);
+
+    let err = scopes::revisions::get_revisions_diff(
+        app_state.clone(),
+        post_path(),
+        web::Query(RevisionDiffQueryDto {
+            from: revision.id,
+            to: None,
+        }),
+        authenticated(&pool, alice.id).await,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.status, 403);
+
+    let res = scopes::revisions::get_revisions(
+        app_state.clone(),
+        post_path(),
+        authenticated(&pool, author_id).await,
+    )
+    .await
+    .unwrap();
+    assert_eq!(res.status(), 200);
+
+    let mut events = app_state.events.subscribe();
+
+    // A stale version is refused like any other edit.
+    let err = scopes::revisions::restore_revision(
+        app_state.clone(),
+        revision_path(),
+        authenticated(&pool, author_id).await,
+        IfMatch(Some(vec![post_one.version])),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.status, 412);
+
+    let post = app_state
+        .db_client
+        .get_post(post_one.id)
+        .await
+        .unwrap()
+        .unwrap();
+    let res = scopes::revisions::restore_revision(
+        app_state.clone(),
+        revision_path(),
+        authenticated(&pool, author_id).await,
+        IfMatch(Some(vec![post.version])),
+    )
+    .await
+    .unwrap();
+    assert_eq!(res.status(), 200);
+
+    let post = app_state
+        .db_client
+        .get_post(post_one.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(post.title, post_one.title);
+    assert_eq!(events.try_recv().unwrap().event, Event::post_updated(&post));
+}
+
+#[sqlx::test]
+async fn test_trash_routes_require_owner_or_admin(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let (alice, john, _, _) = init_test_users(&pool).await;
+    let admin = init_test_admin(&pool).await;
+    let app_state = test_app_state(&pool);
+    let author_id = post_one.author_id.unwrap();
+    let post_path = || {
+        IdPath(GetPostParamsDto {
+            post_id: post_one.id,
+        })
+    };
+    let user_path = || IdPath(GetUserParamsDto { user_id: alice.id });
+
+    scopes::posts::delete_post(
+        app_state.clone(),
+        post_path(),
+        authenticated(&pool, author_id).await,
+    )
+    .await
+    .unwrap();
+
+    let err = scopes::posts::restore_post(
+        app_state.clone(),
+        post_path(),
+        authenticated(&pool, john.id).await,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.status, 403);
+
+    scopes::posts::restore_post(
+        app_state.clone(),
+        post_path(),
+        authenticated(&pool, author_id).await,
+    )
+    .await
+    .unwrap();
+
+    scopes::users::delete_user(
+        app_state.clone(),
+        user_path(),
+        authenticated(&pool, alice.id).await,
+    )
+    .await
+    .unwrap();
+
+    let err = scopes::users::restore_user(
+        app_state.clone(),
+        user_path(),
+        authenticated(&pool, john.id).await,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.status, 403);
+
+    scopes::users::restore_user(
+        app_state.clone(),
+        user_path(),
+        authenticated(&pool, admin.id).await,
+    )
+    .await
+    .unwrap();
+
+    assert!(app_state
+        .db_client
+        .get_post(post_one.id)
+        .await
+        .unwrap()
+        .is_some());
+    assert!(app_state
+        .db_client
+        .get_user(alice.id)
+        .await
+        .unwrap()
+        .is_some());
+}
+
+#[sqlx::test]
+async fn test_deleted_post_goes_to_trash(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    db_client.delete_post(post_one.id).await.unwrap();
+
+    let post = db_client.get_post(post_one.id).await.unwrap();
+    let posts = db_client
+        .get_posts(SearchPostQueryDto::default(), None)
+        .await
+        .unwrap();
+    let trash = db_client.get_deleted_posts(None).await.unwrap();
+
+    assert!(post.is_none());
+    assert!(posts.iter().all(|p| p.id != post_one.id));
+    assert_eq!(trash.len(), 1);
+    assert_eq!(trash[0].id, post_one.id);
+    assert!(trash[0].deleted_at.is_some());
+
+    let restored = db_client
+        .restore_post(post_one.id, 30)
+        .await
+        .unwrap()
+        .expect("Post not restored");
+
+    assert!(restored.deleted_at.is_none());
+    assert!(db_client.get_post(post_one.id).await.unwrap().is_some());
+}
+
+#[sqlx::test]
+async fn test_restore_and_purge_after_retention(pool: Pool<Postgres>) {
+    let (post_one, post_two, _, _, _) = init_test_posts(&pool).await;
+    let db_client = DBClient::new(pool.clone());
+
+    db_client.delete_post(post_one.id).await.unwrap();
+    db_client.delete_post(post_two.id).await.unwrap();
+
+    sqlx::query("UPDATE posts SET deleted_at = NOW() - INTERVAL '31 days' WHERE id = $1")
+        .bind(post_one.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let restored = db_client.restore_post(post_one.id, 30).await.unwrap();
+
+    assert!(restored.is_none());
+
+    let purged = db_client.purge_deleted_posts(30, 10).await.unwrap();
+
+    assert_eq!(purged.len(), 1);
+    assert_eq!(purged[0].id, post_one.id);
+    assert!(db_client
+        .get_deleted_post(post_one.id)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(db_client
+        .get_deleted_post(post_two.id)
+        .await
+        .unwrap()
+        .is_some());
+}
+
+#[sqlx::test]
+async fn test_deleted_user_keeps_emails_until_purged(pool: Pool<Postgres>) {
+    let (user_one, _, _, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool.clone());
+    let email = db_client.get_person_emails(user_one.id).await.unwrap()[0].clone();
+
+    db_client.delete_user(user_one.id).await.unwrap();
+
+    let person = db_client
+        .get_person_by_username(&user_one.username)
+        .await
+        .unwrap();
+    let trash = db_client.get_deleted_users().await.unwrap();
+
+    // Kept, but hidden like their owner.
+    assert!(db_client
+        .get_person_emails(user_one.id)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(db_client.get_email_by_id(email.id).await.unwrap().is_none());
+
+    let emails: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM emails WHERE owner_id = $1")
+        .bind(user_one.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+    assert!(person.is_none());
+    assert_eq!(trash[0].id, user_one.id);
+    assert!(emails > 0);
+
+    assert!(db_client.restore_user(user_one.id, 30).await.unwrap());
+    assert!(db_client.get_email_by_id(email.id).await.unwrap().is_some());
+    assert!(db_client.delete_user(user_one.id).await.unwrap());
+
+    sqlx::query("UPDATE people SET deleted_at = NOW() - INTERVAL '31 days' WHERE id = $1")
+        .bind(user_one.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let purged = db_client.purge_deleted_people(30, 10).await.unwrap();
+    let emails: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM emails WHERE owner_id = $1")
+        .bind(user_one.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+    assert_eq!(purged.len(), 1);
+    assert_eq!(purged[0].id, user_one.id);
+    assert_eq!(emails, 0);
+}
+
+#[sqlx::test]
+async fn test_save_post_generates_unique_slugs(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    assert_eq!(
+        post_one.slug,
+        "mastering-data-structures-and-algorithms-a-comprehensive-guide-for-programmers"
+    );
+
+    let dto = CreatePostDto {
+        title: "Crème Brûlée".to_string(),
+        description: "A classic".to_string(),
+        ..Default::default()
+    };
+
+    let first = db_client
+        .save_post(post_one.author_id.unwrap(), dto.clone())
+        .await
+        .unwrap();
+    let second = db_client
+        .save_post(post_one.author_id.unwrap(), dto)
+        .await
+        .unwrap();
+
+    assert_eq!(first.slug, "creme-brulee");
+    assert_eq!(second.slug, "creme-brulee-2");
+}
+
+#[sqlx::test]
+async fn test_concurrent_saves_get_unique_slugs(pool: Pool<Postgres>) {
+    let (alice, _, _, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+    let dto = CreatePostDto {
+        title: "Same Title".to_string(),
+        description: "Saved at the same time".to_string(),
+        ..Default::default()
+    };
+
+    let saves = (0..8).map(|_| db_client.save_post(alice.id, dto.clone()));
+    let mut slugs = futures_util::future::join_all(saves)
+        .await
+        .into_iter()
+        .map(|post| post.unwrap().slug)
+        .collect::<Vec<_>>();
+    slugs.sort();
+
+    let mut expected = vec!["same-title".to_string()];
+    expected.extend((2..=8).map(|n| format!("same-title-{}", n)));
+    expected.sort();
+
+    assert_eq!(slugs, expected);
+}
+
+#[sqlx::test]
+async fn test_update_post_title_keeps_old_slug(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let rename = |title: &str| UpdatePostDto {
+        title: Some(title.to_string()),
+        ..Default::default()
+    };
+
+    db_client
+        .update_post(
+            post_one.id,
+            post_one.author_id.unwrap(),
+            rename("Data Structures"),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let renamed = db_client
+        .get_post_by_slug("data-structures")
+        .await
+        .unwrap()
+        .expect("Post not found by its new slug");
+    let redirected = db_client.get_redirected_slug(&post_one.slug).await.unwrap();
+
+    assert_eq!(renamed.id, post_one.id);
+    assert!(db_client
+        .get_post_by_slug(&post_one.slug)
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(redirected.as_deref(), Some("data-structures"));
+
+    // The old slug stays reserved for the redirect.
+    let other = db_client
+        .save_post(
+            post_one.author_id.unwrap(),
+            CreatePostDto {
+                title: post_one.title.clone(),
+                description: "Another post".to_string(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(other.slug, format!("{}-2", post_one.slug));
+
+    // Going back to the old title reclaims the old slug.
+    db_client
+        .update_post(
+            post_one.id,
+            post_one.author_id.unwrap(),
+            rename(&post_one.title),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let restored = db_client
+        .get_post(post_one.id)
+        .await
+        .unwrap()
+        .expect("Post not found");
+
+    assert_eq!(restored.slug, post_one.slug);
+    assert_eq!(
+        db_client
+            .get_redirected_slug("data-structures")
+            .await
+            .unwrap()
+            .as_deref(),
+        Some(post_one.slug.as_str())
+    );
+}
+
+#[test]
+fn test_render_markdown() {
+    let rendered = markdown::render(
+        "# Title\n\nSome *emphasis* and [a link](javascript:alert(1)).\n\n<script>alert(1)</script>",
+    );
+
+    assert!(rendered.html.contains("<h1>Title</h1>"));
+    assert!(rendered.html.contains("<em>emphasis</em>"));
+    assert!(!rendered.html.contains("<script>"));
+    assert!(!rendered.html.contains("javascript:"));
+    assert_eq!(rendered.excerpt, "Title Some emphasis and a link.");
+    assert_eq!(rendered.reading_time, 1);
+
+    let long = markdown::render(&"word ".repeat(450));
+
+    assert_eq!(long.reading_time, 3);
+    assert!(long.excerpt.ends_with('…'));
+    assert!(long.excerpt.chars().count() <= markdown::EXCERPT_LENGTH + 1);
+}
+
+#[sqlx::test]
+async fn test_save_post_renders_markdown(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let post = db_client
+        .save_post(
+            post_one.author_id.unwrap(),
+            CreatePostDto {
+                title: "Markdown".to_string(),
+                description: "Hello **world**".to_string(),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        post.description_html,
+        "<p>Hello <strong>world</strong></p>\n"
+    );
+    assert_eq!(post.excerpt, "Hello world");
+
+    db_client
+        .update_post(
+            post.id,
+            post_one.author_id.unwrap(),
+            UpdatePostDto {
+                description: Some("_Updated_".to_string()),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let updated_post = db_client
+        .get_post(post.id)
+        .await
+        .unwrap()
+        .expect("Post not found");
+
+    assert_eq!(updated_post.description_html, "<p><em>Updated</em></p>\n");
+    assert_eq!(updated_post.excerpt, "Updated");
+}
+
+const PNG_BYTES: &[u8] = &[
+    0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48, 0x44, 0x52,
+];
+
+fn test_attachment(storage_key: &str) -> CreateAttachmentDto {
+    CreateAttachmentDto {
+        storage_key: storage_key.to_string(),
+        url: format!("http://localhost:5000/media/{}", storage_key),
+        filename: "diagram.png".to_string(),
+        content_type: "image/png".to_string(),
+        size: PNG_BYTES.len() as i64,
+    }
+}
+
+#[test]
+fn test_sniff_content_type() {
+    assert_eq!(sniff_content_type(PNG_BYTES), Some(("image/png", "png")));
+    assert_eq!(
+        sniff_content_type(b"%PDF-1.7\n"),
+        Some(("application/pdf", "pdf"))
+    );
+
+    // Types are never taken from the client, and unlisted ones are refused.
+    assert_eq!(
+        sniff_content_type(b"<svg xmlns='http://www.w3.org/2000/svg'/>"),
+        None
+    );
+    assert_eq!(sniff_content_type(b"MZ\x90\x00\x03\x00\x00\x00"), None);
+    assert_eq!(sniff_content_type(b"plain text"), None);
+}
+
+#[test]
+fn test_clean_filename() {
+    assert_eq!(clean_filename("diagram.png"), "diagram.png");
+    assert_eq!(clean_filename("../../etc/passwd"), "passwd");
+    assert_eq!(clean_filename("C:\\Users\\me\\photo.jpg"), "photo.jpg");
+    assert_eq!(clean_filename("  "), "upload");
+    assert_eq!(clean_filename(&"a".repeat(300)).len(), 255);
+}
+
+#[actix_web::test]
+async fn test_local_storage_put_get_and_delete() {
+    let root = std::env::temp_dir().join(format!("storage-{}", uuid::Uuid::new_v4()));
+    let storage = LocalStorage::with_root(&root, "http://localhost:5000/media/");
+
+    storage
+        .put("posts/1/file.png", PNG_BYTES.into(), "image/png")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        std::fs::read(root.join("posts/1/file.png")).unwrap(),
+        PNG_BYTES
+    );
+    assert_eq!(storage.get("posts/1/file.png").await.unwrap(), PNG_BYTES);
+    assert_eq!(
+        storage.url("posts/1/file.png"),
+        "http://localhost:5000/media/posts/1/file.png"
+    );
+
+    storage.delete("posts/1/file.png").await.unwrap();
+    // Deleting twice is not an error.
+    storage.delete("posts/1/file.png").await.unwrap();
+
+    assert!(!root.join("posts/1/file.png").exists());
+
+    std::fs::remove_dir_all(root).unwrap();
+}
+
+type FakeBucket = std::sync::Mutex<std::collections::HashMap<String, (String, Vec<u8>)>>;
+
+// Just enough of the S3 API for single part uploads, downloads and deletes.
+async fn fake_s3(
+    req: actix_web::HttpRequest,
+    body: actix_web::web::Bytes,
+    bucket: actix_web::web::Data<FakeBucket>,
+) -> actix_web::HttpResponse {
+    let key = req.path().to_string();
+
+    match *req.method() {
+        actix_web::http::Method::PUT => {
+            let content_type = req
+                .headers()
+                .get("content-type")
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+                .to_string();
+
+            bucket
+                .lock()
+                .unwrap()
+                .insert(key, (content_type, body.to_vec()));
+
+            actix_web::HttpResponse::Ok()
+                .insert_header(("ETag", "\"1\""))
+                .finish()
+        }
+        actix_web::http::Method::GET => match bucket.lock().unwrap().get(&key) {
+            Some((content_type, bytes)) => actix_web::HttpResponse::Ok()
+                .content_type(content_type.as_str())
+                .insert_header(("ETag", "\"1\""))
+                .insert_header(("Last-Modified", "Mon, 19 Oct 2026 00:00:00 GMT"))
+                .body(bytes.clone()),
+            None => actix_web::HttpResponse::NotFound().finish(),
+        },
+        actix_web::http::Method::DELETE => {
+            bucket.lock().unwrap().remove(&key);
+
+            actix_web::HttpResponse::NoContent().finish()
+        }
+        _ => actix_web::HttpResponse::MethodNotAllowed().finish(),
+    }
+}
+
+#[actix_web::test]
+async fn test_s3_storage_against_fake_bucket() {
+    let bucket = actix_web::web::Data::new(FakeBucket::default());
+    let server_bucket = bucket.clone();
+
+    let server = actix_web::HttpServer::new(move || {
+        actix_web::App::new()
+            .app_data(server_bucket.clone())
+            .default_service(actix_web::web::to(fake_s3))
+    })
+    .workers(1)
+    .bind(("127.0.0.1", 0))
+    .unwrap();
+    let endpoint = format!("http://127.0.0.1:{}", server.addrs()[0].port());
+    actix_web::rt::spawn(server.run());
+
+    let storage = S3Storage::with_builder(
+        object_store::aws::AmazonS3Builder::new()
+            .with_endpoint(&endpoint)
+            .with_allow_http(true)
+            .with_bucket_name("media")
+            .with_region("us-east-1")
+            .with_access_key_id("test")
+            .with_secret_access_key("test"),
+        "https://cdn.example.com",
+    );
+
+    storage
+        .put("avatars/1/face.png", PNG_BYTES.into(), "image/png")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        bucket.lock().unwrap().get("/media/avatars/1/face.png"),
+        Some(&("image/png".to_string(), PNG_BYTES.to_vec()))
+    );
+    assert_eq!(storage.get("avatars/1/face.png").await.unwrap(), PNG_BYTES);
+    assert_eq!(
+        storage.url("avatars/1/face.png"),
+        "https://cdn.example.com/avatars/1/face.png"
+    );
+
+    storage.delete("avatars/1/face.png").await.unwrap();
+
+    assert!(bucket.lock().unwrap().is_empty());
+}
+
+#[sqlx::test]
+async fn test_save_and_delete_attachments(pool: Pool<Postgres>) {
+    let (post_one, post_two, _, _, _) = init_test_posts(&pool).await;
+    let db_client = DBClient::new(pool);
+    let author_id = post_one.author_id.unwrap();
+
+    let first = db_client
+        .save_attachment(post_one.id, author_id, test_attachment("posts/1/a.png"))
+        .await
+        .unwrap();
+    db_client
+        .save_attachment(post_two.id, author_id, test_attachment("posts/2/b.png"))
+        .await
+        .unwrap();
+
+    let attachments = db_client
+        .get_posts_attachments(&[post_one.id, post_two.id])
+        .await
+        .unwrap();
+
+    assert_eq!(attachments.len(), 2);
+    assert_eq!(first.uploader_id, Some(author_id));
+    assert_eq!(first.content_type, "image/png");
+
+    // Attachments are only found through the post they belong to.
+    let wrong_post = db_client
+        .delete_attachment(post_two.id, first.id)
+        .await
+        .unwrap();
+    let deleted = db_client
+        .delete_attachment(post_one.id, first.id)
+        .await
+        .unwrap();
+
+    assert!(wrong_post.is_none());
+    assert_eq!(deleted.unwrap().storage_key, "posts/1/a.png");
+    assert!(db_client
+        .get_posts_attachments(&[post_one.id])
+        .await
+        .unwrap()
+        .is_empty());
+
+    let duplicate = db_client
+        .save_attachment(post_one.id, author_id, test_attachment("posts/2/b.png"))
+        .await;
+
+    assert!(duplicate.is_err());
+}
+
+#[sqlx::test]
+async fn test_purge_returns_storage_keys(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let (user_one, _, _, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool.clone());
+
+    for key in ["posts/1/a.png", "posts/1/b.png"] {
+        db_client
+            .save_attachment(
+                post_one.id,
+                post_one.author_id.unwrap(),
+                test_attachment(key),
+            )
+            .await
+            .unwrap();
+    }
+
+    assert!(db_client
+        .update_user_avatar(
+            user_one.id,
+            Some("avatars/1/face.png".to_string()),
+            Some("http://localhost:5000/media/avatars/1/face.png".to_string()),
+        )
+        .await
+        .unwrap());
+
+    let user = db_client.get_user(user_one.id).await.unwrap().unwrap();
+
+    assert_eq!(
+        user.avatar_url.as_deref(),
+        Some("http://localhost:5000/media/avatars/1/face.png")
+    );
+
+    db_client.delete_post(post_one.id).await.unwrap();
+    db_client.delete_user(user_one.id).await.unwrap();
+
+    sqlx::query("UPDATE posts SET deleted_at = NOW() - INTERVAL '31 days' WHERE id = $1")
+        .bind(post_one.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE people SET deleted_at = NOW() - INTERVAL '31 days' WHERE id = $1")
+        .bind(user_one.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let mut purged_posts = db_client.purge_deleted_posts(30, 10).await.unwrap();
+    let purged_people = db_client.purge_deleted_people(30, 10).await.unwrap();
+
+    purged_posts[0].storage_keys.sort();
+
+    assert_eq!(
+        purged_posts[0].storage_keys,
+        vec!["posts/1/a.png", "posts/1/b.png"]
+    );
+    assert_eq!(purged_people[0].id, user_one.id);
+    assert_eq!(purged_people[0].storage_keys, vec!["avatars/1/face.png"]);
+}
+
+fn test_specs() -> Vec<ThumbnailSpec> {
+    ThumbnailSpec::parse_list("avatar:128, card:640")
+}
+
+fn encode_test_image(width: u32, height: u32, format: image::ImageFormat) -> Vec<u8> {
+    let mut bytes = std::io::Cursor::new(Vec::new());
+
+    image::DynamicImage::ImageRgb8(image::RgbImage::new(width, height))
+        .write_to(&mut bytes, format)
+        .unwrap();
+
+    bytes.into_inner()
+}
+
+#[test]
+fn test_thumbnail_specs_and_keys() {
+    assert_eq!(
+        test_specs(),
+        vec![
+            ThumbnailSpec {
+                name: "avatar".to_string(),
+                size: 128
+            },
+            ThumbnailSpec {
+                name: "card".to_string(),
+                size: 640
+            },
+        ]
+    );
+    assert!(ThumbnailSpec::parse_list("").is_empty());
+
+    assert_eq!(
+        variant_key("posts/1/abc.jpg", "card", "jpg"),
+        "posts/1/abc_card.jpg"
+    );
+    assert_eq!(
+        variant_key("avatars/1/abc.gif", "avatar", "png"),
+        "avatars/1/abc_avatar.png"
+    );
+}
+
+#[test]
+fn test_thumbnail_resizes_and_strips_exif() {
+    let jpeg = encode_test_image(200, 100, image::ImageFormat::Jpeg);
+
+    // An APP1 segment whose EXIF orientation says "rotate 90° clockwise".
+    let exif: &[u8] = &[
+        0xFF, 0xE1, 0x00, 0x22, b'E', b'x', b'i', b'f', 0x00, 0x00, b'M', b'M', 0x00, 0x2A, 0x00,
+        0x00, 0x00, 0x08, 0x00, 0x01, 0x01, 0x12, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01, 0x00, 0x06,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    let tagged: Vec<u8> = [&jpeg[..2], exif, &jpeg[2..]].concat();
+
+    let (image, format) = thumbnail::decode(&tagged).unwrap();
+
+    assert_eq!(format, image::ImageFormat::Jpeg);
+    assert_eq!((image.width(), image.height()), (100, 200));
+
+    let small = thumbnail::render(&image, format, 50).unwrap();
+
+    assert_eq!((small.width, small.height), (25, 50));
+    assert_eq!(small.content_type, "image/jpeg");
+    assert!(!small.bytes.windows(4).any(|window| window == b"Exif"));
+
+    // Smaller images are never scaled up, and only JPEGs stay JPEGs.
+    let png = encode_test_image(40, 20, image::ImageFormat::Png);
+    let (image, format) = thumbnail::decode(&png).unwrap();
+    let same = thumbnail::render(&image, format, 640).unwrap();
+
+    assert_eq!((same.width, same.height), (40, 20));
+    assert_eq!(same.extension, "png");
+
+    assert!(thumbnail::decode(b"not an image").is_err());
+}
+
+#[sqlx::test]
+async fn test_image_variant_queue(pool: Pool<Postgres>) {
+    let db_client = DBClient::new(pool);
+    let source_key = "posts/1/a.png".to_string();
+
+    let queued = db_client
+        .queue_image_variants(&source_key, &test_specs())
+        .await
+        .unwrap();
+    let requeued = db_client
+        .queue_image_variants(&source_key, &test_specs())
+        .await
+        .unwrap();
+
+    assert_eq!(queued.len(), 2);
+    assert!(requeued.is_empty());
+    assert_eq!(
+        variant_urls("http://original", &queued).get("card"),
+        Some(&"http://original".to_string())
+    );
+
+    let claimed = db_client
+        .claim_pending_image_variants(10, 300)
+        .await
+        .unwrap();
+    let claimed_again = db_client
+        .claim_pending_image_variants(10, 300)
+        .await
+        .unwrap();
+
+    assert_eq!(claimed.len(), 2);
+    assert!(claimed_again.is_empty());
+
+    let avatar = claimed.iter().find(|v| v.name == "avatar").unwrap();
+    let card = claimed.iter().find(|v| v.name == "card").unwrap();
+
+    assert!(db_client
+        .complete_image_variant(avatar.id, "posts/1/a_avatar.png", "http://avatar", 128, 64)
+        .await
+        .unwrap());
+
+    // A failed attempt releases the lease until the attempts run out.
+    db_client.fail_image_variant(card.id, 2).await.unwrap();
+    let retried = db_client
+        .claim_pending_image_variants(10, 300)
+        .await
+        .unwrap();
+    db_client.fail_image_variant(card.id, 2).await.unwrap();
+
+    assert_eq!(retried.len(), 1);
+    assert_eq!(retried[0].attempts, 2);
+
+    let variants = db_client
+        .get_image_variants(std::slice::from_ref(&source_key))
+        .await
+        .unwrap();
+    let urls = variant_urls("http://original", &variants);
+
+    assert_eq!(variants[0].status, ImageVariantStatus::Ready);
+    assert_eq!(variants[1].status, ImageVariantStatus::Failed);
+    assert_eq!(urls.get("avatar"), Some(&"http://avatar".to_string()));
+    assert_eq!(urls.get("card"), Some(&"http://original".to_string()));
+
+    let deleted = db_client
+        .delete_image_variants(std::slice::from_ref(&source_key))
+        .await
+        .unwrap();
+
+    assert_eq!(deleted, vec!["posts/1/a_avatar.png"]);
+    assert!(!db_client
+        .complete_image_variant(card.id, "posts/1/a_card.png", "http://card", 1, 1)
+        .await
+        .unwrap());
+}
+
+#[sqlx::test]
+async fn test_render_pending_variants(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let root = std::env::temp_dir().join(format!("storage-{}", uuid::Uuid::new_v4()));
+    let storage = LocalStorage::with_root(&root, "http://localhost:5000/media");
+
+    let png = encode_test_image(1000, 500, image::ImageFormat::Png);
+    storage
+        .put("posts/1/a.png", png.into(), "image/png")
+        .await
+        .unwrap();
+    storage
+        .put("posts/1/broken.png", PNG_BYTES.into(), "image/png")
+        .await
+        .unwrap();
+
+    let attachment = db_client
+        .save_attachment(
+            post_one.id,
+            post_one.author_id.unwrap(),
+            test_attachment("posts/1/a.png"),
+        )
+        .await
+        .unwrap();
+
+    for key in ["posts/1/a.png", "posts/1/broken.png"] {
+        db_client
+            .queue_image_variants(key, &test_specs())
+            .await
+            .unwrap();
+    }
+
+    // Rendering runs on the actix blocking pool, which needs an actix system.
+    let render_client = db_client.clone();
+    std::thread::spawn(move || {
+        actix_web::rt::System::new()
+            .block_on(async move { render_pending_variants(&render_client, &storage).await })
+    })
+    .join()
+    .unwrap();
+
+    let attachments = db_client
+        .get_posts_attachments(&[post_one.id])
+        .await
+        .unwrap();
+    let variants = &attachments[0].variants;
+
+    assert_eq!(attachments[0].id, attachment.id);
+    assert_eq!(variants.len(), 2);
+    assert!(variants
+        .iter()
+        .all(|variant| variant.status == ImageVariantStatus::Ready));
+    assert_eq!(
+        (variants[0].width, variants[0].height),
+        (Some(128), Some(64))
+    );
+    assert_eq!(
+        variants[1].url.as_deref(),
+        Some("http://localhost:5000/media/posts/1/a_card.png")
+    );
+    assert!(root.join("posts/1/a_avatar.png").exists());
+
+    // Undecodable images stay pending for another attempt.
+    let broken = db_client
+        .get_image_variants(&["posts/1/broken.png".to_string()])
+        .await
+        .unwrap();
+
+    assert!(broken
+        .iter()
+        .all(|variant| variant.status == ImageVariantStatus::Pending && variant.attempts == 1));
+
+    std::fs::remove_dir_all(root).unwrap();
+}
+
+#[sqlx::test]
+async fn test_follow_public_and_private_profiles(pool: Pool<Postgres>) {
+    let (alice, john, sarah, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    db_client
+        .update_user(
+            sarah.id,
+            UpdateUserDto {
+                is_profile_private: Some(true),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let follow = db_client
+        .save_follow(alice.id, john.id)
+        .await
+        .unwrap()
+        .expect("Follow not saved");
+    let request = db_client
+        .save_follow(alice.id, sarah.id)
+        .await
+        .unwrap()
+        .expect("Follow request not saved");
+
+    assert_eq!(follow.status, FollowStatus::Accepted);
+    assert!(follow.accepted_at.is_some());
+    assert_eq!(request.status, FollowStatus::Pending);
+    assert!(request.accepted_at.is_none());
+
+    // Following twice, or following yourself, saves nothing.
+    assert!(db_client
+        .save_follow(alice.id, john.id)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(db_client.save_follow(john.id, john.id).await.is_err());
+
+    let john_after = db_client.get_user(john.id).await.unwrap().unwrap();
+    let sarah_after = db_client.get_user(sarah.id).await.unwrap().unwrap();
+    let alice_after = db_client.get_user(alice.id).await.unwrap().unwrap();
+
+    assert_eq!(john_after.followers_count, 1);
+    assert_eq!(sarah_after.followers_count, 0);
+    assert_eq!(alice_after.following_count, 1);
+
+    let requests = db_client
+        .get_followers(sarah.id, FollowStatus::Pending, 1, 20)
+        .await
+        .unwrap();
+
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].id, alice.id);
+    assert_eq!(requests[0].following_count, 1);
+
+    assert!(db_client.accept_follow(alice.id, sarah.id).await.unwrap());
+    assert!(!db_client.accept_follow(alice.id, sarah.id).await.unwrap());
+
+    let following = db_client.get_following(alice.id, 1, 20).await.unwrap();
+
+    assert_eq!(following.len(), 2);
+    assert_eq!(following[0].id, sarah.id);
+    assert_eq!(following[0].followers_count, 1);
+}
+
+#[sqlx::test]
+async fn test_unfollow_and_accept_follow_requests(pool: Pool<Postgres>) {
+    let (alice, john, sarah, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    db_client
+        .update_user(
+            alice.id,
+            UpdateUserDto {
+                is_profile_private: Some(true),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    db_client.save_follow(john.id, alice.id).await.unwrap();
+    db_client.save_follow(sarah.id, alice.id).await.unwrap();
+
+    // Removing a follower only matches accepted follows.
+    assert!(!db_client
+        .delete_follow(john.id, alice.id, Some(FollowStatus::Accepted))
+        .await
+        .unwrap());
+    assert!(db_client
+        .delete_follow(john.id, alice.id, Some(FollowStatus::Pending))
+        .await
+        .unwrap());
+
+    assert_eq!(db_client.accept_follow_requests(alice.id).await.unwrap(), 1);
+
+    let followers = db_client
+        .get_followers(alice.id, FollowStatus::Accepted, 1, 20)
+        .await
+        .unwrap();
+
+    assert_eq!(followers.len(), 1);
+    assert_eq!(followers[0].id, sarah.id);
+
+    // Trashed accounts drop out of the lists and counts.
+    db_client.delete_user(sarah.id).await.unwrap();
+
+    let alice_after = db_client.get_user(alice.id).await.unwrap().unwrap();
+    let followers = db_client
+        .get_followers(alice.id, FollowStatus::Accepted, 1, 20)
+        .await
+        .unwrap();
+
+    assert_eq!(alice_after.followers_count, 0);
+    assert!(followers.is_empty());
+
+    assert!(db_client
+        .delete_follow(sarah.id, alice.id, None)
+        .await
+        .unwrap());
+    assert!(!db_client
+        .delete_follow(sarah.id, alice.id, None)
+        .await
+        .unwrap());
+}
+
+#[sqlx::test]
+async fn test_only_the_user_manages_their_followers(pool: Pool<Postgres>) {
+    let (alice, john, _, _) = init_test_users(&pool).await;
+    let admin = init_test_admin(&pool).await;
+    let app_state = test_app_state(&pool);
+
+    app_state
+        .db_client
+        .update_user(
+            alice.id,
+            UpdateUserDto {
+                is_profile_private: Some(true),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    app_state
+        .db_client
+        .save_follow(john.id, alice.id)
+        .await
+        .unwrap();
+
+    let approve = |person_id| {
+        let app_state = app_state.clone();
+        let pool = pool.clone();
+
+        async move {
+            scopes::follows::approve_follow_request(
+                app_state,
+                IdPath(GetFollowerParamsDto {
+                    user_id: alice.id,
+                    follower_id: john.id,
+                }),
+                authenticated(&pool, person_id).await,
+            )
+            .await
+        }
+    };
+
+    // Admins can't let anyone into a private profile either.
+    assert_eq!(approve(admin.id).await.unwrap_err().status, 403);
+    assert_eq!(approve(alice.id).await.unwrap().status(), 200);
+}
+
+#[test]
+fn test_cursor_round_trip() {
+    let cursor = Cursor::new(Utc::now(), uuid::Uuid::new_v4());
+    let encoded = cursor.encode();
+
+    assert_eq!(Cursor::decode(&encoded), Some(cursor));
+    assert!(!encoded.contains('='));
+
+    assert_eq!(Cursor::decode("not a cursor"), None);
+    assert_eq!(Cursor::decode(""), None);
+
+    let query = FeedQueryDto {
+        cursor: Some("bm9wZQ".to_string()),
+        limit: Some(20),
+    };
+
+    assert!(query.validate().is_err());
+}
+
+#[sqlx::test]
+async fn test_get_feed(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, post_five) = init_test_posts(&pool).await;
+    let (alice, john, sarah, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+    let author_id = post_one.author_id.unwrap();
+
+    db_client
+        .save_post(
+            john.id,
+            CreatePostDto {
+                title: "A draft nobody should see".to_string(),
+                description: "Still being written".to_string(),
+                tags: None,
+                publish: Some(false),
+                scheduled_at: None,
+            },
+        )
+        .await
+        .unwrap();
+    let johns_post = db_client
+        .save_post(
+            john.id,
+            CreatePostDto {
+                title: "Hello from John".to_string(),
+                description: "Published for followers".to_string(),
+                tags: None,
+                publish: Some(true),
+                scheduled_at: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    assert!(db_client
+        .get_feed(alice.id, None, 10)
+        .await
+        .unwrap()
+        .is_empty());
+
+    db_client.save_follow(alice.id, author_id).await.unwrap();
+    db_client.save_follow(alice.id, john.id).await.unwrap();
+    db_client.delete_post(post_five.id).await.unwrap();
+
+    let feed = db_client.get_feed(alice.id, None, 10).await.unwrap();
+    let ids: Vec<uuid::Uuid> = feed.iter().map(|post| post.id).collect();
+
+    assert_eq!(feed.len(), 5);
+    assert_eq!(feed[0].id, johns_post.id);
+    assert_eq!(*ids.last().unwrap(), post_one.id);
+    assert!(!ids.contains(&post_five.id));
+
+    // Pages pick up right after the cursor, without gaps or repeats.
+    let mut paged: Vec<uuid::Uuid> = vec![];
+    let mut after = None;
+
+    loop {
+        let page = db_client.get_feed(alice.id, after, 2).await.unwrap();
+
+        paged.extend(page.iter().map(|post| post.id));
+
+        match page.last() {
+            Some(post) if page.len() == 2 => {
+                after = Some(Cursor::new(post.published_at.unwrap(), post.id))
+            }
+            _ => break,
+        }
+    }
+
+    assert_eq!(paged, ids);
+
+    // A follow request that is still pending does not fill the feed.
+    db_client
+        .update_user(
+            alice.id,
+            UpdateUserDto {
+                is_profile_private: Some(true),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    db_client.save_follow(sarah.id, alice.id).await.unwrap();
+    db_client
+        .save_post(
+            alice.id,
+            CreatePostDto {
+                title: "Only for approved followers".to_string(),
+                description: "Private profile".to_string(),
+                tags: None,
+                publish: Some(true),
+                scheduled_at: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    assert!(db_client
+        .get_feed(sarah.id, None, 10)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[sqlx::test]
+async fn test_block_user(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let (alice, john, sarah, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+    let author_id = post_one.author_id.unwrap();
+
+    db_client.save_follow(alice.id, john.id).await.unwrap();
+    db_client.save_follow(john.id, alice.id).await.unwrap();
+    db_client.save_follow(alice.id, sarah.id).await.unwrap();
+    let comment = db_client
+        .save_comment(
+            post_one.id,
+            john.id,
+            CreateCommentDto {
+                body: "Blocked people can't read this".to_string(),
+                parent_id: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    assert!(db_client.save_block(alice.id, john.id).await.unwrap());
+    assert!(!db_client.save_block(alice.id, john.id).await.unwrap());
+    assert!(db_client.save_block(alice.id, alice.id).await.is_err());
+
+    // Blocks work both ways and drop the follows between the two of them only.
+    assert!(db_client.is_blocked(alice.id, john.id).await.unwrap());
+    assert!(db_client.is_blocked(john.id, alice.id).await.unwrap());
+    assert!(!db_client.is_blocked(alice.id, sarah.id).await.unwrap());
+    assert!(db_client
+        .get_follow(alice.id, john.id)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(db_client
+        .get_follow(john.id, alice.id)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(db_client
+        .get_follow(alice.id, sarah.id)
+        .await
+        .unwrap()
+        .is_some());
+    assert!(db_client
+        .save_follow(john.id, alice.id)
+        .await
+        .unwrap()
+        .is_none());
+
+    let users = db_client
+        .get_users(SearchUserQueryDto::default(), true, Some(john.id))
+        .await
+        .unwrap();
+    let comments = db_client
+        .get_comments(
+            post_one.id,
+            None,
+            SearchCommentQueryDto::default(),
+            Some(alice.id),
+        )
+        .await
+        .unwrap();
+
+    let sarahs_comments = db_client
+        .get_comments(
+            post_one.id,
+            None,
+            SearchCommentQueryDto::default(),
+            Some(sarah.id),
+        )
+        .await
+        .unwrap();
+
+    assert!(!users.iter().any(|user| user.id == alice.id));
+    assert!(users.iter().any(|user| user.id == sarah.id));
+    assert!(comments.is_empty());
+    assert_eq!(sarahs_comments[0].id, comment.id);
+
+    db_client.save_block(sarah.id, author_id).await.unwrap();
+
+    let posts = db_client
+        .get_posts(SearchPostQueryDto::default(), Some(sarah.id))
+        .await
+        .unwrap();
+    let anonymous_posts = db_client
+        .get_posts(SearchPostQueryDto::default(), None)
+        .await
+        .unwrap();
+
+    assert!(posts.is_empty());
+    assert!(!anonymous_posts.is_empty());
+
+    let blocked = db_client.get_blocked_users(alice.id, 1, 20).await.unwrap();
+
+    assert_eq!(blocked.len(), 1);
+    assert_eq!(blocked[0].id, john.id);
+
+    assert!(db_client.delete_block(alice.id, john.id).await.unwrap());
+    assert!(!db_client.delete_block(alice.id, john.id).await.unwrap());
+    assert!(!db_client.is_blocked(john.id, alice.id).await.unwrap());
+}
+
+#[sqlx::test]
+async fn test_mute_user(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let (alice, john, _, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+    let author_id = post_one.author_id.unwrap();
+
+    db_client.save_follow(alice.id, author_id).await.unwrap();
+
+    assert!(!db_client
+        .get_feed(alice.id, None, 10)
+        .await
+        .unwrap()
+        .is_empty());
+
+    assert!(db_client.save_mute(alice.id, author_id).await.unwrap());
+    assert!(!db_client.save_mute(alice.id, author_id).await.unwrap());
+
+    // Muting hides the author from the muter only, and keeps the follow.
+    assert!(db_client
+        .get_feed(alice.id, None, 10)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(db_client
+        .get_posts(SearchPostQueryDto::default(), Some(alice.id))
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(!db_client
+        .get_posts(SearchPostQueryDto::default(), Some(john.id))
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(db_client
+        .get_follow(alice.id, author_id)
+        .await
+        .unwrap()
+        .is_some());
+    assert!(!db_client.is_blocked(alice.id, author_id).await.unwrap());
+
+    // Outside of feeds the muted author stays visible.
+    let comment = db_client
+        .save_comment(
+            post_one.id,
+            author_id,
+            CreateCommentDto {
+                body: "Thanks for reading!".to_string(),
+                parent_id: None,
+            },
+        )
+        .await
+        .unwrap();
+    let comments = db_client
+        .get_comments(
+            post_one.id,
+            None,
+            SearchCommentQueryDto::default(),
+            Some(alice.id),
+        )
+        .await
+        .unwrap();
+
+    assert!(comments.iter().any(|c| c.id == comment.id));
+
+    let users = db_client
+        .get_users(
+            SearchUserQueryDto {
+                limit: Some(50),
+                ..Default::default()
+            },
+            false,
+            Some(alice.id),
+        )
+        .await
+        .unwrap();
+
+    assert!(users.iter().any(|user| user.id == author_id));
+
+    let muted = db_client.get_muted_users(alice.id, 1, 20).await.unwrap();
+
+    assert_eq!(muted.len(), 1);
+    assert_eq!(muted[0].id, author_id);
+
+    assert!(db_client.delete_mute(alice.id, author_id).await.unwrap());
+    assert!(!db_client.delete_mute(alice.id, author_id).await.unwrap());
+    assert!(db_client
+        .get_muted_users(alice.id, 1, 20)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[sqlx::test]
+async fn test_save_notification(pool: Pool<Postgres>) {
+    let (post_one, _, _, _, _) = init_test_posts(&pool).await;
+    let (alice, john, sarah, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+    let author_id = post_one.author_id.unwrap();
+    let reaction = NotificationPayload::Reaction {
+        post_id: post_one.id,
+        reaction: ReactionKind::Love,
+    };
+
+    let notification = db_client
+        .save_notification(author_id, alice.id, reaction.clone())
+        .await
+        .unwrap()
+        .expect("Notification not saved");
+
+    assert_eq!(notification.kind, NotificationKind::Reaction);
+    assert_eq!(notification.payload.0, reaction);
+    assert!(notification.read_at.is_none());
+
+    // Nobody is notified of their own actions, nor by people they blocked or muted.
+    db_client.save_block(author_id, john.id).await.unwrap();
+    db_client.save_mute(author_id, sarah.id).await.unwrap();
+
+    for actor_id in [author_id, john.id, sarah.id] {
+        assert!(db_client
+            .save_notification(author_id, actor_id, NotificationPayload::Follow)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    let author = db_client.get_person(author_id).await.unwrap().unwrap();
+
+    // Kinds that were turned off are not saved either.
+    let disabled = db_client
+        .update_disabled_notification_kinds(
+            author_id,
+            &[],
+            &[NotificationKind::Follow, NotificationKind::Reaction],
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        disabled,
+        vec![NotificationKind::Follow, NotificationKind::Reaction]
+    );
+    assert!(db_client
+        .save_notification(author_id, alice.id, NotificationPayload::Follow)
+        .await
+        .unwrap()
+        .is_none());
+
+    let disabled = db_client
+        .update_disabled_notification_kinds(author_id, &[NotificationKind::Follow], &[])
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(disabled, vec![NotificationKind::Reaction]);
+    assert!(db_client
+        .save_notification(author_id, alice.id, NotificationPayload::Follow)
+        .await
+        .unwrap()
+        .is_some());
+    assert_eq!(
+        db_client
+            .get_disabled_notification_kinds(author_id)
+            .await
+            .unwrap(),
+        Some(vec![NotificationKind::Reaction])
+    );
+
+    // Preferences are not part of the profile, so its ETag stays the same.
+    let author_after = db_client.get_person(author_id).await.unwrap().unwrap();
+
+    assert_eq!(author_after.version, author.version);
+    assert_eq!(author_after.updated_at, author.updated_at);
+}
+
+#[sqlx::test]
+async fn test_get_and_read_notifications(pool: Pool<Postgres>) {
+    let (alice, john, sarah, _) = init_test_users(&pool).await;
+    let db_client = DBClient::new(pool);
+
+    let mut saved: Vec<uuid::Uuid> = vec![];
+
+    for actor_id in [john.id, sarah.id, john.id] {
+        let notification = db_client
+            .save_notification(alice.id, actor_id, NotificationPayload::Follow)
+            .await
+            .unwrap()
+            .unwrap();
+
+        saved.insert(0, notification.id);
+    }
+
+    let notifications = db_client
+        .get_notifications(alice.id, false, None, 10)
+        .await
+        .unwrap();
+    let ids: Vec<uuid::Uuid> = notifications.iter().map(|n| n.id).collect();
+
+    assert_eq!(ids, saved);
+
+    let last = &notifications[1];
+    let next_page = db_client
+        .get_notifications(
+            alice.id,
+            false,
+            Some(Cursor::new(last.created_at, last.id)),
+            10,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(next_page.len(), 1);
+    assert_eq!(next_page[0].id, saved[2]);
+
+    assert!(db_client
+        .mark_notification_read(saved[0], alice.id)
+        .await
+        .unwrap());
+    assert!(!db_client
+        .mark_notification_read(saved[0], john.id)
+        .await
+        .unwrap());
+
+    let unread = db_client
+        .get_notifications(alice.id, true, None, 10)
+        .await
+        .unwrap();
+
+    assert_eq!(unread.len(), 2);
+    assert_eq!(
+        db_client
+            .get_unread_notifications_count(alice.id)
+            .await
+            .unwrap(),
+        2
+    );
+
+    // Muting someone later hides what they already did.
+    db_client.save_mute(alice.id, sarah.id).await.unwrap();
+
+    assert_eq!(
+        db_client
+            .get_unread_notifications_count(alice.id)
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        db_client
+            .mark_all_notifications_read(alice.id)
+            .await
+            .unwrap(),
+        2
+    );
+    assert!(db_client
+        .get_notifications(alice.id, true, None, 10)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_notification_dto() {
+    let post_id = uuid::Uuid::new_v4();
+    let comment_id = uuid::Uuid::new_v4();
+    let notification = crate::models::Notification {
+        id: uuid::Uuid::new_v4(),
+        recipient_id: uuid::Uuid::new_v4(),
+        actor_id: uuid::Uuid::new_v4(),
+        kind: NotificationKind::Comment,
+        payload: sqlx::types::Json(NotificationPayload::Comment {
+            post_id,
+            comment_id,
+        }),
+        read_at: None,
+        created_at: Utc::now(),
+    };
+
+    let json = serde_json::to_value(NotificationDto::filter_notification(&notification)).unwrap();
+
+    assert_eq!(json["kind"], "comment");
+    assert_eq!(json["postId"], post_id.to_string());
+    assert_eq!(json["commentId"], comment_id.to_string());
+    assert!(json["readAt"].is_null());
+
+    let follow = serde_json::to_value(NotificationPayload::FollowRequest).unwrap();
+
+    assert_eq!(follow, serde_json::json!({ "kind": "follow_request" }));
+}
+
+#[test]
+fn test_update_notification_preferences_dto() {
+    let dto = serde_json::from_str::<UpdateNotificationPreferencesDto>(
+        r#"{ "reply": false, "followRequest": true }"#,
+    )
+    .unwrap();
+
+    assert!(dto.validate().is_ok());
+    assert_eq!(
+        dto.changes(),
+        (
+            vec![NotificationKind::FollowRequest],
+            vec![NotificationKind::Reply]
+        )
+    );
+    assert!(UpdateNotificationPreferencesDto::default()
+        .validate()
+        .is_err());
+}
+
+#[test]
+fn test_topic_parse_and_display() {
+    let id = uuid::Uuid::new_v4();
+
+    assert_eq!(format!("post:{}", id).parse::<Topic>(), Ok(Topic::Post(id)));
+    assert_eq!(format!("user:{}", id).parse::<Topic>(), Ok(Topic::User(id)));
+    assert_eq!(Topic::User(id).to_string(), format!("user:{}", id));
+    assert!(format!("tag:{}", id).parse::<Topic>().is_err());
+    assert!("post:not-a-uuid".parse::<Topic>().is_err());
+    assert!("post".parse::<Topic>().is_err());
+}
+
+#[test]
+fn test_event_delivery() {
+    let post_id = uuid::Uuid::new_v4();
+    let author_id = uuid::Uuid::new_v4();
+    let person_id = uuid::Uuid::new_v4();
+    let event = Event::PostUpdated {
+        post_id,
+        author_id: Some(author_id),
+    };
+
+    let json = serde_json::to_value(&event).unwrap();
+
+    assert_eq!(json["type"], "post_updated");
+    assert_eq!(json["postId"], post_id.to_string());
+    assert_eq!(json["authorId"], author_id.to_string());
+
+    let no_topics = HashSet::new();
+    let post_topic = HashSet::from([Topic::Post(post_id)]);
+    let author_topic = HashSet::from([Topic::User(author_id)]);
+    let other_topic = HashSet::from([Topic::User(person_id)]);
+
+    assert!(!event.is_delivered_to(person_id, &no_topics));
+    assert!(event.is_delivered_to(person_id, &post_topic));
+    assert!(event.is_delivered_to(person_id, &author_topic));
+    assert!(!event.is_delivered_to(person_id, &other_topic));
+
+    // Notifications reach their recipient only, whatever they subscribed to.
+    let notification = Event::Notification {
+        recipient_id: person_id,
+        notification: NotificationDto {
+            id: uuid::Uuid::new_v4().to_string(),
+            actor_id: author_id.to_string(),
+            payload: NotificationPayload::Follow,
+            read_at: None,
+            created_at: Utc::now(),
+        },
+    };
+
+    assert!(notification.is_delivered_to(person_id, &no_topics));
+    assert!(!notification.is_delivered_to(author_id, &author_topic));
+}
+
+#[sqlx::test]
+async fn test_ws_drops_topics_that_became_hidden(pool: Pool<Postgres>) {
+    let (post_one, post_two, _, _, _) = init_test_posts(&pool).await;
+    let (alice, john, _, _) = init_test_users(&pool).await;
+    let app_state = test_app_state(&pool);
+    let author_id = post_one.author_id.unwrap();
+    let alice_person = authenticated(&pool, alice.id).await;
+    let mut topics = HashSet::from([Topic::Post(post_one.id), Topic::User(author_id)]);
+
+    let dropped = scopes::ws::drop_hidden_topics(
+        &app_state,
+        &alice_person,
+        &mut topics,
+        &Event::post_updated(&post_one),
+    )
+    .await;
+    assert!(dropped.is_empty());
+
+    // Going private hides the author's activity from people who don't follow them.
+    app_state
+        .db_client
+        .update_user(
+            author_id,
+            UpdateUserDto {
+                is_profile_private: Some(true),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let dropped = scopes::ws::drop_hidden_topics(
+        &app_state,
+        &alice_person,
+        &mut topics,
+        &Event::post_updated(&post_one),
+    )
+    .await;
+    assert_eq!(dropped, vec![Topic::User(author_id)]);
+    assert_eq!(topics, HashSet::from([Topic::Post(post_one.id)]));
+
+    // Being blocked hides the posts too.
+    app_state
+        .db_client
+        .save_block(author_id, alice.id)
+        .await
+        .unwrap();
+
+    let dropped = scopes::ws::drop_hidden_topics(
+        &app_state,
+        &alice_person,
+        &mut topics,
+        &Event::post_updated(&post_one),
+    )
+    .await;
+    assert_eq!(dropped, vec![Topic::Post(post_one.id)]);
+    assert!(topics.is_empty());
+
+    // Deleting a post still reaches those subscribed to it.
+    app_state.db_client.delete_post(post_two.id).await.unwrap();
+
+    let mut topics = HashSet::from([Topic::Post(post_two.id)]);
+    let dropped = scopes::ws::drop_hidden_topics(
+        &app_state,
+        &authenticated(&pool, john.id).await,
+        &mut topics,
+        &Event::post_deleted(&post_two),
+    )
+    .await;
+    assert!(dropped.is_empty());
+}
+
+#[test]
+fn test_redact_token() {
+    assert_eq!(
+        redact_token("/api/ws?token=eyJhbGciOi.payload.signature"),
+        "/api/ws?token=REDACTED"
+    );
+    assert_eq!(
+        redact_token("/api/ws?lang=en&token=secret&x"),
+        "/api/ws?lang=en&token=REDACTED&x"
+    );
+    assert_eq!(
+        redact_token("/api/posts?tokens=2&page=1"),
+        "/api/posts?tokens=2&page=1"
+    );
+    assert_eq!(redact_token("/api/posts"), "/api/posts");
+}
+
+#[actix_web::test]
+async fn test_local_event_hub() {
+    let hub = LocalEventHub::new(2, 0);
+    let event = Event::PostDeleted {
+        post_id: uuid::Uuid::new_v4(),
+        author_id: None,
+    };
+
+    // Nobody listens yet, so the event is dropped.
+    hub.publish(event.clone());
+
+    let mut first = hub.subscribe();
+    let mut second = hub.subscribe();
+
+    hub.publish(event.clone());
+
+    assert_eq!(first.recv().await.unwrap().event, event);
+    assert_eq!(second.recv().await.unwrap().event, event);
+    assert!(first.try_recv().is_err());
+
+    // Subscribers that fall too far behind are told how much they missed.
+    for _ in 0..3 {
+        hub.publish(event.clone());
+    }
+
+    assert!(matches!(
+        first.recv().await,
+        Err(tokio::sync::broadcast::error::RecvError::Lagged(1))
+    ));
+    assert_eq!(first.recv().await.unwrap().event, event);
+}
+
+#[test]
+fn test_event_log() {
+    let hub = LocalEventHub::new(8, 3);
+    let event = Event::PostUpdated {
+        post_id: uuid::Uuid::new_v4(),
+        author_id: None,
+    };
+
+    for _ in 0..4 {
+        hub.publish(event.clone());
+    }
+
+    let mut receiver = hub.subscribe();
+    hub.publish(event.clone());
+    let logged = receiver.try_recv().unwrap();
+
+    let epoch = logged.id.epoch;
+    assert_eq!(logged.id.seq, 5);
+
+    // Only the last three events are kept.
+    let missed = hub.events_after(EventId { epoch, seq: 2 }).unwrap();
+    assert_eq!(
+        missed
+            .iter()
+            .map(|logged| logged.id.seq)
+            .collect::<Vec<_>>(),
+        vec![3, 4, 5]
+    );
+    assert_eq!(missed[2], logged);
+
+    assert!(hub
+        .events_after(EventId { epoch, seq: 5 })
+        .unwrap()
+        .is_empty());
+
+    // Evicted, not issued yet or from another run of the server.
+    assert!(hub.events_after(EventId { epoch, seq: 1 }).is_none());
+    assert!(hub.events_after(EventId { epoch, seq: 6 }).is_none());
+    assert!(hub
+        .events_after(EventId {
+            epoch: epoch - 1,
+            seq: 4
+        })
+        .is_none());
+}
+
+/// Publishes an event right before reading the log, as another request could
+/// between a stream subscribing and catching up.
+#[derive(Debug)]
+struct RacingEventHub {
+    inner: LocalEventHub,
+    event: Event,
+}
+
+impl EventHub for RacingEventHub {
+    fn publish(&self, event: Event) {
+        self.inner.publish(event);
+    }
+
+    fn subscribe(&self) -> tokio::sync::broadcast::Receiver<events::LoggedEvent> {
+        self.inner.subscribe()
+    }
+
+    fn events_after(&self, id: EventId) -> Option<Vec<events::LoggedEvent>> {
+        self.inner.publish(self.event.clone());
+        self.inner.events_after(id)
+    }
+}
+
+#[actix_web::test]
+async fn test_stream_posts_resumes_without_duplicates() {
+    // The stream's timers need the actix runtime, and it never reads the database.
+    let pool = sqlx::postgres::PgPoolOptions::new()
+        .connect_lazy("postgres://localhost")
+        .unwrap();
+    let event = Event::PostUpdated {
+        post_id: uuid::Uuid::new_v4(),
+        author_id: None,
+    };
+    let hub = std::sync::Arc::new(RacingEventHub {
+        inner: LocalEventHub::new(16, 16),
+        event: event.clone(),
+    });
+    let app_state = test_app_state_with_events(&pool, hub.clone());
+
+    let mut receiver = hub.subscribe();
+    for _ in 0..3 {
+        hub.publish(event.clone());
+    }
+    let first_id = receiver.try_recv().unwrap().id;
+
+    let req = actix_web::test::TestRequest::get()
+        .insert_header(("last-event-id", first_id.to_string()))
+        .to_http_request();
+    let res = scopes::stream::stream_posts(req, app_state).await;
+
+    assert_eq!(res.status(), actix_web::http::StatusCode::OK);
+
+    // Published after the stream caught up, so only received live.
+    hub.publish(event.clone());
+
+    let mut body = Box::pin(res.into_body());
+    let mut ids = vec![];
+
+    while ids.len() < 4 {
+        let frame = futures_util::future::poll_fn(|cx| {
+            actix_web::body::MessageBody::poll_next(body.as_mut(), cx)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+        let frame = String::from_utf8(frame.to_vec()).unwrap();
+
+        ids.extend(
+            frame
+                .lines()
+                .filter_map(|line| line.strip_prefix("id: "))
+                .map(|id| id.parse::<EventId>().unwrap().seq),
+        );
+    }
+
+    // The event published while subscribing is sent once, from the log.
+    assert_eq!(ids, vec![2, 3, 4, 5]);
+}
+
+#[test]
+fn test_event_id_parse_and_display() {
+    let id = EventId {
+        epoch: 1760000000000,
+        seq: 42,
+    };
+
+    assert_eq!(id.to_string(), "1760000000000-42");
+    assert_eq!("1760000000000-42".parse::<EventId>(), Ok(id));
+
+    for invalid in ["", "42", "a-1", "1-b", "1-2-3", "1--2"] {
+        assert!(invalid.parse::<EventId>().is_err(), "{}", invalid);
+    }
+
+    let event = Event::Notification {
+        recipient_id: uuid::Uuid::new_v4(),
+        notification: NotificationDto {
+            id: uuid::Uuid::new_v4().to_string(),
+            actor_id: uuid::Uuid::new_v4().to_string(),
+            payload: NotificationPayload::Follow,
+            read_at: None,
+            created_at: Utc::now(),
+        },
+    };
+    assert!(!event.is_public());
+    assert!(Event::PostCreated {
+        post_id: uuid::Uuid::new_v4(),
+        author_id: None,
+    }
+    .is_public());
+}
+
+#[sqlx::test]
+async fn test_account_routes_require_owner_or_admin(pool: Pool<Postgres>) {
+    let (alice, john, _, _) = init_test_users(&pool).await;
+    let admin = init_test_admin(&pool).await;
+    let app_state = test_app_state(&pool);
+
+    let new_admin = || {
+        web::Json(CreateAdminDto {
+            firstname: "Eve".to_string(),
+            lastname: "Intruder".to_string(),
+            username: "eve_admin".to_string(),
+            email: "eve@example.com".to_string(),
+            birthdate: NaiveDate::from_ymd_opt(1990, 1, 1).unwrap(),
+            gender: None,
+            gender_description: None,
+            pronouns: None,
+            password: "password123".to_string(),
+        })
+    };
+
+    let err = scopes::admins::save_admin(
+        app_state.clone(),
+        authenticated(&pool, alice.id).await,
+        new_admin(),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.status, 403);
+
+    let res = scopes::admins::save_admin(
+        app_state.clone(),
+        authenticated(&pool, admin.id).await,
+        new_admin(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(res.status(), 201);
+
+    let err = scopes::users::update_user(
+        app_state.clone(),
+        IdPath(GetUserParamsDto { user_id: alice.id }),
+        authenticated(&pool, john.id).await,
+        IfMatch(None),
+        web::Json(UpdateUserPublicInfoDto {
+            firstname: Some("Mallory".to_string()),
+            ..Default::default()
+        }),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.status, 403);
+
+    let new_email = || {
+        web::Json(CreateEmailDto {
+            address: "alice.work@example.com".to_string(),
+            is_private: Some(false),
+            is_primary: Some(false),
+        })
+    };
+
+    let err = scopes::emails::save_email(
+        app_state.clone(),
+        IdPath(GetEmailsByOwnerIdParamsDto { owner_id: alice.id }),
+        authenticated(&pool, john.id).await,
+        new_email(),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.status, 403);
+
+    scopes::emails::save_email(
+        app_state.clone(),
+        IdPath(GetEmailsByOwnerIdParamsDto { owner_id: alice.id }),
+        authenticated(&pool, alice.id).await,
+        new_email(),
+    )
+    .await
+    .unwrap();
+
+    let email = app_state
+        .db_client
+        .get_person_emails(alice.id)
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|email| email.address == "alice.work@example.com")
+        .unwrap();
+
+    let err = scopes::emails::delete_email(
+        app_state.clone(),
+        IdPath(GetEmailByIdParamsDto { id: email.id }),
+        authenticated(&pool, john.id).await,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.status, 403);
+
+    let err = scopes::users::delete_user(
+        app_state.clone(),
+        IdPath(GetUserParamsDto { user_id: alice.id }),
+        authenticated(&pool, john.id).await,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.status, 403);
+
+    let res = scopes::users::delete_user(
+        app_state.clone(),
+        IdPath(GetUserParamsDto { user_id: alice.id }),
+        authenticated(&pool, admin.id).await,
+    )
+    .await
+    .unwrap();
+    assert_eq!(res.status(), 200);
+}
+
+#[sqlx::test]
+async fn test_only_the_user_changes_profile_privacy(pool: Pool<Postgres>) {
+    let (alice, john, _, _) = init_test_users(&pool).await;
+    let admin = init_test_admin(&pool).await;
+    let app_state = test_app_state(&pool);
+
+    app_state
+        .db_client
+        .update_user(
+            alice.id,
+            UpdateUserDto {
+                is_profile_private: Some(true),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    let follow = app_state
+        .db_client
+        .save_follow(john.id, alice.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(follow.status, FollowStatus::Pending);
+
+    let make_public = |person_id| {
+        let app_state = app_state.clone();
+        let pool = pool.clone();
+
+        async move {
+            scopes::users::update_user_privacy(
+                app_state,
+                IdPath(GetUserParamsDto { user_id: alice.id }),
+                authenticated(&pool, person_id).await,
+                IfMatch(None),
+                web::Json(UpdateUserProfileStatusDto {
+                    is_profile_private: false,
+                }),
+            )
+            .await
+        }
+    };
+
+    assert_eq!(make_public(john.id).await.unwrap_err().status, 403);
+    assert_eq!(make_public(admin.id).await.unwrap_err().status, 403);
+
+    let follow = app_state
+        .db_client
+        .get_follow(john.id, alice.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(follow.status, FollowStatus::Pending);
+
+    make_public(alice.id).await.unwrap();
+
+    let follow = app_state
+        .db_client
+        .get_follow(john.id, alice.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(follow.status, FollowStatus::Accepted);
+}
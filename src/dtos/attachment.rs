@@ -0,0 +1,84 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Attachment, ImageVariant, ImageVariantStatus};
+
+/// URL of every variant of an image by name. Variants that are not rendered
+/// yet, or could not be, fall back to the original.
+pub fn variant_urls(original_url: &str, variants: &[ImageVariant]) -> BTreeMap<String, String> {
+    variants
+        .iter()
+        .map(|variant| {
+            let url = match (&variant.status, &variant.url) {
+                (ImageVariantStatus::Ready, Some(url)) => url.to_owned(),
+                _ => original_url.to_owned(),
+            };
+
+            (variant.name.to_owned(), url)
+        })
+        .collect()
+}
+
+/// An uploaded file that has already been written to storage.
+#[derive(Debug, Clone)]
+pub struct CreateAttachmentDto {
+    pub storage_key: String,
+    pub url: String,
+    pub filename: String,
+    pub content_type: String,
+    pub size: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AttachmentDto {
+    pub id: String,
+    pub url: String,
+    pub filename: String,
+
+    #[serde(rename = "contentType")]
+    pub content_type: String,
+
+    pub size: i64,
+
+    // Empty for files that are not images.
+    pub variants: BTreeMap<String, String>,
+
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl AttachmentDto {
+    pub fn filter_attachment(attachment: &Attachment) -> Self {
+        Self {
+            id: attachment.id.to_string(),
+            url: attachment.url.to_owned(),
+            filename: attachment.filename.to_owned(),
+            content_type: attachment.content_type.to_owned(),
+            size: attachment.size,
+            variants: variant_urls(&attachment.url, &attachment.variants),
+            created_at: attachment.created_at,
+        }
+    }
+
+    pub fn filter_post_attachments(post_id: uuid::Uuid, attachments: &[Attachment]) -> Vec<Self> {
+        attachments
+            .iter()
+            .filter(|attachment| attachment.post_id == post_id)
+            .map(Self::filter_attachment)
+            .collect()
+    }
+}
+
+#[derive(Deserialize)]
+pub struct GetAttachmentParamsDto {
+    pub post_id: uuid::Uuid,
+    pub attachment_id: uuid::Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AttachmentResponseDto {
+    pub status: u16,
+    pub attachment: AttachmentDto,
+}
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct LoginDto {
+    #[validate(length(min = 1, message = "Username is required"))]
+    pub username: String,
+
+    #[validate(length(min = 1, message = "Password is required"))]
+    pub password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TokenResponseDto {
+    pub status: u16,
+    pub token: String,
+}
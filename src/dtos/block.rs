@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SearchBlockQueryDto {
+    #[validate(range(min = 1, message = "Page must be at least 1"))]
+    pub page: Option<u32>,
+
+    #[validate(range(min = 1, max = 100, message = "Limit must be between 1 and 100"))]
+    pub limit: Option<usize>,
+}
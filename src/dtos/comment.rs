@@ -0,0 +1,108 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::models::Comment;
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct CreateCommentDto {
+    #[validate(length(
+        min = 1,
+        max = 2047,
+        message = "Body must be between 1 and 2047 characters"
+    ))]
+    pub body: String,
+
+    #[serde(rename = "parentId")]
+    pub parent_id: Option<uuid::Uuid>,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct UpdateCommentDto {
+    #[validate(length(
+        min = 1,
+        max = 2047,
+        message = "Body must be between 1 and 2047 characters"
+    ))]
+    pub body: String,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommentSort {
+    #[default]
+    Oldest,
+    Newest,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SearchCommentQueryDto {
+    pub sort: Option<CommentSort>,
+
+    #[validate(range(min = 1, message = "Page must be at least 1"))]
+    pub page: Option<u32>,
+
+    #[validate(range(min = 1, max = 100, message = "Limit must be between 1 and 100"))]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommentDto {
+    pub id: String,
+    pub body: String,
+
+    #[serde(rename = "postId")]
+    pub post_id: String,
+
+    #[serde(rename = "authorId")]
+    pub author_id: String,
+
+    #[serde(rename = "parentId")]
+    pub parent_id: Option<String>,
+
+    #[serde(rename = "repliesCount")]
+    pub replies_count: i64,
+
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl CommentDto {
+    pub fn filter_comment(comment: &Comment) -> Self {
+        Self {
+            id: comment.id.to_string(),
+            body: comment.body.to_owned(),
+            post_id: comment.post_id.to_string(),
+            author_id: comment.author_id.to_string(),
+            parent_id: comment.parent_id.map(|id| id.to_string()),
+            replies_count: comment.replies_count,
+            created_at: comment.created_at,
+            updated_at: comment.updated_at,
+        }
+    }
+
+    pub fn filter_comments(comments: &[Comment]) -> Vec<Self> {
+        comments.iter().map(Self::filter_comment).collect()
+    }
+}
+
+#[derive(Deserialize)]
+pub struct GetCommentParamsDto {
+    pub post_id: uuid::Uuid,
+    pub comment_id: uuid::Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommentResponseDto {
+    pub status: u16,
+    pub comment: CommentDto,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommentListResponseDto {
+    pub status: u16,
+    pub comments: Vec<CommentDto>,
+    pub results: usize,
+}
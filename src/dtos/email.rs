@@ -3,6 +3,8 @@ use validator::Validate;
 
 use crate::models::Email;
 
+use super::{validate_not_empty, PartialUpdate};
+
 #[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
 pub struct CreateEmailDto {
     #[validate(
@@ -27,23 +29,44 @@ pub struct UpdateEmailDto {
 }
 
 #[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+#[validate(schema(function = "validate_not_empty"))]
 pub struct UpdateEmailAddressDto {
     #[validate(email(message = "Email is invalid"))]
     pub address: Option<String>,
 }
 
+impl PartialUpdate for UpdateEmailAddressDto {
+    fn is_empty(&self) -> bool {
+        self.address.is_none()
+    }
+}
+
 #[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+#[validate(schema(function = "validate_not_empty"))]
 pub struct UpdateEmailPrivacyDto {
     #[serde(rename = "isPrivate")]
     pub is_private: Option<bool>,
 }
 
+impl PartialUpdate for UpdateEmailPrivacyDto {
+    fn is_empty(&self) -> bool {
+        self.is_private.is_none()
+    }
+}
+
 #[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+#[validate(schema(function = "validate_not_empty"))]
 pub struct UpdateEmailPrimaryStatusDto {
     #[serde(rename = "isPrimary")]
     pub is_primary: Option<bool>,
 }
 
+impl PartialUpdate for UpdateEmailPrimaryStatusDto {
+    fn is_empty(&self) -> bool {
+        self.is_primary.is_none()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EmailDto {
     pub id: String,
@@ -70,14 +93,24 @@ impl EmailDto {
         }
     }
 
+    #[allow(dead_code)]
     pub fn filter_emails(emails: &[Email], remove_owner_id: bool) -> Vec<Self> {
         emails
             .iter()
             .map(|e| Self::filter_email(e, remove_owner_id))
             .collect()
     }
+
+    pub fn filter_public_emails(emails: &[Email], remove_owner_id: bool) -> Vec<Self> {
+        emails
+            .iter()
+            .filter(|e| !e.is_private)
+            .map(|e| Self::filter_email(e, remove_owner_id))
+            .collect()
+    }
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PersonalEmailDto {
     pub id: String,
@@ -93,6 +126,7 @@ pub struct PersonalEmailDto {
     pub is_verified: bool,
 }
 
+#[allow(dead_code)]
 impl PersonalEmailDto {
     pub fn filter_email(email: &Email) -> Self {
         Self {
@@ -105,18 +139,18 @@ impl PersonalEmailDto {
     }
 
     pub fn filter_emails(emails: &[Email]) -> Vec<Self> {
-        emails.iter().map(|e| Self::filter_email(e)).collect()
+        emails.iter().map(Self::filter_email).collect()
     }
 }
 
 #[derive(Deserialize)]
 pub struct GetEmailByIdParamsDto {
-    pub id: String,
+    pub id: uuid::Uuid,
 }
 
 #[derive(Deserialize)]
 pub struct GetEmailsByOwnerIdParamsDto {
-    pub owner_id: String,
+    pub owner_id: uuid::Uuid,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
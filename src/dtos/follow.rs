@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::models::{Follow, FollowStatus};
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SearchFollowQueryDto {
+    #[validate(range(min = 1, message = "Page must be at least 1"))]
+    pub page: Option<u32>,
+
+    #[validate(range(min = 1, max = 100, message = "Limit must be between 1 and 100"))]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FollowDto {
+    #[serde(rename = "followerId")]
+    pub follower_id: String,
+
+    #[serde(rename = "followeeId")]
+    pub followee_id: String,
+
+    pub status: FollowStatus,
+
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "acceptedAt")]
+    pub accepted_at: Option<DateTime<Utc>>,
+}
+
+impl FollowDto {
+    pub fn filter_follow(follow: &Follow) -> Self {
+        Self {
+            follower_id: follow.follower_id.to_string(),
+            followee_id: follow.followee_id.to_string(),
+            status: follow.status,
+            created_at: follow.created_at,
+            accepted_at: follow.accepted_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct GetFollowerParamsDto {
+    pub user_id: uuid::Uuid,
+    pub follower_id: uuid::Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FollowResponseDto {
+    pub status: u16,
+    pub follow: FollowDto,
+}
@@ -1,3 +1,41 @@
+use validator::ValidationError;
+
+use crate::utils::cursor::Cursor;
+
+pub mod attachment;
+pub mod auth;
+pub mod block;
+pub mod comment;
 pub mod email;
+pub mod follow;
+pub mod notification;
 pub mod person;
 pub mod post;
+pub mod revision;
+pub mod tag;
+pub mod ws;
+
+/// Bodies of `PATCH` requests, where every field is optional.
+pub trait PartialUpdate {
+    fn is_empty(&self) -> bool;
+}
+
+pub fn validate_not_empty<T: PartialUpdate>(dto: &T) -> Result<(), ValidationError> {
+    if dto.is_empty() {
+        let mut error = ValidationError::new("empty");
+        error.message = Some("At least one field must be provided".into());
+        return Err(error);
+    }
+
+    Ok(())
+}
+
+pub fn validate_cursor(cursor: &str) -> Result<(), ValidationError> {
+    if Cursor::decode(cursor).is_none() {
+        let mut error = ValidationError::new("cursor");
+        error.message = Some("Cursor is not valid".into());
+        return Err(error);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,158 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::models::{Notification, NotificationKind, NotificationPayload};
+
+use super::{validate_cursor, validate_not_empty, PartialUpdate};
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SearchNotificationQueryDto {
+    // Only lists the notifications that were not read yet.
+    pub unread: Option<bool>,
+
+    // Returned as `nextCursor` by the previous page.
+    #[validate(custom(function = "validate_cursor"))]
+    pub cursor: Option<String>,
+
+    #[validate(range(min = 1, max = 100, message = "Limit must be between 1 and 100"))]
+    pub limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub struct GetNotificationParamsDto {
+    pub notification_id: uuid::Uuid,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationDto {
+    pub id: String,
+
+    #[serde(rename = "actorId")]
+    pub actor_id: String,
+
+    // Adds `kind` and the fields that go with it.
+    #[serde(flatten)]
+    pub payload: NotificationPayload,
+
+    #[serde(rename = "readAt")]
+    pub read_at: Option<DateTime<Utc>>,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl NotificationDto {
+    pub fn filter_notification(notification: &Notification) -> Self {
+        Self {
+            id: notification.id.to_string(),
+            actor_id: notification.actor_id.to_string(),
+            payload: notification.payload.0.clone(),
+            read_at: notification.read_at,
+            created_at: notification.created_at,
+        }
+    }
+
+    pub fn filter_notifications(notifications: &[Notification]) -> Vec<Self> {
+        notifications
+            .iter()
+            .map(NotificationDto::filter_notification)
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NotificationListResponseDto {
+    pub status: u16,
+    pub notifications: Vec<NotificationDto>,
+    pub results: usize,
+    #[serde(rename = "unreadCount")]
+    pub unread_count: i64,
+    // `None` on the last page.
+    #[serde(rename = "nextCursor")]
+    pub next_cursor: Option<String>,
+}
+
+/// Whether each kind of notification is turned on.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NotificationPreferencesDto {
+    pub comment: bool,
+    pub reply: bool,
+    pub follow: bool,
+    #[serde(rename = "followRequest")]
+    pub follow_request: bool,
+    #[serde(rename = "followAccepted")]
+    pub follow_accepted: bool,
+    pub reaction: bool,
+}
+
+impl NotificationPreferencesDto {
+    pub fn from_disabled(disabled: &[NotificationKind]) -> Self {
+        let is_enabled = |kind: NotificationKind| !disabled.contains(&kind);
+
+        Self {
+            comment: is_enabled(NotificationKind::Comment),
+            reply: is_enabled(NotificationKind::Reply),
+            follow: is_enabled(NotificationKind::Follow),
+            follow_request: is_enabled(NotificationKind::FollowRequest),
+            follow_accepted: is_enabled(NotificationKind::FollowAccepted),
+            reaction: is_enabled(NotificationKind::Reaction),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NotificationPreferencesResponseDto {
+    pub status: u16,
+    pub preferences: NotificationPreferencesDto,
+}
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+#[validate(schema(function = "validate_not_empty"))]
+pub struct UpdateNotificationPreferencesDto {
+    pub comment: Option<bool>,
+    pub reply: Option<bool>,
+    pub follow: Option<bool>,
+    #[serde(rename = "followRequest")]
+    pub follow_request: Option<bool>,
+    #[serde(rename = "followAccepted")]
+    pub follow_accepted: Option<bool>,
+    pub reaction: Option<bool>,
+}
+
+impl UpdateNotificationPreferencesDto {
+    /// Splits the given kinds into the ones to turn on and the ones to turn off.
+    pub fn changes(&self) -> (Vec<NotificationKind>, Vec<NotificationKind>) {
+        let preferences = [
+            (NotificationKind::Comment, self.comment),
+            (NotificationKind::Reply, self.reply),
+            (NotificationKind::Follow, self.follow),
+            (NotificationKind::FollowRequest, self.follow_request),
+            (NotificationKind::FollowAccepted, self.follow_accepted),
+            (NotificationKind::Reaction, self.reaction),
+        ];
+
+        let mut enabled = vec![];
+        let mut disabled = vec![];
+
+        for (kind, is_enabled) in preferences {
+            match is_enabled {
+                Some(true) => enabled.push(kind),
+                Some(false) => disabled.push(kind),
+                None => {}
+            }
+        }
+
+        (enabled, disabled)
+    }
+}
+
+impl PartialUpdate for UpdateNotificationPreferencesDto {
+    fn is_empty(&self) -> bool {
+        self.comment.is_none()
+            && self.reply.is_none()
+            && self.follow.is_none()
+            && self.follow_request.is_none()
+            && self.follow_accepted.is_none()
+            && self.reaction.is_none()
+    }
+}
@@ -1,12 +1,14 @@
-use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
-use crate::models::{Admin, User};
+use crate::models::{Admin, Gender, User};
 
-use super::email::EmailDto;
+use super::{attachment::variant_urls, email::EmailDto, validate_not_empty, PartialUpdate};
 
-#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Validate, Debug, Clone, Serialize, Deserialize)]
 pub struct CreateUserDto {
     #[validate(length(min = 1, message = "Firstname is required"))]
     pub firstname: String,
@@ -23,11 +25,24 @@ pub struct CreateUserDto {
     )]
     pub email: String,
 
-    #[validate(length(min = 1, message = "Birthdate is required"))]
-    pub birthdate: String,
+    #[validate(custom(function = "validate_birthdate", arg = "u32"))]
+    pub birthdate: NaiveDate,
+
+    pub gender: Option<Gender>,
 
-    #[validate(length(min = 1, message = "Gender is required"))]
-    pub gender: String,
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Gender description must be between 1 and 100 characters"
+    ))]
+    pub gender_description: Option<String>,
+
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "Pronouns must be between 1 and 50 characters"
+    ))]
+    pub pronouns: Option<String>,
 
     pub is_profile_private: Option<bool>,
 
@@ -39,7 +54,7 @@ pub struct CreateUserDto {
     pub password: String,
 }
 
-#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Validate, Debug, Clone, Serialize, Deserialize)]
 pub struct CreateAdminDto {
     #[validate(length(min = 1, message = "Firstname is required"))]
     pub firstname: String,
@@ -56,11 +71,24 @@ pub struct CreateAdminDto {
     )]
     pub email: String,
 
-    #[validate(length(min = 1, message = "Birthdate is required"))]
-    pub birthdate: String,
+    #[validate(custom(function = "validate_birthdate", arg = "u32"))]
+    pub birthdate: NaiveDate,
 
-    #[validate(length(min = 1, message = "Gender is required"))]
-    pub gender: String,
+    pub gender: Option<Gender>,
+
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Gender description must be between 1 and 100 characters"
+    ))]
+    pub gender_description: Option<String>,
+
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "Pronouns must be between 1 and 50 characters"
+    ))]
+    pub pronouns: Option<String>,
 
     #[validate(
         length(min = 1, message = "Password is required"),
@@ -70,13 +98,37 @@ pub struct CreateAdminDto {
     pub password: String,
 }
 
+/// Rejects birthdates in the future and people younger than `min_age` years.
+pub fn validate_birthdate(birthdate: &NaiveDate, min_age: u32) -> Result<(), ValidationError> {
+    let today = Utc::now().date_naive();
+
+    if *birthdate > today {
+        let mut error = ValidationError::new("future_date");
+        error.message = Some("Birthdate cannot be in the future".into());
+        return Err(error);
+    }
+
+    let age = today.years_since(*birthdate).unwrap_or(0);
+
+    if age < min_age {
+        let mut error = ValidationError::new("min_age");
+        error.message = Some(format!("You must be at least {} years old", min_age).into());
+        error.add_param("min_age".into(), &min_age);
+        return Err(error);
+    }
+
+    Ok(())
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct UpdateUserDto {
     pub firstname: Option<String>,
     pub lastname: Option<String>,
     pub username: Option<String>,
-    pub birthdate: Option<String>,
-    pub gender: Option<String>,
+    pub birthdate: Option<NaiveDate>,
+    pub gender: Option<Gender>,
+    pub gender_description: Option<String>,
+    pub pronouns: Option<String>,
     pub biography: Option<String>,
     pub is_profile_private: Option<bool>,
 }
@@ -86,32 +138,146 @@ pub struct UpdateAdminDto {
     pub firstname: Option<String>,
     pub lastname: Option<String>,
     pub username: Option<String>,
-    pub birthdate: Option<String>,
-    pub gender: Option<String>,
+    pub birthdate: Option<NaiveDate>,
+    pub gender: Option<Gender>,
+    pub gender_description: Option<String>,
+    pub pronouns: Option<String>,
 }
 
 #[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+#[validate(schema(function = "validate_not_empty"))]
 pub struct UpdateUserPublicInfoDto {
+    #[validate(length(min = 1, message = "Firstname cannot be empty"))]
     pub firstname: Option<String>,
+
+    #[validate(length(min = 1, message = "Lastname cannot be empty"))]
     pub lastname: Option<String>,
+
+    #[validate(length(min = 1, message = "Username cannot be empty"))]
     pub username: Option<String>,
-    pub gender: Option<String>,
-    pub birthdate: Option<String>,
+
+    pub gender: Option<Gender>,
+
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Gender description must be between 1 and 100 characters"
+    ))]
+    pub gender_description: Option<String>,
+
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "Pronouns must be between 1 and 50 characters"
+    ))]
+    pub pronouns: Option<String>,
+
+    #[validate(custom(function = "validate_birthdate", arg = "u32"))]
+    pub birthdate: Option<NaiveDate>,
+
+    #[validate(length(max = 1024, message = "Biography cannot be more than 1024 characters"))]
     pub biography: Option<String>,
 }
 
+impl PartialUpdate for UpdateUserPublicInfoDto {
+    fn is_empty(&self) -> bool {
+        self.firstname.is_none()
+            && self.lastname.is_none()
+            && self.username.is_none()
+            && self.gender.is_none()
+            && self.gender_description.is_none()
+            && self.pronouns.is_none()
+            && self.birthdate.is_none()
+            && self.biography.is_none()
+    }
+}
+
+impl From<UpdateUserPublicInfoDto> for UpdateUserDto {
+    fn from(dto: UpdateUserPublicInfoDto) -> Self {
+        Self {
+            firstname: dto.firstname,
+            lastname: dto.lastname,
+            username: dto.username,
+            birthdate: dto.birthdate,
+            gender: dto.gender,
+            gender_description: dto.gender_description,
+            pronouns: dto.pronouns,
+            biography: dto.biography,
+            is_profile_private: None,
+        }
+    }
+}
+
 #[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
 pub struct UpdateUserProfileStatusDto {
     pub is_profile_private: bool,
 }
 
+impl From<UpdateUserProfileStatusDto> for UpdateUserDto {
+    fn from(dto: UpdateUserProfileStatusDto) -> Self {
+        Self {
+            is_profile_private: Some(dto.is_profile_private),
+            ..Default::default()
+        }
+    }
+}
+
 #[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+#[validate(schema(function = "validate_not_empty"))]
 pub struct UpdateAdminPublicInfoDto {
+    #[validate(length(min = 1, message = "Firstname cannot be empty"))]
     pub firstname: Option<String>,
+
+    #[validate(length(min = 1, message = "Lastname cannot be empty"))]
     pub lastname: Option<String>,
+
+    #[validate(length(min = 1, message = "Username cannot be empty"))]
     pub username: Option<String>,
-    pub birthdate: Option<String>,
-    pub gender: Option<String>,
+
+    #[validate(custom(function = "validate_birthdate", arg = "u32"))]
+    pub birthdate: Option<NaiveDate>,
+
+    pub gender: Option<Gender>,
+
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Gender description must be between 1 and 100 characters"
+    ))]
+    pub gender_description: Option<String>,
+
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "Pronouns must be between 1 and 50 characters"
+    ))]
+    pub pronouns: Option<String>,
+}
+
+impl PartialUpdate for UpdateAdminPublicInfoDto {
+    fn is_empty(&self) -> bool {
+        self.firstname.is_none()
+            && self.lastname.is_none()
+            && self.username.is_none()
+            && self.birthdate.is_none()
+            && self.gender.is_none()
+            && self.gender_description.is_none()
+            && self.pronouns.is_none()
+    }
+}
+
+impl From<UpdateAdminPublicInfoDto> for UpdateAdminDto {
+    fn from(dto: UpdateAdminPublicInfoDto) -> Self {
+        Self {
+            firstname: dto.firstname,
+            lastname: dto.lastname,
+            username: dto.username,
+            birthdate: dto.birthdate,
+            gender: dto.gender,
+            gender_description: dto.gender_description,
+            pronouns: dto.pronouns,
+        }
+    }
 }
 
 #[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
@@ -138,14 +304,27 @@ pub struct UserDto {
     pub username: String,
     pub firstname: String,
     pub lastname: String,
-    pub gender: String,
+    pub gender: Option<String>,
+    #[serde(rename = "genderDescription")]
+    pub gender_description: Option<String>,
+    pub pronouns: Option<String>,
     pub biography: String,
-    pub birthdate: DateTime<Utc>,
+    pub birthdate: NaiveDate,
+    #[serde(rename = "avatarUrl")]
+    pub avatar_url: Option<String>,
+    #[serde(rename = "avatarVariants")]
+    pub avatar_variants: BTreeMap<String, String>,
+    #[serde(rename = "followersCount")]
+    pub followers_count: i64,
+    #[serde(rename = "followingCount")]
+    pub following_count: i64,
     pub emails: Vec<EmailDto>,
     #[serde(rename = "createdAt")]
     pub created_at: DateTime<Utc>,
     #[serde(rename = "updatedAt")]
     pub updated_at: DateTime<Utc>,
+    #[serde(rename = "deletedAt")]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 impl UserDto {
@@ -156,10 +335,21 @@ impl UserDto {
             lastname: user.lastname.to_owned(),
             username: user.username.to_owned(),
             birthdate: user.birthdate,
-            gender: user.gender.to_string(),
-            created_at: user.created_at.unwrap(),
-            updated_at: user.updated_at.unwrap(),
-            emails: EmailDto::filter_emails(&user.emails, true),
+            gender: user.gender.map(|g| g.to_string()),
+            gender_description: user.gender_description.to_owned(),
+            pronouns: user.pronouns.to_owned(),
+            avatar_url: user.avatar_url.to_owned(),
+            avatar_variants: user
+                .avatar_url
+                .as_ref()
+                .map(|url| variant_urls(url, &user.avatar_variants))
+                .unwrap_or_default(),
+            followers_count: user.followers_count,
+            following_count: user.following_count,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+            deleted_at: user.deleted_at,
+            emails: EmailDto::filter_public_emails(&user.emails, true),
             biography: if let Some(bio) = &user.biography {
                 bio.to_owned()
             } else {
@@ -179,13 +369,18 @@ pub struct AdminDto {
     pub username: String,
     pub firstname: String,
     pub lastname: String,
-    pub gender: String,
-    pub birthdate: DateTime<Utc>,
+    pub gender: Option<String>,
+    #[serde(rename = "genderDescription")]
+    pub gender_description: Option<String>,
+    pub pronouns: Option<String>,
+    pub birthdate: NaiveDate,
     pub emails: Vec<EmailDto>,
     #[serde(rename = "createdAt")]
     pub created_at: DateTime<Utc>,
     #[serde(rename = "updatedAt")]
     pub updated_at: DateTime<Utc>,
+    #[serde(rename = "deletedAt")]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 impl AdminDto {
@@ -196,10 +391,13 @@ impl AdminDto {
             lastname: admin.lastname.to_owned(),
             username: admin.username.to_owned(),
             birthdate: admin.birthdate,
-            gender: admin.gender.to_string(),
-            emails: EmailDto::filter_emails(&admin.emails, true),
-            created_at: admin.created_at.unwrap(),
-            updated_at: admin.updated_at.unwrap(),
+            gender: admin.gender.map(|g| g.to_string()),
+            gender_description: admin.gender_description.to_owned(),
+            pronouns: admin.pronouns.to_owned(),
+            emails: EmailDto::filter_public_emails(&admin.emails, true),
+            created_at: admin.created_at,
+            updated_at: admin.updated_at,
+            deleted_at: admin.deleted_at,
         }
     }
 
@@ -210,7 +408,7 @@ impl AdminDto {
 
 #[derive(Deserialize)]
 pub struct GetUserParamsDto {
-    pub user_id: String,
+    pub user_id: uuid::Uuid,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -225,3 +423,21 @@ pub struct UserListResponseDto {
     pub users: Vec<UserDto>,
     pub results: usize,
 }
+
+#[derive(Deserialize)]
+pub struct GetAdminParamsDto {
+    pub admin_id: uuid::Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdminResponseDto {
+    pub status: u16,
+    pub admin: AdminDto,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdminListResponseDto {
+    pub status: u16,
+    pub admins: Vec<AdminDto>,
+    pub results: usize,
+}
@@ -1,61 +1,271 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use validator::Validate;
+use validator::{Validate, ValidationError};
+
+use crate::{
+    models::{Attachment, Post, PostReactionCount, PostStatus, PostTag, ReactionKind},
+    utils::slug::slugify,
+};
 
-use crate::models::Post;
+use super::{
+    attachment::AttachmentDto, tag::PostTagDto, validate_cursor, validate_not_empty, PartialUpdate,
+};
+
+pub const MAX_TAGS_PER_POST: usize = 10;
+pub const MAX_TAG_LENGTH: usize = 50;
 
 #[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
 pub struct CreatePostDto {
     #[validate(length(min = 1, message = "Title is required"))]
     pub title: String,
 
-    #[validate(length(min = 1, message = "Description is required"))]
+    // CommonMark, rendered to sanitized HTML when the post is saved.
+    #[validate(length(
+        min = 1,
+        max = 100000,
+        message = "Description must be between 1 and 100000 characters"
+    ))]
     pub description: String,
+
+    #[validate(custom(function = "validate_tags"))]
+    pub tags: Option<Vec<String>>,
+
+    // New posts are drafts unless they are published right away or scheduled.
+    pub publish: Option<bool>,
+
+    #[serde(rename = "scheduledAt")]
+    #[validate(custom(function = "validate_scheduled_at"))]
+    pub scheduled_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+#[validate(schema(function = "validate_not_empty"))]
 pub struct UpdatePostDto {
+    #[validate(length(min = 1, message = "Title is required"))]
     pub title: Option<String>,
+
+    #[validate(length(
+        min = 1,
+        max = 100000,
+        message = "Description must be between 1 and 100000 characters"
+    ))]
     pub description: Option<String>,
+
+    // Replaces every tag of the post when present.
+    #[validate(custom(function = "validate_tags"))]
+    pub tags: Option<Vec<String>>,
+}
+
+impl PartialUpdate for UpdatePostDto {
+    fn is_empty(&self) -> bool {
+        self.title.is_none() && self.description.is_none() && self.tags.is_none()
+    }
 }
 
 #[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
 pub struct SearchPostQueryDto {
     pub title: Option<String>,
+
+    // Defaults to published posts, drafts are only listed for their author.
+    pub status: Option<PostStatus>,
+
+    // Tag filters take slugs or names, `tags_any` and `tags_all` are comma separated.
+    pub tag: Option<String>,
+    pub tags_any: Option<String>,
+    pub tags_all: Option<String>,
+
     pub page: Option<u32>,
     pub limit: Option<usize>,
 }
 
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct FeedQueryDto {
+    // Returned as `nextCursor` by the previous page.
+    #[validate(custom(function = "validate_cursor"))]
+    pub cursor: Option<String>,
+
+    #[validate(range(min = 1, max = 100, message = "Limit must be between 1 and 100"))]
+    pub limit: Option<usize>,
+}
+
+impl SearchPostQueryDto {
+    pub fn tag_slugs(value: &str) -> Vec<String> {
+        let mut slugs: Vec<String> = value
+            .split(',')
+            .map(slugify)
+            .filter(|slug| !slug.is_empty())
+            .collect();
+
+        slugs.sort();
+        slugs.dedup();
+        slugs
+    }
+}
+
+pub fn validate_scheduled_at(scheduled_at: &DateTime<Utc>) -> Result<(), ValidationError> {
+    if *scheduled_at <= Utc::now() {
+        let mut error = ValidationError::new("past_date");
+        error.message = Some("Scheduled time must be in the future".into());
+        return Err(error);
+    }
+
+    Ok(())
+}
+
+pub fn validate_tags(tags: &[String]) -> Result<(), ValidationError> {
+    if tags.len() > MAX_TAGS_PER_POST {
+        let mut error = ValidationError::new("max_tags");
+        error.message =
+            Some(format!("A post cannot have more than {} tags", MAX_TAGS_PER_POST).into());
+        return Err(error);
+    }
+
+    for tag in tags {
+        let slug = slugify(tag);
+
+        if slug.is_empty() || tag.trim().chars().count() > MAX_TAG_LENGTH {
+            let mut error = ValidationError::new("tag");
+            error.message = Some(
+                format!(
+                    "Tags must contain a letter or digit and be at most {} characters",
+                    MAX_TAG_LENGTH
+                )
+                .into(),
+            );
+            return Err(error);
+        }
+    }
+
+    Ok(())
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PostDto {
     pub id: String,
     pub title: String,
-    pub description: String,
+    pub slug: String,
+
+    // Left out of list views, which carry the excerpt instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "descriptionHtml", skip_serializing_if = "Option::is_none")]
+    pub description_html: Option<String>,
+
+    pub excerpt: String,
+    #[serde(rename = "readingTime")]
+    pub reading_time: i32,
+
+    pub status: PostStatus,
+
+    #[serde(rename = "authorId")]
+    pub author_id: Option<String>,
+
+    pub tags: Vec<PostTagDto>,
+
+    pub attachments: Vec<AttachmentDto>,
+
+    #[serde(rename = "likesCount")]
+    pub likes_count: i64,
+
+    #[serde(rename = "reactionCounts")]
+    pub reaction_counts: BTreeMap<ReactionKind, i64>,
+
+    #[serde(rename = "hasReacted")]
+    pub has_reacted: bool,
+
+    #[serde(rename = "viewerReactions")]
+    pub viewer_reactions: Vec<ReactionKind>,
+
+    #[serde(rename = "publishedAt")]
+    pub published_at: Option<DateTime<Utc>>,
+    #[serde(rename = "scheduledAt")]
+    pub scheduled_at: Option<DateTime<Utc>>,
     #[serde(rename = "createdAt")]
     pub created_at: DateTime<Utc>,
     #[serde(rename = "updatedAt")]
     pub updated_at: DateTime<Utc>,
+    #[serde(rename = "deletedAt")]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 impl PostDto {
-    pub fn filter_post(post: &Post) -> Self {
+    /// `tags`, `attachments` and `reactions` may hold rows for other posts too,
+    /// only this post's are used.
+    pub fn filter_post(
+        post: &Post,
+        tags: &[PostTag],
+        attachments: &[Attachment],
+        reactions: &[PostReactionCount],
+    ) -> Self {
+        let reactions: Vec<&PostReactionCount> =
+            reactions.iter().filter(|r| r.post_id == post.id).collect();
+
+        let viewer_reactions: Vec<ReactionKind> = reactions
+            .iter()
+            .filter(|r| r.viewer_reacted)
+            .map(|r| r.kind)
+            .collect();
+
         Self {
             id: post.id.to_string(),
             title: post.title.to_owned(),
-            description: post.description.to_owned(),
-            created_at: post.created_at.unwrap(),
-            updated_at: post.updated_at.unwrap(),
+            slug: post.slug.to_owned(),
+            description: Some(post.description.to_owned()),
+            description_html: Some(post.description_html.to_owned()),
+            excerpt: post.excerpt.to_owned(),
+            reading_time: post.reading_time,
+            status: post.status,
+            author_id: post.author_id.map(|id| id.to_string()),
+            tags: PostTagDto::filter_post_tags(post.id, tags),
+            attachments: AttachmentDto::filter_post_attachments(post.id, attachments),
+            likes_count: reactions
+                .iter()
+                .find(|r| r.kind == ReactionKind::Like)
+                .map_or(0, |r| r.count),
+            reaction_counts: reactions.iter().map(|r| (r.kind, r.count)).collect(),
+            has_reacted: !viewer_reactions.is_empty(),
+            viewer_reactions,
+            published_at: post.published_at,
+            scheduled_at: post.scheduled_at,
+            created_at: post.created_at,
+            updated_at: post.updated_at,
+            deleted_at: post.deleted_at,
         }
     }
 
-    pub fn filter_posts(posts: &[Post]) -> Vec<Self> {
-        posts.iter().map(Self::filter_post).collect()
+    pub fn filter_posts(
+        posts: &[Post],
+        tags: &[PostTag],
+        attachments: &[Attachment],
+        reactions: &[PostReactionCount],
+    ) -> Vec<Self> {
+        posts
+            .iter()
+            .map(|post| Self {
+                description: None,
+                description_html: None,
+                ..Self::filter_post(post, tags, attachments, reactions)
+            })
+            .collect()
     }
 }
 
 #[derive(Deserialize)]
 pub struct GetPostParamsDto {
-    pub post_id: String,
+    pub post_id: uuid::Uuid,
+}
+
+#[derive(Deserialize)]
+pub struct GetPostSlugParamsDto {
+    pub slug: String,
+}
+
+#[derive(Deserialize)]
+pub struct GetReactionParamsDto {
+    pub post_id: uuid::Uuid,
+    pub kind: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -70,3 +280,13 @@ pub struct PostListResponseDto {
     pub posts: Vec<PostDto>,
     pub results: usize,
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FeedResponseDto {
+    pub status: u16,
+    pub posts: Vec<PostDto>,
+    pub results: usize,
+    // `None` on the last page.
+    #[serde(rename = "nextCursor")]
+    pub next_cursor: Option<String>,
+}
@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{models::PostRevision, utils::diff::DiffChange};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PostRevisionDto {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+
+    #[serde(rename = "postId")]
+    pub post_id: String,
+
+    #[serde(rename = "editorId")]
+    pub editor_id: Option<String>,
+
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl PostRevisionDto {
+    pub fn filter_revision(revision: &PostRevision) -> Self {
+        Self {
+            id: revision.id.to_string(),
+            title: revision.title.to_owned(),
+            description: revision.description.to_owned(),
+            post_id: revision.post_id.to_string(),
+            editor_id: revision.editor_id.map(|id| id.to_string()),
+            created_at: revision.created_at,
+        }
+    }
+
+    pub fn filter_revisions(revisions: &[PostRevision]) -> Vec<Self> {
+        revisions.iter().map(Self::filter_revision).collect()
+    }
+}
+
+#[derive(Deserialize)]
+pub struct GetRevisionParamsDto {
+    pub post_id: uuid::Uuid,
+    pub revision_id: uuid::Uuid,
+}
+
+// `to` defaults to the current version of the post.
+#[derive(Debug, Deserialize)]
+pub struct RevisionDiffQueryDto {
+    pub from: uuid::Uuid,
+    pub to: Option<uuid::Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevisionDiffDto {
+    pub from: String,
+    pub to: Option<String>,
+    pub title: Vec<DiffChange>,
+    pub description: Vec<DiffChange>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevisionListResponseDto {
+    pub status: u16,
+    pub revisions: Vec<PostRevisionDto>,
+    pub results: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevisionDiffResponseDto {
+    pub status: u16,
+    pub diff: RevisionDiffDto,
+}
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::models::{PostTag, Tag};
+
+#[derive(Validate, Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SearchTagQueryDto {
+    pub name: Option<String>,
+
+    #[validate(range(min = 1, message = "Page must be at least 1"))]
+    pub page: Option<u32>,
+
+    #[validate(range(min = 1, max = 100, message = "Limit must be between 1 and 100"))]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagDto {
+    pub id: String,
+    pub name: String,
+    pub slug: String,
+
+    #[serde(rename = "postsCount")]
+    pub posts_count: i64,
+}
+
+impl TagDto {
+    pub fn filter_tag(tag: &Tag) -> Self {
+        Self {
+            id: tag.id.to_string(),
+            name: tag.name.to_owned(),
+            slug: tag.slug.to_owned(),
+            posts_count: tag.posts_count,
+        }
+    }
+
+    pub fn filter_tags(tags: &[Tag]) -> Vec<Self> {
+        tags.iter().map(Self::filter_tag).collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PostTagDto {
+    pub name: String,
+    pub slug: String,
+}
+
+impl PostTagDto {
+    pub fn filter_post_tags(post_id: uuid::Uuid, tags: &[PostTag]) -> Vec<Self> {
+        tags.iter()
+            .filter(|tag| tag.post_id == post_id)
+            .map(|tag| Self {
+                name: tag.name.to_owned(),
+                slug: tag.slug.to_owned(),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagListResponseDto {
+    pub status: u16,
+    pub tags: Vec<TagDto>,
+    pub results: usize,
+}
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+pub struct WsQueryDto {
+    // Browsers can't set headers on WebSocket requests, so the token may be
+    // passed here instead of in `Authorization`. The access log redacts it.
+    pub token: Option<String>,
+}
+
+/// Messages clients send over the socket, such as
+/// `{"action": "subscribe", "topic": "post:<id>"}`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum WsClientMessage {
+    Subscribe { topic: String },
+    Unsubscribe { topic: String },
+}
+
+/// Answers to client messages, events are sent as they are.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WsReplyDto {
+    Subscribed { topic: String },
+    Unsubscribed { topic: String },
+    Error { message: String },
+}
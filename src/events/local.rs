@@ -0,0 +1,94 @@
+use std::{collections::VecDeque, sync::Mutex};
+
+use chrono::Utc;
+use tokio::sync::broadcast;
+
+use super::{Event, EventHub, EventId, LoggedEvent};
+
+/// Keeps events in memory, for a single server instance.
+#[derive(Debug)]
+pub struct LocalEventHub {
+    sender: broadcast::Sender<LoggedEvent>,
+    log: Mutex<EventLog>,
+}
+
+#[derive(Debug)]
+struct EventLog {
+    epoch: i64,
+    next_seq: u64,
+    capacity: usize,
+    events: VecDeque<LoggedEvent>,
+}
+
+impl LocalEventHub {
+    pub fn new(capacity: usize, log_capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+
+        Self {
+            sender,
+            log: Mutex::new(EventLog {
+                epoch: Utc::now().timestamp_millis(),
+                next_seq: 1,
+                capacity: log_capacity,
+                events: VecDeque::with_capacity(log_capacity),
+            }),
+        }
+    }
+}
+
+impl EventHub for LocalEventHub {
+    fn publish(&self, event: Event) {
+        // Sending while holding the lock keeps subscribers receiving events in
+        // the order of their ids.
+        let mut log = self.log.lock().unwrap_or_else(|e| e.into_inner());
+
+        let logged = LoggedEvent {
+            id: EventId {
+                epoch: log.epoch,
+                seq: log.next_seq,
+            },
+            event,
+        };
+        log.next_seq += 1;
+
+        if log.capacity > 0 {
+            if log.events.len() >= log.capacity {
+                log.events.pop_front();
+            }
+            log.events.push_back(logged.clone());
+        }
+
+        // Only fails when nobody is listening.
+        let _ = self.sender.send(logged);
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<LoggedEvent> {
+        self.sender.subscribe()
+    }
+
+    fn events_after(&self, id: EventId) -> Option<Vec<LoggedEvent>> {
+        let log = self.log.lock().unwrap_or_else(|e| e.into_inner());
+
+        if id.epoch != log.epoch || id.seq >= log.next_seq {
+            return None;
+        }
+
+        let oldest_seq = log
+            .events
+            .front()
+            .map(|logged| logged.id.seq)
+            .unwrap_or(log.next_seq);
+
+        if id.seq + 1 < oldest_seq {
+            return None;
+        }
+
+        Some(
+            log.events
+                .iter()
+                .filter(|logged| logged.id.seq > id.seq)
+                .cloned()
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,185 @@
+use std::{collections::HashSet, fmt, str::FromStr, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::{dtos::notification::NotificationDto, models::Post};
+
+pub mod local;
+
+/// How many events a slow subscriber may fall behind before it misses some.
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// How many past events are kept for clients resuming a stream.
+const LOG_CAPACITY: usize = 1024;
+
+/// Fans events out to the subscribers of this server instance.
+///
+/// Events are only delivered to subscribers of the same instance. A backend
+/// relaying them through Postgres `LISTEN/NOTIFY` would share them between
+/// instances, which is why events are plain serializable values.
+pub trait EventHub: fmt::Debug + Send + Sync {
+    /// Numbers `event`, logs it and sends it to every current subscriber.
+    fn publish(&self, event: Event);
+
+    /// Receives the events published from now on.
+    fn subscribe(&self) -> broadcast::Receiver<LoggedEvent>;
+
+    /// Logged events published after `id`, oldest first. `None` when some of
+    /// them are no longer kept or `id` was issued before the server restarted.
+    fn events_after(&self, id: EventId) -> Option<Vec<LoggedEvent>>;
+}
+
+/// Position of an event in the log, written `<epoch>-<seq>`.
+///
+/// The epoch tells apart the ids issued by each run of the server, since the
+/// sequence starts over on restart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EventId {
+    pub epoch: i64,
+    pub seq: u64,
+}
+
+impl fmt::Display for EventId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.epoch, self.seq)
+    }
+}
+
+impl FromStr for EventId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid event id: {}", s);
+        let (epoch, seq) = s.split_once('-').ok_or_else(invalid)?;
+
+        Ok(EventId {
+            epoch: epoch.parse().map_err(|_| invalid())?,
+            seq: seq.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// An event along with its id.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoggedEvent {
+    pub id: EventId,
+    pub event: Event,
+}
+
+/// Something that happened, as pushed to clients.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum Event {
+    PostCreated {
+        post_id: Uuid,
+        author_id: Option<Uuid>,
+    },
+    PostUpdated {
+        post_id: Uuid,
+        author_id: Option<Uuid>,
+    },
+    PostDeleted {
+        post_id: Uuid,
+        author_id: Option<Uuid>,
+    },
+    /// Only delivered to its recipient, who does not need to subscribe.
+    Notification {
+        recipient_id: Uuid,
+        notification: NotificationDto,
+    },
+}
+
+impl Event {
+    pub fn post_created(post: &Post) -> Self {
+        Event::PostCreated {
+            post_id: post.id,
+            author_id: post.author_id,
+        }
+    }
+
+    pub fn post_updated(post: &Post) -> Self {
+        Event::PostUpdated {
+            post_id: post.id,
+            author_id: post.author_id,
+        }
+    }
+
+    pub fn post_deleted(post: &Post) -> Self {
+        Event::PostDeleted {
+            post_id: post.id,
+            author_id: post.author_id,
+        }
+    }
+
+    /// Topics whose subscribers receive the event.
+    pub fn topics(&self) -> Vec<Topic> {
+        match self {
+            Event::PostCreated { post_id, author_id }
+            | Event::PostUpdated { post_id, author_id }
+            | Event::PostDeleted { post_id, author_id } => {
+                let mut topics = vec![Topic::Post(*post_id)];
+                topics.extend(author_id.map(Topic::User));
+                topics
+            }
+            Event::Notification { .. } => vec![],
+        }
+    }
+
+    /// Whether anyone may receive the event, notifications are private.
+    pub fn is_public(&self) -> bool {
+        !matches!(self, Event::Notification { .. })
+    }
+
+    /// Whether a client of `person_id` that subscribed to `topics` receives the event.
+    pub fn is_delivered_to(&self, person_id: Uuid, topics: &HashSet<Topic>) -> bool {
+        match self {
+            Event::Notification { recipient_id, .. } => *recipient_id == person_id,
+            _ => self.topics().iter().any(|topic| topics.contains(topic)),
+        }
+    }
+}
+
+/// What clients subscribe to, written `post:<id>` or `user:<id>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Topic {
+    /// Changes to one post.
+    Post(Uuid),
+    /// Posts written by one user.
+    User(Uuid),
+}
+
+impl fmt::Display for Topic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Topic::Post(id) => write!(f, "post:{}", id),
+            Topic::User(id) => write!(f, "user:{}", id),
+        }
+    }
+}
+
+impl FromStr for Topic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, id) = s
+            .split_once(':')
+            .ok_or_else(|| format!("Unknown topic: {}", s))?;
+        let id = Uuid::parse_str(id).map_err(|_| format!("Unknown topic: {}", s))?;
+
+        match kind {
+            "post" => Ok(Topic::Post(id)),
+            "user" => Ok(Topic::User(id)),
+            _ => Err(format!("Unknown topic: {}", s)),
+        }
+    }
+}
+
+pub fn init() -> Arc<dyn EventHub> {
+    Arc::new(local::LocalEventHub::new(CHANNEL_CAPACITY, LOG_CAPACITY))
+}
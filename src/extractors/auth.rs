@@ -0,0 +1,91 @@
+use std::ops::Deref;
+
+use actix_web::{dev::Payload, http::header, web, FromRequest, HttpRequest};
+use futures_util::future::LocalBoxFuture;
+use uuid::Uuid;
+
+use crate::{
+    db::person::PersonExt,
+    models::{Person, PersonRole},
+    response::DefaultHttpError,
+    utils::token,
+    AppState,
+};
+
+/// Extractor for routes that require a signed in person.
+///
+/// Reads the bearer token from the `Authorization` header and loads the person
+/// it was issued for. Use `Option<AuthenticatedPerson>` where signing in is
+/// optional.
+#[derive(Debug)]
+pub struct AuthenticatedPerson(pub Person);
+
+impl Deref for AuthenticatedPerson {
+    type Target = Person;
+
+    fn deref(&self) -> &Person {
+        &self.0
+    }
+}
+
+impl AuthenticatedPerson {
+    pub fn is_admin(&self) -> bool {
+        self.role == PersonRole::Admin
+    }
+}
+
+impl AuthenticatedPerson {
+    /// Loads the person a bearer token was issued for.
+    pub async fn from_token(app_state: &AppState, token: &str) -> Result<Self, DefaultHttpError> {
+        let person_id = token::decode_token(token, app_state.env.jwt_secret.as_bytes())
+            .ok()
+            .and_then(|sub| Uuid::parse_str(&sub).ok())
+            .ok_or_else(|| DefaultHttpError::unauthorized("Invalid token"))?;
+
+        let person = app_state
+            .db_client
+            .get_person(person_id)
+            .await
+            .map_err(DefaultHttpError::from)?
+            .ok_or_else(|| {
+                DefaultHttpError::unauthorized(
+                    "The person belonging to this token no longer exists",
+                )
+            })?;
+
+        Ok(AuthenticatedPerson(person))
+    }
+}
+
+/// The token of an `Authorization: Bearer <token>` header.
+pub fn bearer_token(req: &HttpRequest) -> Option<String> {
+    req.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|value| value.to_string())
+}
+
+impl FromRequest for AuthenticatedPerson {
+    type Error = DefaultHttpError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let token = bearer_token(req);
+
+        let app_state = req.app_data::<web::Data<AppState>>().cloned();
+
+        Box::pin(async move {
+            let token = token.ok_or_else(|| {
+                DefaultHttpError::unauthorized("You are not logged in, please provide a token")
+            })?;
+
+            let app_state = app_state.ok_or_else(|| {
+                DefaultHttpError::server_error("Something went wrong, please try again later")
+                    .with_internal("AppState is not registered")
+            })?;
+
+            AuthenticatedPerson::from_token(&app_state, &token).await
+        })
+    }
+}
@@ -0,0 +1,60 @@
+use std::ops::Deref;
+
+use actix_web::{dev::Payload, web, FromRequest, HttpRequest};
+use futures_util::future::LocalBoxFuture;
+use serde::de::DeserializeOwned;
+use uuid::Uuid;
+
+use crate::response::{DefaultHttpError, ErrorCode, FieldError};
+
+/// Path extractor for routes addressed by UUID ids.
+///
+/// Works like `web::Path<T>`, but a malformed id is rejected with a 400 that
+/// names every offending segment instead of actix's plain-text 404.
+#[derive(Debug)]
+pub struct IdPath<T>(pub T);
+
+impl<T> Deref for IdPath<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> FromRequest for IdPath<T>
+where
+    T: DeserializeOwned + 'static,
+{
+    type Error = DefaultHttpError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let path = web::Path::<T>::from_request(req, payload);
+        let req = req.clone();
+
+        Box::pin(async move {
+            match path.await {
+                Ok(path) => Ok(IdPath(path.into_inner())),
+                Err(e) => Err(invalid_id_error(&req).with_internal(e.to_string())),
+            }
+        })
+    }
+}
+
+fn invalid_id_error(req: &HttpRequest) -> DefaultHttpError {
+    let errors: Vec<FieldError> = req
+        .match_info()
+        .iter()
+        .filter(|(name, value)| name.ends_with("id") && Uuid::parse_str(value).is_err())
+        .map(|(name, _)| FieldError {
+            field: name.to_string(),
+            code: String::from("uuid"),
+            message: format!("{} must be a valid UUID", name),
+        })
+        .collect();
+
+    DefaultHttpError::bad_request("The provided id is not valid")
+        .with_code(ErrorCode::InvalidId)
+        .with_errors(errors)
+}
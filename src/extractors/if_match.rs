@@ -0,0 +1,70 @@
+use actix_web::{dev::Payload, http::header, web, FromRequest, HttpRequest};
+use futures_util::future::{ready, Ready};
+
+use crate::{
+    response::{DefaultHttpError, ErrorCode},
+    AppState,
+};
+
+/// Formats a row version as a strong ETag, e.g. `"3"`.
+pub fn etag(version: i32) -> String {
+    format!("\"{}\"", version)
+}
+
+/// Versions listed in the `If-Match` header of a conditional update.
+///
+/// `None` means the update is unconditional, because the header is missing or
+/// is `*`. Tags that are weak or were not issued by us match no version, so an
+/// empty list always fails the precondition. The header becomes mandatory when
+/// `REQUIRE_IF_MATCH` is set.
+#[derive(Debug)]
+pub struct IfMatch(pub Option<Vec<i32>>);
+
+impl IfMatch {
+    pub fn matches(&self, version: i32) -> bool {
+        match &self.0 {
+            Some(versions) => versions.contains(&version),
+            None => true,
+        }
+    }
+}
+
+impl FromRequest for IfMatch {
+    type Error = DefaultHttpError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let value = req
+            .headers()
+            .get(header::IF_MATCH)
+            .and_then(|value| value.to_str().ok());
+
+        let is_required = req
+            .app_data::<web::Data<AppState>>()
+            .map(|app_state| app_state.env.require_if_match)
+            .unwrap_or(false);
+
+        let result = match value {
+            None if is_required => Err(DefaultHttpError::precondition_required(
+                "This update requires an If-Match header",
+            )
+            .with_code(ErrorCode::PreconditionRequired)),
+            None => Ok(IfMatch(None)),
+            Some(value) if value.trim() == "*" => Ok(IfMatch(None)),
+            Some(value) => Ok(IfMatch(Some(
+                value
+                    .split(',')
+                    .filter_map(|tag| {
+                        tag.trim()
+                            .strip_prefix('"')?
+                            .strip_suffix('"')?
+                            .parse::<i32>()
+                            .ok()
+                    })
+                    .collect(),
+            ))),
+        };
+
+        ready(result)
+    }
+}
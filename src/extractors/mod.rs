@@ -0,0 +1,4 @@
+pub mod auth;
+pub mod id_path;
+pub mod if_match;
+pub mod upload;
@@ -0,0 +1,147 @@
+use actix_multipart::Multipart;
+use actix_web::{dev::Payload, web, FromRequest, HttpRequest};
+use bytes::{Bytes, BytesMut};
+use futures_util::{future::LocalBoxFuture, TryStreamExt};
+
+use crate::{
+    response::{DefaultHttpError, ErrorCode, FieldError},
+    AppState,
+};
+
+/// Name of the multipart field that carries the file.
+pub const FILE_FIELD: &str = "file";
+
+pub const MAX_FILENAME_LENGTH: usize = 255;
+
+/// Types accepted for uploads. They are sniffed from the file's leading bytes,
+/// the content type sent by the client is ignored.
+pub const ALLOWED_CONTENT_TYPES: &[&str] = &[
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+    "application/pdf",
+];
+
+/// Content type and file extension of `bytes`, if it is an allowed type.
+pub fn sniff_content_type(bytes: &[u8]) -> Option<(&'static str, &'static str)> {
+    infer::get(bytes)
+        .filter(|kind| ALLOWED_CONTENT_TYPES.contains(&kind.mime_type()))
+        .map(|kind| (kind.mime_type(), kind.extension()))
+}
+
+/// Last path segment of a client supplied file name, capped in length.
+pub fn clean_filename(filename: &str) -> String {
+    let name = filename
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .trim();
+
+    match name {
+        "" => String::from("upload"),
+        name => name.chars().take(MAX_FILENAME_LENGTH).collect(),
+    }
+}
+
+/// Extractor for a `multipart/form-data` body with a single `file` field.
+///
+/// Files larger than `MAX_UPLOAD_SIZE` are rejected while they are read, and
+/// so are files whose type is not in `ALLOWED_CONTENT_TYPES`. Other fields are
+/// ignored.
+#[derive(Debug)]
+pub struct FileUpload {
+    pub filename: String,
+    pub content_type: &'static str,
+    pub extension: &'static str,
+    pub bytes: Bytes,
+}
+
+impl FileUpload {
+    pub fn is_image(&self) -> bool {
+        self.content_type.starts_with("image/")
+    }
+}
+
+impl FromRequest for FileUpload {
+    type Error = DefaultHttpError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let max_size = req
+            .app_data::<web::Data<AppState>>()
+            .map(|app_state| app_state.env.max_upload_size);
+
+        let mut multipart = Multipart::new(req.headers(), payload.take());
+
+        Box::pin(async move {
+            let max_size = max_size.ok_or_else(|| {
+                DefaultHttpError::server_error("Something went wrong, please try again later")
+                    .with_internal("AppState is not registered")
+            })?;
+
+            let mut file: Option<(String, Bytes)> = None;
+
+            while let Some(mut field) = multipart.try_next().await.map_err(invalid_multipart)? {
+                if field.name() != Some(FILE_FIELD) || file.is_some() {
+                    // Fields have to be drained before the next one can be read.
+                    while field.try_next().await.map_err(invalid_multipart)?.is_some() {}
+                    continue;
+                }
+
+                let filename = field
+                    .content_disposition()
+                    .and_then(|disposition| disposition.get_filename())
+                    .map(clean_filename)
+                    .unwrap_or_else(|| String::from("upload"));
+
+                let mut bytes = BytesMut::new();
+
+                while let Some(chunk) = field.try_next().await.map_err(invalid_multipart)? {
+                    if bytes.len() + chunk.len() > max_size {
+                        return Err(DefaultHttpError::payload_too_large(format!(
+                            "Files cannot be larger than {} bytes",
+                            max_size
+                        ))
+                        .with_code(ErrorCode::PayloadTooLarge));
+                    }
+
+                    bytes.extend_from_slice(&chunk);
+                }
+
+                file = Some((filename, bytes.freeze()));
+            }
+
+            let (filename, bytes) =
+                file.filter(|(_, bytes)| !bytes.is_empty()).ok_or_else(|| {
+                    DefaultHttpError::bad_request("The request does not contain a file")
+                        .with_code(ErrorCode::ValidationFailed)
+                        .with_errors(vec![FieldError {
+                            field: String::from(FILE_FIELD),
+                            code: String::from("required"),
+                            message: String::from("file is required"),
+                        }])
+                })?;
+
+            let (content_type, extension) = sniff_content_type(&bytes).ok_or_else(|| {
+                DefaultHttpError::unsupported_media_type(format!(
+                    "Only files of type {} can be uploaded",
+                    ALLOWED_CONTENT_TYPES.join(", ")
+                ))
+                .with_code(ErrorCode::UnsupportedMediaType)
+            })?;
+
+            Ok(FileUpload {
+                filename,
+                content_type,
+                extension,
+                bytes,
+            })
+        })
+    }
+}
+
+fn invalid_multipart(error: actix_multipart::MultipartError) -> DefaultHttpError {
+    DefaultHttpError::bad_request("The multipart body could not be read")
+        .with_internal(error.to_string())
+}
@@ -2,27 +2,40 @@ mod config;
 mod db;
 mod db_test;
 mod dtos;
+mod events;
+mod extractors;
+mod middlewares;
 mod models;
 mod response;
 mod scopes;
+mod storage;
+mod tasks;
 mod utils;
 
-use actix_web::{get, middleware::Logger, web, App, HttpResponse, HttpServer, Responder};
+use actix_files::Files;
+use actix_web::{web, App, HttpServer};
 use config::Config;
 use db::DBClient;
 use dotenv::dotenv;
+use events::EventHub;
+use middlewares::{logger::logger, request_id::RequestIdMiddleware};
+use response::DefaultHttpError;
 use sqlx::postgres::PgPoolOptions;
+use std::{sync::Arc, time::Duration};
+use storage::Storage;
 
 #[derive(Debug, Clone)]
 pub struct AppState {
     pub env: Config,
     pub db_client: DBClient,
+    pub storage: Arc<dyn Storage>,
+    pub events: Arc<dyn EventHub>,
 }
 
 #[actix_web::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     if std::env::var_os("RUST_LOG").is_none() {
-        std::env::set_var("RUST_LOG", "actix_web=info");
+        std::env::set_var("RUST_LOG", "actix_web=info,rusty_post_api=info");
     }
 
     dotenv().ok();
@@ -37,18 +50,66 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let db_client = DBClient::new(pool);
 
+    let storage = storage::init(&config);
+    let events = events::init();
+
+    actix_web::rt::spawn(tasks::scheduled_posts::run(
+        db_client.clone(),
+        events.clone(),
+        Duration::from_secs(config.scheduler_interval),
+    ));
+
+    actix_web::rt::spawn(tasks::purge_trash::run(
+        db_client.clone(),
+        storage.clone(),
+        Duration::from_secs(config.purge_interval),
+        config.trash_retention_days,
+    ));
+
+    actix_web::rt::spawn(tasks::thumbnails::run(
+        db_client.clone(),
+        storage.clone(),
+        Duration::from_secs(config.thumbnail_interval),
+    ));
+
     let app_state: AppState = AppState {
         env: config.clone(),
         db_client,
+        storage,
+        events,
     };
 
     println!("Server is running on {}:{}", config.url, config.port);
 
     HttpServer::new(move || {
-        App::new()
+        let mut app = App::new()
             .app_data(web::Data::new(app_state.clone()))
-            .wrap(Logger::default())
+            .app_data(
+                web::JsonConfig::default()
+                    .error_handler(|err, _| DefaultHttpError::bad_request(err.to_string()).into()),
+            )
+            .app_data(
+                web::QueryConfig::default()
+                    .error_handler(|err, _| DefaultHttpError::bad_request(err.to_string()).into()),
+            )
+            .wrap(RequestIdMiddleware)
+            .wrap(logger())
+            .service(scopes::auth::auth_scope())
             .service(scopes::posts::posts_scope())
+            .service(scopes::feed::feed_scope())
+            .service(scopes::notifications::notifications_scope())
+            .service(scopes::ws::ws_scope())
+            .service(scopes::users::users_scope())
+            .service(scopes::admins::admins_scope())
+            .service(scopes::emails::emails_scope())
+            .service(scopes::tags::tags_scope());
+
+        // Other backends serve their files themselves.
+        if config.storage_backend == "local" {
+            app = app.service(Files::new("/media", &config.storage_local_root));
+        }
+
+        app
     })
     .bind((config.host_ip, config.port))?
     .run()
@@ -0,0 +1,39 @@
+use actix_web::{dev::ServiceRequest, middleware::Logger};
+
+/// The format of `Logger::default()`, with the request line going through
+/// `request_line` below.
+const FORMAT: &str = r#"%a "%{request_line}xi" %s %b "%{Referer}i" "%{User-Agent}i" %T"#;
+
+/// Logs requests like `Logger::default()`, except that tokens passed in the
+/// query string, as browsers do to open the WebSocket, stay out of the logs.
+pub fn logger() -> Logger {
+    Logger::new(FORMAT).custom_request_replace("request_line", request_line)
+}
+
+fn request_line(req: &ServiceRequest) -> String {
+    let target = req
+        .uri()
+        .path_and_query()
+        .map(|target| redact_token(target.as_str()))
+        .unwrap_or_else(|| req.path().to_string());
+
+    format!("{} {} {:?}", req.method(), target, req.version())
+}
+
+/// Replaces the value of any `token` parameter in `target`.
+pub fn redact_token(target: &str) -> String {
+    let Some((path, query)) = target.split_once('?') else {
+        return target.to_string();
+    };
+
+    let query = query
+        .split('&')
+        .map(|param| match param.split_once('=') {
+            Some(("token", _)) => "token=REDACTED",
+            _ => param,
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+
+    format!("{}?{}", path, query)
+}
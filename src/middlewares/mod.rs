@@ -0,0 +1,2 @@
+pub mod logger;
+pub mod request_id;
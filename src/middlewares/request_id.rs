@@ -0,0 +1,120 @@
+use std::future::{ready, Ready};
+
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{HeaderName, HeaderValue},
+    Error,
+};
+use futures_util::future::LocalBoxFuture;
+use uuid::Uuid;
+
+use crate::response::{DefaultHttpError, HttpResponse};
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Tags every request with an id (taken from `X-Request-Id` when the client
+/// sends a sane one), echoes it back as a header and attaches it to error
+/// bodies so that client reports can be matched with server logs.
+pub struct RequestIdMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestIdMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = RequestIdService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestIdService { service }))
+    }
+}
+
+pub struct RequestIdService<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestIdService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let request_id = req
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .filter(|value| is_valid_request_id(value))
+            .map(|value| value.to_string())
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+        let fut = self.service.call(req);
+
+        Box::pin(async move {
+            let res = fut.await?;
+
+            let http_error = res
+                .response()
+                .error()
+                .and_then(|e| e.as_error::<DefaultHttpError>())
+                .cloned();
+
+            let mut res = match http_error {
+                Some(mut http_error) => {
+                    let (req, _) = res.into_parts();
+
+                    if let Some(internal) = &http_error.internal {
+                        let level = if http_error.status >= 500 {
+                            log::Level::Error
+                        } else {
+                            log::Level::Info
+                        };
+
+                        log::log!(
+                            level,
+                            "[{}] {} {} failed with {:?}: {}",
+                            request_id,
+                            req.method(),
+                            req.path(),
+                            http_error.code,
+                            internal
+                        );
+                    }
+
+                    http_error.request_id = Some(request_id.clone());
+                    http_error.instance = Some(req.path().to_string());
+
+                    ServiceResponse::new(req, http_error.into_http_response()).map_into_right_body()
+                }
+                None => res.map_into_left_body(),
+            };
+
+            if let Ok(value) = HeaderValue::from_str(&request_id) {
+                res.headers_mut()
+                    .insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+            }
+
+            Ok(res)
+        })
+    }
+}
+
+fn is_valid_request_id(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= 128
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+}
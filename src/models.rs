@@ -1,6 +1,14 @@
-use chrono::prelude::{DateTime, Utc};
+use chrono::{
+    prelude::{DateTime, Utc},
+    NaiveDate,
+};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, Type};
+use sqlx::{
+    postgres::{PgHasArrayType, PgTypeInfo},
+    types::Json,
+    FromRow, Type,
+};
+use std::{fmt, str::FromStr};
 
 #[derive(Debug, Deserialize, Serialize, Clone, Copy, Type, PartialEq)]
 #[sqlx(type_name = "person_role", rename_all = "lowercase")]
@@ -9,54 +17,126 @@ pub enum PersonRole {
     User,
 }
 
-impl PersonRole {
-    pub fn to_str(&self) -> &str {
+impl fmt::Display for PersonRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            PersonRole::Admin => "admin",
-            PersonRole::User => "user",
-        }
-    }
-
-    pub fn to_string(&self) -> String {
-        match self {
-            PersonRole::Admin => "Admin".to_string(),
-            PersonRole::User => "User".to_string(),
+            PersonRole::Admin => write!(f, "Admin"),
+            PersonRole::User => write!(f, "User"),
         }
     }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, Copy, Type, PartialEq)]
-#[sqlx(type_name = "gender", rename_all = "lowercase")]
+#[sqlx(type_name = "gender", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
 pub enum Gender {
     Male,
     Female,
+    NonBinary,
+    PreferNotToSay,
+    // The person's own wording is kept in `gender_description`.
+    SelfDescribed,
 }
 
-impl Gender {
-    pub fn to_str(&self) -> &str {
+impl fmt::Display for Gender {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Gender::Male => "male",
-            Gender::Female => "female",
+            Gender::Male => write!(f, "Male"),
+            Gender::Female => write!(f, "Female"),
+            Gender::NonBinary => write!(f, "Non-binary"),
+            Gender::PreferNotToSay => write!(f, "Prefer not to say"),
+            Gender::SelfDescribed => write!(f, "Self-described"),
         }
     }
+}
 
-    pub fn to_string(&self) -> String {
-        match self {
-            Gender::Male => "Male".to_string(),
-            Gender::Female => "Female".to_string(),
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Type, PartialEq, Eq, PartialOrd, Ord)]
+#[sqlx(type_name = "reaction_kind", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ReactionKind {
+    Like,
+    Love,
+    Insightful,
+    Funny,
+    Sad,
+}
+
+impl FromStr for ReactionKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "like" => Ok(ReactionKind::Like),
+            "love" => Ok(ReactionKind::Love),
+            "insightful" => Ok(ReactionKind::Insightful),
+            "funny" => Ok(ReactionKind::Funny),
+            "sad" => Ok(ReactionKind::Sad),
+            _ => Err(format!("Unknown reaction kind: {}", s)),
         }
     }
 }
 
-impl From<String> for Gender {
-    fn from(string: String) -> Gender {
-        match string.to_lowercase().as_str() {
-            "male" => Gender::Male,
-            "female" => Gender::Female,
-            _ => {
-                eprintln!("Gender is not correct");
-                Gender::Male
-            }
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Type, PartialEq)]
+#[sqlx(type_name = "image_variant_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ImageVariantStatus {
+    Pending,
+    Ready,
+    Failed,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Type, PartialEq)]
+#[sqlx(type_name = "follow_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum FollowStatus {
+    Pending,
+    Accepted,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Type, PartialEq)]
+#[sqlx(type_name = "notification_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    Comment,
+    Reply,
+    Follow,
+    FollowRequest,
+    FollowAccepted,
+    Reaction,
+}
+
+impl PgHasArrayType for NotificationKind {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("_notification_kind")
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Type, PartialEq)]
+#[sqlx(type_name = "post_status", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum PostStatus {
+    Draft,
+    Published,
+    Archived,
+}
+
+impl PostStatus {
+    pub fn can_transition_to(self, next: PostStatus) -> bool {
+        matches!(
+            (self, next),
+            (PostStatus::Draft, PostStatus::Published)
+                | (PostStatus::Published, PostStatus::Archived)
+                | (PostStatus::Archived, PostStatus::Published)
+        )
+    }
+}
+
+impl fmt::Display for PostStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PostStatus::Draft => write!(f, "draft"),
+            PostStatus::Published => write!(f, "published"),
+            PostStatus::Archived => write!(f, "archived"),
         }
     }
 }
@@ -65,9 +145,37 @@ impl From<String> for Gender {
 pub struct Post {
     pub id: uuid::Uuid,
     pub title: String,
+    pub slug: String,
+    pub description: String,
+    pub description_html: String,
+    pub excerpt: String,
+    pub reading_time: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub status: PostStatus,
+    pub published_at: Option<DateTime<Utc>>,
+    pub author_id: Option<uuid::Uuid>,
+    pub scheduled_at: Option<DateTime<Utc>>,
+    pub version: i32,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl Post {
+    /// Drafts are only visible to their author.
+    pub fn is_visible_to(&self, viewer_id: Option<uuid::Uuid>) -> bool {
+        self.status != PostStatus::Draft || (viewer_id.is_some() && self.author_id == viewer_id)
+    }
+}
+
+/// Title and description of a post as they were before an edit.
+#[derive(Debug, Deserialize, FromRow, Serialize, Clone)]
+pub struct PostRevision {
+    pub id: uuid::Uuid,
+    pub post_id: uuid::Uuid,
+    pub editor_id: Option<uuid::Uuid>,
+    pub title: String,
     pub description: String,
-    pub created_at: Option<DateTime<Utc>>,
-    pub updated_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Deserialize, FromRow, Serialize, Clone)]
@@ -78,15 +186,30 @@ pub struct Person {
     pub lastname: String,
     pub password: String,
     pub role: PersonRole,
-    pub birthdate: DateTime<Utc>,
-    pub gender: Gender,
+    pub birthdate: NaiveDate,
+    pub gender: Option<Gender>,
+    pub gender_description: Option<String>,
+    pub pronouns: Option<String>,
     pub biography: Option<String>,
     pub is_profile_private: bool,
-    pub created_at: Option<DateTime<Utc>>,
-    pub updated_at: Option<DateTime<Utc>>,
+    pub avatar_key: Option<String>,
+    pub avatar_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub version: i32,
+    pub deleted_at: Option<DateTime<Utc>>,
+
+    #[sqlx(default)]
+    pub followers_count: i64,
+
+    #[sqlx(default)]
+    pub following_count: i64,
 
     #[sqlx(skip)]
     pub emails: Vec<Email>,
+
+    #[sqlx(skip)]
+    pub avatar_variants: Vec<ImageVariant>,
 }
 
 #[derive(Debug, Deserialize, FromRow, Serialize, Clone)]
@@ -96,15 +219,30 @@ pub struct User {
     pub firstname: String,
     pub lastname: String,
     pub password: String,
-    pub birthdate: DateTime<Utc>,
-    pub gender: Gender,
+    pub birthdate: NaiveDate,
+    pub gender: Option<Gender>,
+    pub gender_description: Option<String>,
+    pub pronouns: Option<String>,
     pub biography: Option<String>,
     pub is_profile_private: bool,
-    pub created_at: Option<DateTime<Utc>>,
-    pub updated_at: Option<DateTime<Utc>>,
+    pub avatar_key: Option<String>,
+    pub avatar_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub version: i32,
+    pub deleted_at: Option<DateTime<Utc>>,
+
+    #[sqlx(default)]
+    pub followers_count: i64,
+
+    #[sqlx(default)]
+    pub following_count: i64,
 
     #[sqlx(skip)]
     pub emails: Vec<Email>,
+
+    #[sqlx(skip)]
+    pub avatar_variants: Vec<ImageVariant>,
 }
 
 impl From<Person> for User {
@@ -117,11 +255,20 @@ impl From<Person> for User {
             password: person.password,
             birthdate: person.birthdate,
             gender: person.gender,
+            gender_description: person.gender_description,
+            pronouns: person.pronouns,
             biography: person.biography,
             is_profile_private: person.is_profile_private,
+            avatar_key: person.avatar_key,
+            avatar_url: person.avatar_url,
+            avatar_variants: person.avatar_variants,
+            followers_count: person.followers_count,
+            following_count: person.following_count,
             emails: person.emails,
             created_at: person.created_at,
             updated_at: person.updated_at,
+            version: person.version,
+            deleted_at: person.deleted_at,
         }
     }
 }
@@ -133,10 +280,14 @@ pub struct Admin {
     pub firstname: String,
     pub lastname: String,
     pub password: String,
-    pub birthdate: DateTime<Utc>,
-    pub gender: Gender,
-    pub created_at: Option<DateTime<Utc>>,
-    pub updated_at: Option<DateTime<Utc>>,
+    pub birthdate: NaiveDate,
+    pub gender: Option<Gender>,
+    pub gender_description: Option<String>,
+    pub pronouns: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub version: i32,
+    pub deleted_at: Option<DateTime<Utc>>,
 
     #[sqlx(skip)]
     pub emails: Vec<Email>,
@@ -152,9 +303,13 @@ impl From<Person> for Admin {
             password: person.password,
             birthdate: person.birthdate,
             gender: person.gender,
+            gender_description: person.gender_description,
+            pronouns: person.pronouns,
             emails: person.emails,
             created_at: person.created_at,
             updated_at: person.updated_at,
+            version: person.version,
+            deleted_at: person.deleted_at,
         }
     }
 }
@@ -167,5 +322,152 @@ pub struct Email {
     pub is_primary: bool,
     pub is_verified: bool,
     pub is_private: bool,
-    pub updated_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, FromRow, Serialize, Clone)]
+pub struct Comment {
+    pub id: uuid::Uuid,
+    pub post_id: uuid::Uuid,
+    pub author_id: uuid::Uuid,
+    pub parent_id: Option<uuid::Uuid>,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+
+    #[sqlx(default)]
+    pub replies_count: i64,
+}
+
+/// Number of reactions of one kind on a post, and whether the viewer is among them.
+#[derive(Debug, Deserialize, FromRow, Serialize, Clone)]
+pub struct PostReactionCount {
+    pub post_id: uuid::Uuid,
+    pub kind: ReactionKind,
+    pub count: i64,
+    pub viewer_reacted: bool,
+}
+
+/// One person following another, or asking to while `pending`.
+#[derive(Debug, Deserialize, FromRow, Serialize, Clone)]
+pub struct Follow {
+    pub follower_id: uuid::Uuid,
+    pub followee_id: uuid::Uuid,
+    pub status: FollowStatus,
+    pub created_at: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+}
+
+/// What a notification is about. Stored as JSON, tagged with its `kind`.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(
+    tag = "kind",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum NotificationPayload {
+    /// Someone commented on the recipient's post.
+    Comment {
+        post_id: uuid::Uuid,
+        comment_id: uuid::Uuid,
+    },
+    /// Someone replied to the recipient's comment.
+    Reply {
+        post_id: uuid::Uuid,
+        comment_id: uuid::Uuid,
+        parent_id: uuid::Uuid,
+    },
+    Follow,
+    FollowRequest,
+    /// The recipient's request to follow the actor was approved.
+    FollowAccepted,
+    Reaction {
+        post_id: uuid::Uuid,
+        reaction: ReactionKind,
+    },
+}
+
+impl NotificationPayload {
+    pub fn kind(&self) -> NotificationKind {
+        match self {
+            NotificationPayload::Comment { .. } => NotificationKind::Comment,
+            NotificationPayload::Reply { .. } => NotificationKind::Reply,
+            NotificationPayload::Follow => NotificationKind::Follow,
+            NotificationPayload::FollowRequest => NotificationKind::FollowRequest,
+            NotificationPayload::FollowAccepted => NotificationKind::FollowAccepted,
+            NotificationPayload::Reaction { .. } => NotificationKind::Reaction,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, FromRow, Serialize, Clone)]
+pub struct Notification {
+    pub id: uuid::Uuid,
+    pub recipient_id: uuid::Uuid,
+    pub actor_id: uuid::Uuid,
+    pub kind: NotificationKind,
+    pub payload: Json<NotificationPayload>,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, FromRow, Serialize, Clone)]
+pub struct Tag {
+    pub id: uuid::Uuid,
+    pub name: String,
+    pub slug: String,
+    pub created_at: DateTime<Utc>,
+
+    #[sqlx(default)]
+    pub posts_count: i64,
+}
+
+#[derive(Debug, Deserialize, FromRow, Serialize, Clone)]
+pub struct PostTag {
+    pub post_id: uuid::Uuid,
+    pub name: String,
+    pub slug: String,
+}
+
+#[derive(Debug, Deserialize, FromRow, Serialize, Clone)]
+pub struct Attachment {
+    pub id: uuid::Uuid,
+    pub post_id: uuid::Uuid,
+    pub uploader_id: Option<uuid::Uuid>,
+    pub storage_key: String,
+    pub url: String,
+    pub filename: String,
+    pub content_type: String,
+    pub size: i64,
+    pub created_at: DateTime<Utc>,
+
+    #[sqlx(skip)]
+    pub variants: Vec<ImageVariant>,
+}
+
+/// A resized copy of an uploaded image. `storage_key` and `url` are only set
+/// once the variant is `ready`.
+#[derive(Debug, Deserialize, FromRow, Serialize, Clone)]
+pub struct ImageVariant {
+    pub id: uuid::Uuid,
+    pub source_key: String,
+    pub name: String,
+    pub max_size: i32,
+    pub status: ImageVariantStatus,
+    pub storage_key: Option<String>,
+    pub url: Option<String>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub attempts: i32,
+    pub locked_until: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A hard-deleted post or account, with the storage keys of the files that
+/// went with it and still have to be removed from storage.
+#[derive(Debug, Deserialize, FromRow, Serialize, Clone)]
+pub struct PurgedRow {
+    pub id: uuid::Uuid,
+    pub storage_keys: Vec<String>,
 }
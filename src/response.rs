@@ -1,6 +1,14 @@
-use actix_web::{http::StatusCode, HttpResponse as ActixHttpResponse, ResponseError};
+use actix_web::{
+    http::{header::ContentType, StatusCode},
+    HttpResponse as ActixHttpResponse, ResponseError,
+};
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use validator::ValidationErrors;
+
+use crate::db::error::{constraint_violation, ConstraintViolation, ViolationKind};
+
+pub const PROBLEM_JSON_CONTENT_TYPE: &str = "application/problem+json";
 
 pub trait HttpResponse {
     fn new(message: impl Into<String>, status: u16) -> Self;
@@ -14,10 +22,100 @@ pub struct DefaultHttpResponse {
     pub status: u16,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    BadRequest,
+    ValidationFailed,
+    InvalidId,
+    Unauthorized,
+    InvalidCredentials,
+    Forbidden,
+    NotFound,
+    PostNotFound,
+    CommentNotFound,
+    RevisionNotFound,
+    UserNotFound,
+    AdminNotFound,
+    EmailNotFound,
+    AttachmentNotFound,
+    FollowNotFound,
+    BlockNotFound,
+    MuteNotFound,
+    NotificationNotFound,
+    Conflict,
+    UsernameTaken,
+    EmailTaken,
+    InvalidStateTransition,
+    RestorePeriodExpired,
+    PreconditionFailed,
+    PreconditionRequired,
+    PayloadTooLarge,
+    UnsupportedMediaType,
+    InvalidReference,
+    ConstraintViolation,
+    MissingField,
+    InvalidValue,
+    ValueTooLong,
+    InternalServerError,
+}
+
+impl ErrorCode {
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            400 => ErrorCode::BadRequest,
+            401 => ErrorCode::Unauthorized,
+            403 => ErrorCode::Forbidden,
+            404 => ErrorCode::NotFound,
+            409 => ErrorCode::Conflict,
+            412 => ErrorCode::PreconditionFailed,
+            413 => ErrorCode::PayloadTooLarge,
+            415 => ErrorCode::UnsupportedMediaType,
+            422 => ErrorCode::ConstraintViolation,
+            428 => ErrorCode::PreconditionRequired,
+            _ => ErrorCode::InternalServerError,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldError {
+    pub field: String,
+    pub code: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone)]
 pub struct DefaultHttpError {
     pub message: String,
     pub status: u16,
+    pub code: ErrorCode,
+    pub errors: Vec<FieldError>,
+    pub request_id: Option<String>,
+    pub instance: Option<String>,
+
+    // Internal details that are logged on the server but never sent to the client.
+    pub internal: Option<String>,
+}
+
+/// Body of an `application/problem+json` response as described in RFC 7807.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    pub code: ErrorCode,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub errors: Vec<FieldError>,
+
+    #[serde(rename = "requestId", skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 impl fmt::Display for DefaultHttpResponse {
@@ -30,8 +128,8 @@ impl fmt::Display for DefaultHttpError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "HttpError: message: {}, status: {}",
-            self.message, self.status
+            "HttpError: message: {}, status: {}, code: {:?}",
+            self.message, self.status, self.code
         )
     }
 }
@@ -39,6 +137,10 @@ impl fmt::Display for DefaultHttpError {
 impl std::error::Error for DefaultHttpError {}
 
 impl ResponseError for DefaultHttpError {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
     fn error_response(&self) -> ActixHttpResponse<actix_web::body::BoxBody> {
         let cloned = self.clone();
         cloned.into_http_response()
@@ -65,13 +167,129 @@ impl HttpResponse for DefaultHttpError {
         DefaultHttpError {
             message: message.into(),
             status,
+            code: ErrorCode::from_status(status),
+            errors: vec![],
+            request_id: None,
+            instance: None,
+            internal: None,
         }
     }
 
     fn into_http_response(self) -> ActixHttpResponse {
-        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let status = self.status_code();
 
-        ActixHttpResponse::build(status).json(self)
+        let problem = ProblemDetails {
+            problem_type: String::from("about:blank"),
+            title: status.canonical_reason().unwrap_or("Error").to_string(),
+            status: status.as_u16(),
+            detail: self.message,
+            code: self.code,
+            instance: self.instance,
+            errors: self.errors,
+            request_id: self.request_id,
+        };
+
+        ActixHttpResponse::build(status)
+            .content_type(ContentType(PROBLEM_JSON_CONTENT_TYPE.parse().unwrap()))
+            .body(serde_json::to_string(&problem).unwrap())
+    }
+}
+
+impl From<ValidationErrors> for DefaultHttpError {
+    fn from(errors: ValidationErrors) -> Self {
+        let mut field_errors: Vec<FieldError> = errors
+            .field_errors()
+            .into_iter()
+            .flat_map(|(field, errors)| {
+                // Struct level checks are reported against the whole body.
+                let field = if field == "__all__" { "body" } else { field };
+
+                errors.iter().map(move |e| FieldError {
+                    field: field.to_string(),
+                    code: e.code.to_string(),
+                    message: e
+                        .message
+                        .as_ref()
+                        .map(|m| m.to_string())
+                        .unwrap_or_else(|| format!("{} is invalid", field)),
+                })
+            })
+            .collect();
+
+        field_errors.sort_by(|a, b| a.field.cmp(&b.field));
+
+        Self::bad_request("The request contains invalid fields")
+            .with_code(ErrorCode::ValidationFailed)
+            .with_errors(field_errors)
+    }
+}
+
+impl From<sqlx::Error> for DefaultHttpError {
+    fn from(error: sqlx::Error) -> Self {
+        if let Some(violation) = constraint_violation(&error) {
+            return Self::from(violation).with_internal(error.to_string());
+        }
+
+        Self::server_error("Something went wrong, please try again later")
+            .with_internal(error.to_string())
+    }
+}
+
+impl From<ConstraintViolation> for DefaultHttpError {
+    fn from(violation: ConstraintViolation) -> Self {
+        let field = violation.field.unwrap_or_else(|| String::from("value"));
+
+        let (error, field_code, field_message) = match violation.kind {
+            ViolationKind::Unique => {
+                let code = match field.as_str() {
+                    "username" => ErrorCode::UsernameTaken,
+                    "email" => ErrorCode::EmailTaken,
+                    _ => ErrorCode::Conflict,
+                };
+
+                (
+                    Self::unique_constraint_voilation(format!("The {} is already taken", field))
+                        .with_code(code),
+                    "unique",
+                    format!("{} must be unique", field),
+                )
+            }
+            ViolationKind::ForeignKey => (
+                Self::unprocessable_entity(format!("The referenced {} does not exist", field))
+                    .with_code(ErrorCode::InvalidReference),
+                "reference",
+                format!("{} does not reference an existing record", field),
+            ),
+            ViolationKind::Check => (
+                Self::unprocessable_entity(format!("The {} is not allowed", field))
+                    .with_code(ErrorCode::ConstraintViolation),
+                "check",
+                format!("{} does not satisfy its constraints", field),
+            ),
+            ViolationKind::NotNull => (
+                Self::bad_request(format!("The {} is required", field))
+                    .with_code(ErrorCode::MissingField),
+                "required",
+                format!("{} is required", field),
+            ),
+            ViolationKind::InvalidValue => (
+                Self::bad_request(format!("The {} has an invalid value", field))
+                    .with_code(ErrorCode::InvalidValue),
+                "invalid",
+                format!("{} has an invalid value", field),
+            ),
+            ViolationKind::ValueTooLong => (
+                Self::bad_request("A value is too long").with_code(ErrorCode::ValueTooLong),
+                "length",
+                format!("{} is too long", field),
+            ),
+        };
+
+        error.with_errors(vec![FieldError {
+            field,
+            code: field_code.to_string(),
+            message: field_message,
+        }])
     }
 }
 
@@ -98,7 +316,46 @@ impl DefaultHttpError {
         Self::new(message, 401)
     }
 
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(message, 403)
+    }
+
     pub fn not_found(message: impl Into<String>) -> Self {
         Self::new(message, 404)
     }
+
+    pub fn precondition_failed(message: impl Into<String>) -> Self {
+        Self::new(message, 412)
+    }
+
+    pub fn payload_too_large(message: impl Into<String>) -> Self {
+        Self::new(message, 413)
+    }
+
+    pub fn unsupported_media_type(message: impl Into<String>) -> Self {
+        Self::new(message, 415)
+    }
+
+    pub fn unprocessable_entity(message: impl Into<String>) -> Self {
+        Self::new(message, 422)
+    }
+
+    pub fn precondition_required(message: impl Into<String>) -> Self {
+        Self::new(message, 428)
+    }
+
+    pub fn with_code(mut self, code: ErrorCode) -> Self {
+        self.code = code;
+        self
+    }
+
+    pub fn with_errors(mut self, errors: Vec<FieldError>) -> Self {
+        self.errors = errors;
+        self
+    }
+
+    pub fn with_internal(mut self, internal: impl Into<String>) -> Self {
+        self.internal = Some(internal.into());
+        self
+    }
 }
@@ -0,0 +1,222 @@
+use actix_web::{http::header, web, HttpResponse as ActixHttpResponse, Scope};
+use uuid::Uuid;
+use validator::{Validate, ValidateArgs};
+
+use crate::{
+    db::person::PersonExt,
+    dtos::person::{
+        AdminDto, AdminListResponseDto, AdminResponseDto, CreateAdminDto, GetAdminParamsDto,
+        SearchAdminQueryDto, UpdateAdminPublicInfoDto,
+    },
+    extractors::{
+        auth::AuthenticatedPerson,
+        id_path::IdPath,
+        if_match::{etag, IfMatch},
+    },
+    response::{DefaultHttpError, DefaultHttpResponse, ErrorCode, HttpResponse},
+    utils::password,
+    AppState,
+};
+
+use super::users::trash_forbidden;
+
+pub fn admins_scope() -> Scope {
+    web::scope("/api/admins")
+        // GET methods
+        .route("", web::get().to(get_admins))
+        .route("trash", web::get().to(get_deleted_admins))
+        .route("{admin_id}", web::get().to(get_admin))
+        // POST methods
+        .route("", web::post().to(save_admin))
+        .route("{admin_id}/restore", web::post().to(restore_admin))
+        // PATCH methods
+        .route("{admin_id}", web::patch().to(update_admin))
+        // DELETE methods
+        .route("{admin_id}", web::delete().to(delete_admin))
+}
+
+pub async fn get_admins(
+    query: web::Query<SearchAdminQueryDto>,
+    app_state: web::Data<AppState>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let query_params: SearchAdminQueryDto = query.into_inner();
+
+    query_params.validate().map_err(DefaultHttpError::from)?;
+
+    let admins = app_state
+        .db_client
+        .get_admins(query_params, true)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    Ok(ActixHttpResponse::Ok().json(AdminListResponseDto {
+        status: 200,
+        admins: AdminDto::filter_admins(&admins),
+        results: admins.len(),
+    }))
+}
+
+pub async fn get_admin(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetAdminParamsDto>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let result = app_state.db_client.get_admin(path.admin_id).await;
+
+    match result {
+        Ok(Some(admin)) => Ok(ActixHttpResponse::Ok()
+            .insert_header((header::ETAG, etag(admin.version)))
+            .json(AdminResponseDto {
+                status: 200,
+                admin: AdminDto::filter_admin(&admin),
+            })),
+        Ok(None) => {
+            Err(DefaultHttpError::not_found("Admin not found").with_code(ErrorCode::AdminNotFound))
+        }
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+pub async fn save_admin(
+    app_state: web::Data<AppState>,
+    person: AuthenticatedPerson,
+    body: web::Json<CreateAdminDto>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    if !person.is_admin() {
+        return Err(admins_forbidden());
+    }
+
+    body.validate_args(app_state.env.min_age)
+        .map_err(DefaultHttpError::from)?;
+
+    let mut dto = body.into_inner();
+
+    dto.password = password::hash(&dto.password).map_err(|e| {
+        DefaultHttpError::server_error("Could not process the password")
+            .with_internal(e.to_string())
+    })?;
+
+    let result = app_state.db_client.save_admin(dto).await;
+
+    match result {
+        Ok(admin) => Ok(ActixHttpResponse::Created().json(AdminResponseDto {
+            status: 201,
+            admin: AdminDto::filter_admin(&admin),
+        })),
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+pub async fn update_admin(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetAdminParamsDto>,
+    person: AuthenticatedPerson,
+    if_match: IfMatch,
+    body: web::Json<UpdateAdminPublicInfoDto>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    if !person.is_admin() {
+        return Err(admins_forbidden());
+    }
+
+    body.validate_args(app_state.env.min_age)
+        .map_err(DefaultHttpError::from)?;
+
+    let result = app_state
+        .db_client
+        .update_admin(path.admin_id, body.into_inner().into(), if_match.0.clone())
+        .await;
+
+    match result {
+        Ok(true) => Ok(DefaultHttpResponse::ok("Admin has been updated").into_http_response()),
+        Ok(false) => Err(update_failed(&app_state, path.admin_id, &if_match).await),
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+pub async fn delete_admin(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetAdminParamsDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    if !person.is_admin() {
+        return Err(admins_forbidden());
+    }
+
+    let result = app_state.db_client.delete_admin(path.admin_id).await;
+
+    match result {
+        Ok(true) => Ok(DefaultHttpResponse::ok("Admin has been deleted").into_http_response()),
+        Ok(false) => {
+            Err(DefaultHttpError::not_found("Admin not found").with_code(ErrorCode::AdminNotFound))
+        }
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+pub async fn get_deleted_admins(
+    app_state: web::Data<AppState>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    if !person.is_admin() {
+        return Err(trash_forbidden());
+    }
+
+    let admins = app_state
+        .db_client
+        .get_deleted_admins()
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    Ok(ActixHttpResponse::Ok().json(AdminListResponseDto {
+        status: 200,
+        admins: AdminDto::filter_admins(&admins),
+        results: admins.len(),
+    }))
+}
+
+pub async fn restore_admin(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetAdminParamsDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    if !person.is_admin() {
+        return Err(trash_forbidden());
+    }
+
+    let result = app_state
+        .db_client
+        .restore_admin(path.admin_id, app_state.env.trash_retention_days)
+        .await;
+
+    match result {
+        Ok(true) => Ok(DefaultHttpResponse::ok("Admin has been restored").into_http_response()),
+        Ok(false) => Err(DefaultHttpError::not_found(
+            "Admin not found in the trash or past its restore period",
+        )
+        .with_code(ErrorCode::AdminNotFound)),
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+/// Tells a missing admin apart from one that changed since the client read it.
+async fn update_failed(
+    app_state: &AppState,
+    admin_id: Uuid,
+    if_match: &IfMatch,
+) -> DefaultHttpError {
+    let admin = app_state.db_client.get_admin(admin_id).await;
+
+    match admin {
+        Ok(Some(admin)) if !if_match.matches(admin.version) => {
+            DefaultHttpError::precondition_failed("The admin has been modified since it was read")
+                .with_code(ErrorCode::PreconditionFailed)
+        }
+        Ok(_) => DefaultHttpError::not_found("Admin not found").with_code(ErrorCode::AdminNotFound),
+        Err(e) => DefaultHttpError::from(e),
+    }
+}
+
+/// Only admins can add, change or remove admins, the first one has to be
+/// created directly in the database.
+fn admins_forbidden() -> DefaultHttpError {
+    DefaultHttpError::forbidden("Only admins can manage admins")
+}
@@ -0,0 +1,155 @@
+use actix_web::{web, HttpResponse as ActixHttpResponse, Scope};
+use uuid::Uuid;
+
+use crate::{
+    db::{attachment::AttachmentExt, image_variant::ImageVariantExt},
+    dtos::{
+        attachment::{
+            AttachmentDto, AttachmentResponseDto, CreateAttachmentDto, GetAttachmentParamsDto,
+        },
+        post::GetPostParamsDto,
+    },
+    extractors::{auth::AuthenticatedPerson, id_path::IdPath, upload::FileUpload},
+    models::ImageVariant,
+    response::{DefaultHttpError, DefaultHttpResponse, ErrorCode, HttpResponse},
+    AppState,
+};
+
+use super::posts::{can_manage_post, find_visible_post};
+
+/// Nested under `posts_scope`, so every route is relative to `/api/posts/{post_id}`.
+pub fn attachments_scope() -> Scope {
+    web::scope("/{post_id}/attachments")
+        // POST methods
+        .route("", web::post().to(save_attachment))
+        // DELETE methods
+        .route("{attachment_id}", web::delete().to(delete_attachment))
+}
+
+pub async fn save_attachment(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetPostParamsDto>,
+    person: AuthenticatedPerson,
+    upload: FileUpload,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let post = find_visible_post(&app_state, path.post_id, Some(person.id)).await?;
+
+    if !can_manage_post(&post, &person) {
+        return Err(DefaultHttpError::forbidden(
+            "Only the author or an admin can add attachments to this post",
+        ));
+    }
+
+    let storage_key = store_upload(&app_state, &format!("posts/{}", post.id), &upload).await?;
+
+    let dto = CreateAttachmentDto {
+        url: app_state.storage.url(&storage_key),
+        storage_key,
+        filename: upload.filename.to_owned(),
+        content_type: upload.content_type.to_string(),
+        size: upload.bytes.len() as i64,
+    };
+
+    let result = app_state
+        .db_client
+        .save_attachment(post.id, person.id, dto.clone())
+        .await;
+
+    let mut attachment = match result {
+        Ok(attachment) => attachment,
+        Err(e) => {
+            delete_stored_file(&app_state, &dto.storage_key).await;
+            return Err(DefaultHttpError::from(e));
+        }
+    };
+
+    if upload.is_image() {
+        attachment.variants = queue_variants(&app_state, &attachment.storage_key).await?;
+    }
+
+    Ok(ActixHttpResponse::Created().json(AttachmentResponseDto {
+        status: 201,
+        attachment: AttachmentDto::filter_attachment(&attachment),
+    }))
+}
+
+pub async fn delete_attachment(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetAttachmentParamsDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let post = find_visible_post(&app_state, path.post_id, Some(person.id)).await?;
+
+    if !can_manage_post(&post, &person) {
+        return Err(DefaultHttpError::forbidden(
+            "Only the author or an admin can remove attachments from this post",
+        ));
+    }
+
+    let result = app_state
+        .db_client
+        .delete_attachment(post.id, path.attachment_id)
+        .await;
+
+    match result {
+        Ok(Some(attachment)) => {
+            delete_stored_file(&app_state, &attachment.storage_key).await;
+
+            Ok(DefaultHttpResponse::ok("Attachment has been deleted").into_http_response())
+        }
+        Ok(None) => Err(DefaultHttpError::not_found("Attachment not found")
+            .with_code(ErrorCode::AttachmentNotFound)),
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+/// Writes an upload to storage under a fresh key below `prefix` and returns the key.
+pub(super) async fn store_upload(
+    app_state: &AppState,
+    prefix: &str,
+    upload: &FileUpload,
+) -> Result<String, DefaultHttpError> {
+    let key = format!("{}/{}.{}", prefix, Uuid::new_v4(), upload.extension);
+
+    app_state
+        .storage
+        .put(&key, upload.bytes.clone(), upload.content_type)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    Ok(key)
+}
+
+/// Queues the configured thumbnails of an uploaded image for the background job.
+pub(super) async fn queue_variants(
+    app_state: &AppState,
+    key: &str,
+) -> Result<Vec<ImageVariant>, DefaultHttpError> {
+    app_state
+        .db_client
+        .queue_image_variants(key, &app_state.env.thumbnail_variants)
+        .await
+        .map_err(DefaultHttpError::from)
+}
+
+/// Removes a file whose row is gone, along with the variants rendered from it.
+/// A failure only leaves unreferenced files behind, so it is logged instead of
+/// failing the request.
+pub(super) async fn delete_stored_file(app_state: &AppState, key: &str) {
+    let mut keys = vec![key.to_string()];
+
+    match app_state
+        .db_client
+        .delete_image_variants(&[key.to_string()])
+        .await
+    {
+        Ok(variant_keys) => keys.extend(variant_keys),
+        Err(e) => log::error!("Could not delete the variants of {}: {}", key, e),
+    }
+
+    for key in &keys {
+        if let Err(e) = app_state.storage.delete(key).await {
+            log::error!("Could not delete stored file {}: {}", key, e);
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use actix_web::{web, HttpResponse as ActixHttpResponse, Scope};
+use validator::Validate;
+
+use crate::{
+    db::person::PersonExt,
+    dtos::auth::{LoginDto, TokenResponseDto},
+    response::{DefaultHttpError, ErrorCode},
+    utils::{password, token},
+    AppState,
+};
+
+pub fn auth_scope() -> Scope {
+    web::scope("/api/auth")
+        // POST methods
+        .route("login", web::post().to(login))
+}
+
+pub async fn login(
+    app_state: web::Data<AppState>,
+    body: web::Json<LoginDto>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    body.validate().map_err(DefaultHttpError::from)?;
+
+    let person = app_state
+        .db_client
+        .get_person_by_username(&body.username)
+        .await
+        .map_err(DefaultHttpError::from)?
+        .ok_or_else(invalid_credentials)?;
+
+    let is_valid = password::compare(&body.password, &person.password).map_err(|e| {
+        DefaultHttpError::server_error("Could not process the password")
+            .with_internal(e.to_string())
+    })?;
+
+    if !is_valid {
+        return Err(invalid_credentials());
+    }
+
+    let token = token::create_token(
+        &person.id.to_string(),
+        app_state.env.jwt_secret.as_bytes(),
+        app_state.env.jwt_max_age,
+    )
+    .map_err(|e| {
+        DefaultHttpError::server_error("Could not create the token").with_internal(e.to_string())
+    })?;
+
+    Ok(ActixHttpResponse::Ok().json(TokenResponseDto { status: 200, token }))
+}
+
+fn invalid_credentials() -> DefaultHttpError {
+    DefaultHttpError::unauthorized("Invalid username or password")
+        .with_code(ErrorCode::InvalidCredentials)
+}
@@ -0,0 +1,194 @@
+use actix_web::{web, HttpResponse as ActixHttpResponse};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    db::{block::BlockExt, person::PersonExt},
+    dtos::{
+        block::SearchBlockQueryDto,
+        person::{GetUserParamsDto, UserDto, UserListResponseDto},
+    },
+    extractors::{auth::AuthenticatedPerson, id_path::IdPath},
+    models::User,
+    response::{DefaultHttpError, DefaultHttpResponse, ErrorCode, HttpResponse},
+    AppState,
+};
+
+use super::users::user_not_found;
+
+/// Registered by `users_scope`, so every route is relative to `/api/users/{user_id}`.
+pub fn blocks_routes(cfg: &mut web::ServiceConfig) {
+    cfg
+        // GET methods
+        .route("blocks", web::get().to(get_blocked_users))
+        .route("mutes", web::get().to(get_muted_users))
+        // PUT methods
+        .route("block", web::put().to(block_user))
+        .route("mute", web::put().to(mute_user))
+        // DELETE methods
+        .route("block", web::delete().to(unblock_user))
+        .route("mute", web::delete().to(unmute_user));
+}
+
+pub async fn block_user(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetUserParamsDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    if person.id == path.user_id {
+        return Err(DefaultHttpError::bad_request("You cannot block yourself")
+            .with_code(ErrorCode::InvalidValue));
+    }
+
+    find_user(&app_state, path.user_id).await?;
+
+    // Blocking twice is a no-op.
+    app_state
+        .db_client
+        .save_block(person.id, path.user_id)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    Ok(DefaultHttpResponse::ok("User has been blocked").into_http_response())
+}
+
+pub async fn unblock_user(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetUserParamsDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let result = app_state
+        .db_client
+        .delete_block(person.id, path.user_id)
+        .await;
+
+    match result {
+        Ok(true) => Ok(DefaultHttpResponse::ok("User has been unblocked").into_http_response()),
+        Ok(false) => Err(
+            DefaultHttpError::not_found("You have not blocked this user")
+                .with_code(ErrorCode::BlockNotFound),
+        ),
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+pub async fn mute_user(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetUserParamsDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    if person.id == path.user_id {
+        return Err(DefaultHttpError::bad_request("You cannot mute yourself")
+            .with_code(ErrorCode::InvalidValue));
+    }
+
+    find_user(&app_state, path.user_id).await?;
+
+    // Muting twice is a no-op.
+    app_state
+        .db_client
+        .save_mute(person.id, path.user_id)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    Ok(DefaultHttpResponse::ok("User has been muted").into_http_response())
+}
+
+pub async fn unmute_user(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetUserParamsDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let result = app_state
+        .db_client
+        .delete_mute(person.id, path.user_id)
+        .await;
+
+    match result {
+        Ok(true) => Ok(DefaultHttpResponse::ok("User has been unmuted").into_http_response()),
+        Ok(false) => Err(DefaultHttpError::not_found("You have not muted this user")
+            .with_code(ErrorCode::MuteNotFound)),
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+pub async fn get_blocked_users(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetUserParamsDto>,
+    query: web::Query<SearchBlockQueryDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let query_params: SearchBlockQueryDto = query.into_inner();
+
+    query_params.validate().map_err(DefaultHttpError::from)?;
+
+    if !can_view_blocks(path.user_id, &person) {
+        return Err(not_owner());
+    }
+
+    let users = app_state
+        .db_client
+        .get_blocked_users(
+            path.user_id,
+            query_params.page.unwrap_or(1),
+            query_params.limit.unwrap_or(20),
+        )
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    Ok(user_list_response(&users))
+}
+
+pub async fn get_muted_users(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetUserParamsDto>,
+    query: web::Query<SearchBlockQueryDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let query_params: SearchBlockQueryDto = query.into_inner();
+
+    query_params.validate().map_err(DefaultHttpError::from)?;
+
+    if !can_view_blocks(path.user_id, &person) {
+        return Err(not_owner());
+    }
+
+    let users = app_state
+        .db_client
+        .get_muted_users(
+            path.user_id,
+            query_params.page.unwrap_or(1),
+            query_params.limit.unwrap_or(20),
+        )
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    Ok(user_list_response(&users))
+}
+
+/// Blocking someone who blocked you first is allowed, so this does not hide
+/// blocked users the way `find_visible_user` does.
+async fn find_user(app_state: &AppState, user_id: Uuid) -> Result<User, DefaultHttpError> {
+    app_state
+        .db_client
+        .get_user(user_id)
+        .await
+        .map_err(DefaultHttpError::from)?
+        .ok_or_else(user_not_found)
+}
+
+fn can_view_blocks(user_id: Uuid, person: &AuthenticatedPerson) -> bool {
+    person.id == user_id || person.is_admin()
+}
+
+fn user_list_response(users: &[User]) -> ActixHttpResponse {
+    ActixHttpResponse::Ok().json(UserListResponseDto {
+        status: 200,
+        users: UserDto::filter_users(users),
+        results: users.len(),
+    })
+}
+
+fn not_owner() -> DefaultHttpError {
+    DefaultHttpError::forbidden("Only the user or an admin can see whom they blocked or muted")
+}
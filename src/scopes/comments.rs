@@ -0,0 +1,236 @@
+use actix_web::{web, HttpResponse as ActixHttpResponse, Scope};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    db::{block::BlockExt, comment::CommentExt},
+    dtos::{
+        comment::{
+            CommentDto, CommentListResponseDto, CommentResponseDto, CreateCommentDto,
+            GetCommentParamsDto, SearchCommentQueryDto, UpdateCommentDto,
+        },
+        post::GetPostParamsDto,
+    },
+    extractors::{auth::AuthenticatedPerson, id_path::IdPath},
+    models::{Comment, NotificationPayload},
+    response::{DefaultHttpError, DefaultHttpResponse, ErrorCode, HttpResponse},
+    AppState,
+};
+
+use super::{notifications::notify, posts::find_visible_post};
+
+/// Nested under `posts_scope`, so every route is relative to `/api/posts/{post_id}`.
+pub fn comments_scope() -> Scope {
+    web::scope("/{post_id}/comments")
+        // GET methods
+        .route("", web::get().to(get_comments))
+        .route("{comment_id}/replies", web::get().to(get_replies))
+        // POST methods
+        .route("", web::post().to(save_comment))
+        // PATCH methods
+        .route("{comment_id}", web::patch().to(update_comment))
+        // DELETE methods
+        .route("{comment_id}", web::delete().to(delete_comment))
+}
+
+pub async fn get_comments(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetPostParamsDto>,
+    query: web::Query<SearchCommentQueryDto>,
+    viewer: Option<AuthenticatedPerson>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let query_params: SearchCommentQueryDto = query.into_inner();
+
+    query_params.validate().map_err(DefaultHttpError::from)?;
+
+    let viewer_id = viewer.map(|v| v.id);
+
+    find_visible_post(&app_state, path.post_id, viewer_id).await?;
+
+    let comments = app_state
+        .db_client
+        .get_comments(path.post_id, None, query_params, viewer_id)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    Ok(ActixHttpResponse::Ok().json(CommentListResponseDto {
+        status: 200,
+        comments: CommentDto::filter_comments(&comments),
+        results: comments.len(),
+    }))
+}
+
+pub async fn get_replies(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetCommentParamsDto>,
+    query: web::Query<SearchCommentQueryDto>,
+    viewer: Option<AuthenticatedPerson>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let query_params: SearchCommentQueryDto = query.into_inner();
+
+    query_params.validate().map_err(DefaultHttpError::from)?;
+
+    let viewer_id = viewer.map(|v| v.id);
+
+    find_visible_post(&app_state, path.post_id, viewer_id).await?;
+
+    let comment = find_comment(&app_state, path.post_id, path.comment_id).await?;
+
+    let replies = app_state
+        .db_client
+        .get_comments(comment.post_id, Some(comment.id), query_params, viewer_id)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    Ok(ActixHttpResponse::Ok().json(CommentListResponseDto {
+        status: 200,
+        comments: CommentDto::filter_comments(&replies),
+        results: replies.len(),
+    }))
+}
+
+pub async fn save_comment(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetPostParamsDto>,
+    person: AuthenticatedPerson,
+    body: web::Json<CreateCommentDto>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    body.validate().map_err(DefaultHttpError::from)?;
+
+    let post = find_visible_post(&app_state, path.post_id, Some(person.id)).await?;
+
+    let mut parent = None;
+
+    if let Some(parent_id) = body.parent_id {
+        parent = app_state
+            .db_client
+            .get_comment(parent_id)
+            .await
+            .map_err(DefaultHttpError::from)?;
+
+        if let Some(parent) = &parent {
+            let is_blocked = app_state
+                .db_client
+                .is_blocked(parent.author_id, person.id)
+                .await
+                .map_err(DefaultHttpError::from)?;
+
+            if is_blocked {
+                return Err(DefaultHttpError::forbidden(
+                    "You cannot reply to this comment",
+                ));
+            }
+        }
+    }
+
+    let result = app_state
+        .db_client
+        .save_comment(path.post_id, person.id, body.into_inner())
+        .await;
+
+    match result {
+        Ok(comment) => {
+            // Replies only notify the author of the comment they answer.
+            match parent {
+                Some(parent) => {
+                    let payload = NotificationPayload::Reply {
+                        post_id: post.id,
+                        comment_id: comment.id,
+                        parent_id: parent.id,
+                    };
+
+                    notify(&app_state, parent.author_id, person.id, payload).await;
+                }
+                None => {
+                    if let Some(author_id) = post.author_id {
+                        let payload = NotificationPayload::Comment {
+                            post_id: post.id,
+                            comment_id: comment.id,
+                        };
+
+                        notify(&app_state, author_id, person.id, payload).await;
+                    }
+                }
+            }
+
+            Ok(ActixHttpResponse::Created().json(CommentResponseDto {
+                status: 201,
+                comment: CommentDto::filter_comment(&comment),
+            }))
+        }
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+pub async fn update_comment(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetCommentParamsDto>,
+    person: AuthenticatedPerson,
+    body: web::Json<UpdateCommentDto>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    body.validate().map_err(DefaultHttpError::from)?;
+
+    // Comments go with their post, once it is hidden from the person they can't be changed.
+    find_visible_post(&app_state, path.post_id, Some(person.id)).await?;
+
+    let comment = find_comment(&app_state, path.post_id, path.comment_id).await?;
+
+    if comment.author_id != person.id {
+        return Err(DefaultHttpError::forbidden(
+            "Only the author can edit this comment",
+        ));
+    }
+
+    let result = app_state
+        .db_client
+        .update_comment(comment.id, body.into_inner())
+        .await;
+
+    match result {
+        Ok(true) => Ok(DefaultHttpResponse::ok("Comment has been updated").into_http_response()),
+        Ok(false) => Err(comment_not_found()),
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+pub async fn delete_comment(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetCommentParamsDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    find_visible_post(&app_state, path.post_id, Some(person.id)).await?;
+
+    let comment = find_comment(&app_state, path.post_id, path.comment_id).await?;
+
+    if comment.author_id != person.id && !person.is_admin() {
+        return Err(DefaultHttpError::forbidden(
+            "Only the author or an admin can delete this comment",
+        ));
+    }
+
+    let result = app_state.db_client.delete_comment(comment.id).await;
+
+    match result {
+        Ok(true) => Ok(DefaultHttpResponse::ok("Comment has been deleted").into_http_response()),
+        Ok(false) => Err(comment_not_found()),
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+async fn find_comment(
+    app_state: &AppState,
+    post_id: Uuid,
+    comment_id: Uuid,
+) -> Result<Comment, DefaultHttpError> {
+    app_state
+        .db_client
+        .get_comment(comment_id)
+        .await
+        .map_err(DefaultHttpError::from)?
+        .filter(|comment| comment.post_id == post_id)
+        .ok_or_else(comment_not_found)
+}
+
+fn comment_not_found() -> DefaultHttpError {
+    DefaultHttpError::not_found("Comment not found").with_code(ErrorCode::CommentNotFound)
+}
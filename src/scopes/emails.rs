@@ -0,0 +1,243 @@
+use actix_web::{web, HttpResponse as ActixHttpResponse, Scope};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    db::email::EmailExt,
+    dtos::email::{
+        CreateEmailDto, EmailDto, EmailListResponseDto, EmailResponseDto, GetEmailByIdParamsDto,
+        GetEmailsByOwnerIdParamsDto, UpdateEmailAddressDto, UpdateEmailDto,
+        UpdateEmailPrimaryStatusDto, UpdateEmailPrivacyDto,
+    },
+    extractors::{auth::AuthenticatedPerson, id_path::IdPath},
+    models::Email,
+    response::{DefaultHttpError, DefaultHttpResponse, ErrorCode, HttpResponse},
+    AppState,
+};
+
+use super::users::can_manage_user;
+
+pub fn emails_scope() -> Scope {
+    web::scope("/api/emails")
+        // GET methods
+        .route("owner/{owner_id}", web::get().to(get_owner_emails))
+        .route("{id}", web::get().to(get_email))
+        // POST methods
+        .route("owner/{owner_id}", web::post().to(save_email))
+        // PATCH methods
+        .route("{id}/address", web::patch().to(update_email_address))
+        .route("{id}/privacy", web::patch().to(update_email_privacy))
+        .route("{id}/primary", web::patch().to(update_email_primary_status))
+        // DELETE methods
+        .route("{id}", web::delete().to(delete_email))
+}
+
+pub async fn get_owner_emails(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetEmailsByOwnerIdParamsDto>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let emails = app_state
+        .db_client
+        .get_person_emails(path.owner_id)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    let emails = EmailDto::filter_public_emails(&emails, false);
+
+    Ok(ActixHttpResponse::Ok().json(EmailListResponseDto {
+        status: 200,
+        results: emails.len(),
+        emails,
+        owner_id: path.owner_id,
+    }))
+}
+
+pub async fn get_email(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetEmailByIdParamsDto>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let email = find_email(&app_state, path.id).await?;
+
+    if email.is_private {
+        return Err(email_not_found());
+    }
+
+    Ok(ActixHttpResponse::Ok().json(EmailResponseDto {
+        status: 200,
+        email: EmailDto::filter_email(&email, false),
+        owner_id: email.owner_id,
+    }))
+}
+
+pub async fn save_email(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetEmailsByOwnerIdParamsDto>,
+    person: AuthenticatedPerson,
+    body: web::Json<CreateEmailDto>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    if !can_manage_user(&person, path.owner_id) {
+        return Err(emails_forbidden());
+    }
+
+    body.validate().map_err(DefaultHttpError::from)?;
+
+    let result = app_state
+        .db_client
+        .save_email(path.owner_id, body.into_inner())
+        .await;
+
+    match result {
+        Ok(email) => Ok(ActixHttpResponse::Created().json(EmailResponseDto {
+            status: 201,
+            email: EmailDto::filter_email(&email, false),
+            owner_id: email.owner_id,
+        })),
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+pub async fn update_email_address(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetEmailByIdParamsDto>,
+    person: AuthenticatedPerson,
+    body: web::Json<UpdateEmailAddressDto>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    body.validate().map_err(DefaultHttpError::from)?;
+
+    let dto = UpdateEmailDto {
+        address: body.into_inner().address,
+        ..Default::default()
+    };
+
+    update_email(
+        &app_state,
+        path.id,
+        &person,
+        dto,
+        "Email address has been updated",
+    )
+    .await
+}
+
+pub async fn update_email_privacy(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetEmailByIdParamsDto>,
+    person: AuthenticatedPerson,
+    body: web::Json<UpdateEmailPrivacyDto>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    body.validate().map_err(DefaultHttpError::from)?;
+
+    let dto = UpdateEmailDto {
+        is_private: body.into_inner().is_private,
+        ..Default::default()
+    };
+
+    update_email(
+        &app_state,
+        path.id,
+        &person,
+        dto,
+        "Email privacy has been updated",
+    )
+    .await
+}
+
+pub async fn update_email_primary_status(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetEmailByIdParamsDto>,
+    person: AuthenticatedPerson,
+    body: web::Json<UpdateEmailPrimaryStatusDto>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    body.validate().map_err(DefaultHttpError::from)?;
+
+    let dto = UpdateEmailDto {
+        is_primary: body.into_inner().is_primary,
+        ..Default::default()
+    };
+
+    update_email(
+        &app_state,
+        path.id,
+        &person,
+        dto,
+        "Email primary status has been updated",
+    )
+    .await
+}
+
+pub async fn delete_email(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetEmailByIdParamsDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let email = find_managed_email(&app_state, path.id, &person).await?;
+
+    let result = app_state
+        .db_client
+        .delete_email(email.owner_id, email.id)
+        .await;
+
+    match result {
+        Ok(true) => Ok(DefaultHttpResponse::ok("Email has been deleted").into_http_response()),
+        Ok(false) => Err(email_not_found()),
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+async fn update_email(
+    app_state: &AppState,
+    email_id: Uuid,
+    person: &AuthenticatedPerson,
+    dto: UpdateEmailDto,
+    message: &str,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let email = find_managed_email(app_state, email_id, person).await?;
+
+    let result = app_state
+        .db_client
+        .update_email(email.owner_id, email.id, dto)
+        .await;
+
+    match result {
+        Ok(true) => Ok(DefaultHttpResponse::ok(message).into_http_response()),
+        Ok(false) => Err(email_not_found()),
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+async fn find_email(app_state: &AppState, email_id: Uuid) -> Result<Email, DefaultHttpError> {
+    app_state
+        .db_client
+        .get_email_by_id(email_id)
+        .await
+        .map_err(DefaultHttpError::from)?
+        .ok_or_else(email_not_found)
+}
+
+/// Loads an email the person may change or delete. Private emails of others
+/// are reported missing, as `get_email` does.
+async fn find_managed_email(
+    app_state: &AppState,
+    email_id: Uuid,
+    person: &AuthenticatedPerson,
+) -> Result<Email, DefaultHttpError> {
+    let email = find_email(app_state, email_id).await?;
+
+    if !can_manage_user(person, email.owner_id) {
+        if email.is_private {
+            return Err(email_not_found());
+        }
+
+        return Err(emails_forbidden());
+    }
+
+    Ok(email)
+}
+
+fn emails_forbidden() -> DefaultHttpError {
+    DefaultHttpError::forbidden("Only the owner or an admin can manage these emails")
+}
+
+fn email_not_found() -> DefaultHttpError {
+    DefaultHttpError::not_found("Email not found").with_code(ErrorCode::EmailNotFound)
+}
@@ -0,0 +1,55 @@
+use actix_web::{web, HttpResponse as ActixHttpResponse, Scope};
+use validator::Validate;
+
+use crate::{
+    db::post::PostExt,
+    dtos::post::{FeedQueryDto, FeedResponseDto},
+    extractors::auth::AuthenticatedPerson,
+    response::DefaultHttpError,
+    utils::cursor::Cursor,
+    AppState,
+};
+
+use super::posts::post_list;
+
+pub fn feed_scope() -> Scope {
+    web::scope("/api/feed")
+        // GET methods
+        .route("", web::get().to(get_feed))
+}
+
+pub async fn get_feed(
+    app_state: web::Data<AppState>,
+    query: web::Query<FeedQueryDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let query_params: FeedQueryDto = query.into_inner();
+
+    query_params.validate().map_err(DefaultHttpError::from)?;
+
+    let limit = query_params.limit.unwrap_or(20);
+    let after = query_params.cursor.as_deref().and_then(Cursor::decode);
+
+    // One extra post tells whether there is a next page.
+    let mut posts = app_state
+        .db_client
+        .get_feed(person.id, after, limit as i64 + 1)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    let next_cursor = if posts.len() > limit {
+        posts.truncate(limit);
+        posts
+            .last()
+            .and_then(|post| Some(Cursor::new(post.published_at?, post.id).encode()))
+    } else {
+        None
+    };
+
+    Ok(ActixHttpResponse::Ok().json(FeedResponseDto {
+        status: 200,
+        posts: post_list(&app_state, &posts, Some(person.id)).await?,
+        results: posts.len(),
+        next_cursor,
+    }))
+}
@@ -0,0 +1,324 @@
+use actix_web::{web, HttpResponse as ActixHttpResponse};
+use validator::Validate;
+
+use crate::{
+    db::follow::FollowExt,
+    dtos::{
+        follow::{FollowDto, FollowResponseDto, GetFollowerParamsDto, SearchFollowQueryDto},
+        person::{GetUserParamsDto, UserDto, UserListResponseDto},
+    },
+    extractors::{auth::AuthenticatedPerson, id_path::IdPath},
+    models::{FollowStatus, NotificationPayload, User},
+    response::{DefaultHttpError, DefaultHttpResponse, ErrorCode, HttpResponse},
+    AppState,
+};
+
+use super::{
+    notifications::notify,
+    users::{find_visible_user, user_not_found},
+};
+
+/// Registered by `users_scope`, so every route is relative to `/api/users/{user_id}`.
+pub fn follows_routes(cfg: &mut web::ServiceConfig) {
+    cfg
+        // GET methods
+        .route("followers", web::get().to(get_followers))
+        .route("following", web::get().to(get_following))
+        .route("follow-requests", web::get().to(get_follow_requests))
+        // POST methods
+        .route("follow", web::post().to(follow_user))
+        .route(
+            "follow-requests/{follower_id}/approve",
+            web::post().to(approve_follow_request),
+        )
+        // DELETE methods
+        .route("follow", web::delete().to(unfollow_user))
+        .route("followers/{follower_id}", web::delete().to(remove_follower))
+        .route(
+            "follow-requests/{follower_id}",
+            web::delete().to(decline_follow_request),
+        );
+}
+
+pub async fn follow_user(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetUserParamsDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    if person.id == path.user_id {
+        return Err(DefaultHttpError::bad_request("You cannot follow yourself")
+            .with_code(ErrorCode::InvalidValue));
+    }
+
+    let result = app_state
+        .db_client
+        .save_follow(person.id, path.user_id)
+        .await;
+
+    match result {
+        Ok(Some(follow)) => {
+            let payload = match follow.status {
+                FollowStatus::Pending => NotificationPayload::FollowRequest,
+                FollowStatus::Accepted => NotificationPayload::Follow,
+            };
+
+            notify(&app_state, follow.followee_id, person.id, payload).await;
+
+            Ok(ActixHttpResponse::Created().json(FollowResponseDto {
+                status: 201,
+                follow: FollowDto::filter_follow(&follow),
+            }))
+        }
+        // Following twice is a no-op that returns the existing follow or request.
+        Ok(None) => {
+            let follow = app_state
+                .db_client
+                .get_follow(person.id, path.user_id)
+                .await
+                .map_err(DefaultHttpError::from)?
+                .ok_or_else(user_not_found)?;
+
+            Ok(ActixHttpResponse::Ok().json(FollowResponseDto {
+                status: 200,
+                follow: FollowDto::filter_follow(&follow),
+            }))
+        }
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+pub async fn unfollow_user(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetUserParamsDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    // Also withdraws a request that is still pending.
+    let result = app_state
+        .db_client
+        .delete_follow(person.id, path.user_id, None)
+        .await;
+
+    match result {
+        Ok(true) => Ok(DefaultHttpResponse::ok("User has been unfollowed").into_http_response()),
+        Ok(false) => Err(
+            DefaultHttpError::not_found("You are not following this user")
+                .with_code(ErrorCode::FollowNotFound),
+        ),
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+pub async fn get_followers(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetUserParamsDto>,
+    query: web::Query<SearchFollowQueryDto>,
+    viewer: Option<AuthenticatedPerson>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let query_params: SearchFollowQueryDto = query.into_inner();
+
+    query_params.validate().map_err(DefaultHttpError::from)?;
+
+    let user = find_visible_user(&app_state, path.user_id, viewer.as_ref().map(|v| v.id)).await?;
+
+    if !can_view_follows(&app_state, &user, viewer.as_ref()).await? {
+        return Err(private_profile());
+    }
+
+    let followers = app_state
+        .db_client
+        .get_followers(
+            user.id,
+            FollowStatus::Accepted,
+            query_params.page.unwrap_or(1),
+            query_params.limit.unwrap_or(20),
+        )
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    Ok(user_list_response(&followers))
+}
+
+pub async fn get_following(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetUserParamsDto>,
+    query: web::Query<SearchFollowQueryDto>,
+    viewer: Option<AuthenticatedPerson>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let query_params: SearchFollowQueryDto = query.into_inner();
+
+    query_params.validate().map_err(DefaultHttpError::from)?;
+
+    let user = find_visible_user(&app_state, path.user_id, viewer.as_ref().map(|v| v.id)).await?;
+
+    if !can_view_follows(&app_state, &user, viewer.as_ref()).await? {
+        return Err(private_profile());
+    }
+
+    let following = app_state
+        .db_client
+        .get_following(
+            user.id,
+            query_params.page.unwrap_or(1),
+            query_params.limit.unwrap_or(20),
+        )
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    Ok(user_list_response(&following))
+}
+
+pub async fn get_follow_requests(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetUserParamsDto>,
+    query: web::Query<SearchFollowQueryDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let query_params: SearchFollowQueryDto = query.into_inner();
+
+    query_params.validate().map_err(DefaultHttpError::from)?;
+
+    if person.id != path.user_id {
+        return Err(not_followee());
+    }
+
+    let requests = app_state
+        .db_client
+        .get_followers(
+            path.user_id,
+            FollowStatus::Pending,
+            query_params.page.unwrap_or(1),
+            query_params.limit.unwrap_or(20),
+        )
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    Ok(user_list_response(&requests))
+}
+
+pub async fn approve_follow_request(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetFollowerParamsDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    if person.id != path.user_id {
+        return Err(not_followee());
+    }
+
+    let result = app_state
+        .db_client
+        .accept_follow(path.follower_id, path.user_id)
+        .await;
+
+    match result {
+        Ok(true) => {
+            notify(
+                &app_state,
+                path.follower_id,
+                path.user_id,
+                NotificationPayload::FollowAccepted,
+            )
+            .await;
+
+            Ok(DefaultHttpResponse::ok("Follow request has been approved").into_http_response())
+        }
+        Ok(false) => Err(follow_request_not_found()),
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+pub async fn decline_follow_request(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetFollowerParamsDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    if person.id != path.user_id {
+        return Err(not_followee());
+    }
+
+    let result = app_state
+        .db_client
+        .delete_follow(path.follower_id, path.user_id, Some(FollowStatus::Pending))
+        .await;
+
+    match result {
+        Ok(true) => {
+            Ok(DefaultHttpResponse::ok("Follow request has been declined").into_http_response())
+        }
+        Ok(false) => Err(follow_request_not_found()),
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+pub async fn remove_follower(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetFollowerParamsDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    if person.id != path.user_id {
+        return Err(not_followee());
+    }
+
+    let result = app_state
+        .db_client
+        .delete_follow(path.follower_id, path.user_id, Some(FollowStatus::Accepted))
+        .await;
+
+    match result {
+        Ok(true) => Ok(DefaultHttpResponse::ok("Follower has been removed").into_http_response()),
+        Ok(false) => {
+            Err(DefaultHttpError::not_found("Follower not found")
+                .with_code(ErrorCode::FollowNotFound))
+        }
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+/// Who follows a private profile, whom it follows and what it posts as it
+/// happens are only shown to the user, their accepted followers and admins.
+pub(super) async fn can_view_follows(
+    app_state: &AppState,
+    user: &User,
+    viewer: Option<&AuthenticatedPerson>,
+) -> Result<bool, DefaultHttpError> {
+    if !user.is_profile_private {
+        return Ok(true);
+    }
+
+    let Some(viewer) = viewer else {
+        return Ok(false);
+    };
+
+    if viewer.id == user.id || viewer.is_admin() {
+        return Ok(true);
+    }
+
+    let follow = app_state
+        .db_client
+        .get_follow(viewer.id, user.id)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    Ok(follow.is_some_and(|follow| follow.status == FollowStatus::Accepted))
+}
+
+fn user_list_response(users: &[User]) -> ActixHttpResponse {
+    ActixHttpResponse::Ok().json(UserListResponseDto {
+        status: 200,
+        users: UserDto::filter_users(users),
+        results: users.len(),
+    })
+}
+
+pub(super) fn private_profile() -> DefaultHttpError {
+    DefaultHttpError::forbidden("This profile is private")
+}
+
+/// Who may follow a profile is only up to its user, admins included, as with
+/// its privacy.
+fn not_followee() -> DefaultHttpError {
+    DefaultHttpError::forbidden("Only the user can manage their followers")
+}
+
+fn follow_request_not_found() -> DefaultHttpError {
+    DefaultHttpError::not_found("Follow request not found").with_code(ErrorCode::FollowNotFound)
+}
@@ -1 +1,15 @@
+pub mod admins;
+pub mod attachments;
+pub mod auth;
+pub mod blocks;
+pub mod comments;
+pub mod emails;
+pub mod feed;
+pub mod follows;
+pub mod notifications;
 pub mod posts;
+pub mod revisions;
+pub mod stream;
+pub mod tags;
+pub mod users;
+pub mod ws;
@@ -0,0 +1,184 @@
+use actix_web::{web, HttpResponse as ActixHttpResponse, Scope};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    db::notification::NotificationExt,
+    dtos::notification::{
+        GetNotificationParamsDto, NotificationDto, NotificationListResponseDto,
+        NotificationPreferencesDto, NotificationPreferencesResponseDto, SearchNotificationQueryDto,
+        UpdateNotificationPreferencesDto,
+    },
+    events::Event,
+    extractors::{auth::AuthenticatedPerson, id_path::IdPath},
+    models::{NotificationKind, NotificationPayload},
+    response::{DefaultHttpError, DefaultHttpResponse, ErrorCode, HttpResponse},
+    utils::cursor::Cursor,
+    AppState,
+};
+
+use super::users::user_not_found;
+
+pub fn notifications_scope() -> Scope {
+    web::scope("/api/me/notifications")
+        // GET methods
+        .route("", web::get().to(get_notifications))
+        .route("preferences", web::get().to(get_notification_preferences))
+        // POST methods
+        .route("read", web::post().to(mark_all_notifications_read))
+        .route(
+            "{notification_id}/read",
+            web::post().to(mark_notification_read),
+        )
+        // PATCH methods
+        .route(
+            "preferences",
+            web::patch().to(update_notification_preferences),
+        )
+}
+
+pub async fn get_notifications(
+    app_state: web::Data<AppState>,
+    query: web::Query<SearchNotificationQueryDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let query_params: SearchNotificationQueryDto = query.into_inner();
+
+    query_params.validate().map_err(DefaultHttpError::from)?;
+
+    let limit = query_params.limit.unwrap_or(20);
+    let after = query_params.cursor.as_deref().and_then(Cursor::decode);
+
+    // One extra notification tells whether there is a next page.
+    let mut notifications = app_state
+        .db_client
+        .get_notifications(
+            person.id,
+            query_params.unread.unwrap_or(false),
+            after,
+            limit as i64 + 1,
+        )
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    let next_cursor = if notifications.len() > limit {
+        notifications.truncate(limit);
+        notifications
+            .last()
+            .map(|notification| Cursor::new(notification.created_at, notification.id).encode())
+    } else {
+        None
+    };
+
+    let unread_count = app_state
+        .db_client
+        .get_unread_notifications_count(person.id)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    Ok(ActixHttpResponse::Ok().json(NotificationListResponseDto {
+        status: 200,
+        notifications: NotificationDto::filter_notifications(&notifications),
+        results: notifications.len(),
+        unread_count,
+        next_cursor,
+    }))
+}
+
+pub async fn mark_notification_read(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetNotificationParamsDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let result = app_state
+        .db_client
+        .mark_notification_read(path.notification_id, person.id)
+        .await;
+
+    match result {
+        Ok(true) => Ok(
+            DefaultHttpResponse::ok("Notification has been marked as read").into_http_response(),
+        ),
+        // Someone else's notifications look just like missing ones.
+        Ok(false) => Err(DefaultHttpError::not_found("Notification not found")
+            .with_code(ErrorCode::NotificationNotFound)),
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+pub async fn mark_all_notifications_read(
+    app_state: web::Data<AppState>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    app_state
+        .db_client
+        .mark_all_notifications_read(person.id)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    Ok(DefaultHttpResponse::ok("All notifications have been marked as read").into_http_response())
+}
+
+pub async fn get_notification_preferences(
+    app_state: web::Data<AppState>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let disabled = app_state
+        .db_client
+        .get_disabled_notification_kinds(person.id)
+        .await
+        .map_err(DefaultHttpError::from)?
+        .ok_or_else(user_not_found)?;
+
+    Ok(preferences_response(&disabled))
+}
+
+pub async fn update_notification_preferences(
+    app_state: web::Data<AppState>,
+    person: AuthenticatedPerson,
+    body: web::Json<UpdateNotificationPreferencesDto>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    body.validate().map_err(DefaultHttpError::from)?;
+
+    let (enabled, disabled) = body.changes();
+
+    let disabled = app_state
+        .db_client
+        .update_disabled_notification_kinds(person.id, &enabled, &disabled)
+        .await
+        .map_err(DefaultHttpError::from)?
+        .ok_or_else(user_not_found)?;
+
+    Ok(preferences_response(&disabled))
+}
+
+/// Notifies `recipient_id` of something `actor_id` just did. The action that
+/// triggered it already succeeded, so a failure here is only logged.
+pub(super) async fn notify(
+    app_state: &AppState,
+    recipient_id: Uuid,
+    actor_id: Uuid,
+    payload: NotificationPayload,
+) {
+    let result = app_state
+        .db_client
+        .save_notification(recipient_id, actor_id, payload)
+        .await;
+
+    match result {
+        Ok(Some(notification)) => app_state.events.publish(Event::Notification {
+            recipient_id,
+            notification: NotificationDto::filter_notification(&notification),
+        }),
+        // Turned off, or from someone the recipient does not want to hear from.
+        Ok(None) => {}
+        Err(e) => log::error!("Could not notify {}: {}", recipient_id, e),
+    }
+}
+
+fn preferences_response(disabled: &[NotificationKind]) -> ActixHttpResponse {
+    ActixHttpResponse::Ok().json(NotificationPreferencesResponseDto {
+        status: 200,
+        preferences: NotificationPreferencesDto::from_disabled(disabled),
+    })
+}
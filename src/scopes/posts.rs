@@ -1,169 +1,693 @@
-use actix_web::{web, HttpResponse as ActixHttpResponse, Scope};
+use actix_web::{http::header, web, HttpResponse as ActixHttpResponse, Scope};
+use chrono::{Duration, Utc};
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
-    db::post::PostExt,
+    db::{
+        attachment::AttachmentExt, block::BlockExt, post::PostExt, reaction::ReactionExt,
+        tag::TagExt,
+    },
     dtos::post::{
-        CreatePostDto, GetPostParamsDto, PostDto, PostListResponseDto, PostResponseDto,
-        SearchPostQueryDto, UpdatePostDto,
+        CreatePostDto, GetPostParamsDto, GetPostSlugParamsDto, GetReactionParamsDto, PostDto,
+        PostListResponseDto, PostResponseDto, SearchPostQueryDto, UpdatePostDto,
+    },
+    events::Event,
+    extractors::{
+        auth::AuthenticatedPerson,
+        id_path::IdPath,
+        if_match::{etag, IfMatch},
     },
-    response::{DefaultHttpError, DefaultHttpResponse, HttpResponse},
+    models::{NotificationPayload, Post, PostStatus, ReactionKind},
+    response::{DefaultHttpError, DefaultHttpResponse, ErrorCode, FieldError, HttpResponse},
     AppState,
 };
 
+use super::{
+    attachments::attachments_scope, comments::comments_scope, notifications::notify,
+    revisions::revisions_scope, stream::stream_posts,
+};
+
 pub fn posts_scope() -> Scope {
     web::scope("/api/posts")
         // GET methods
         .route("", web::get().to(get_posts))
+        .route("scheduled", web::get().to(get_scheduled_posts))
+        .route("stream", web::get().to(stream_posts))
+        .route("trash", web::get().to(get_deleted_posts))
+        .route("by-slug/{slug}", web::get().to(get_post_by_slug))
         .route("{post_id}", web::get().to(get_post))
         // POST methods
         .route("", web::post().to(save_post))
+        .route("{post_id}/publish", web::post().to(publish_post))
+        .route("{post_id}/archive", web::post().to(archive_post))
+        .route("{post_id}/restore", web::post().to(restore_post))
+        // PUT methods
+        .route("{post_id}/reactions/{kind}", web::put().to(save_reaction))
         // PATCH methods
         .route("{post_id}", web::patch().to(update_post))
         // DELETE methods
         .route("{post_id}", web::delete().to(delete_post))
+        .route(
+            "{post_id}/schedule",
+            web::delete().to(cancel_scheduled_post),
+        )
+        .route(
+            "{post_id}/reactions/{kind}",
+            web::delete().to(delete_reaction),
+        )
+        // Nested scopes
+        .service(comments_scope())
+        .service(revisions_scope())
+        .service(attachments_scope())
 }
 
 pub async fn get_posts(
     query: web::Query<SearchPostQueryDto>,
     app_state: web::Data<AppState>,
+    viewer: Option<AuthenticatedPerson>,
 ) -> Result<ActixHttpResponse, DefaultHttpError> {
     let query_params: SearchPostQueryDto = query.into_inner();
 
-    query_params
-        .validate()
-        .map_err(|e| DefaultHttpError::bad_request(e.to_string()))?;
+    query_params.validate().map_err(DefaultHttpError::from)?;
 
     let posts = app_state
         .db_client
-        .get_posts(query_params)
+        .get_posts(query_params, viewer.as_ref().map(|v| v.id))
         .await
-        .map_err(|e| DefaultHttpError::server_error(e.to_string()))?;
+        .map_err(DefaultHttpError::from)?;
 
     Ok(ActixHttpResponse::Ok().json(PostListResponseDto {
         status: 200,
-        posts: PostDto::filter_posts(&posts),
+        posts: post_list(&app_state, &posts, viewer.map(|v| v.id)).await?,
         results: posts.len(),
     }))
 }
 
 pub async fn get_post(
     app_state: web::Data<AppState>,
-    path: web::Path<GetPostParamsDto>,
+    path: IdPath<GetPostParamsDto>,
+    viewer: Option<AuthenticatedPerson>,
 ) -> Result<ActixHttpResponse, DefaultHttpError> {
-    let post_id = Uuid::parse_str(&path.post_id);
+    let viewer_id = viewer.map(|v| v.id);
 
-    if let Ok(id) = post_id {
-        let result = app_state.db_client.get_post(id).await;
+    let post = find_visible_post(&app_state, path.post_id, viewer_id).await?;
 
-        return match result {
-            Ok(post) => {
-                if let Some(post) = post {
-                    return Ok(ActixHttpResponse::Ok().json(PostResponseDto {
-                        status: 200,
-                        post: PostDto::filter_post(&post),
-                    }));
-                }
+    post_response(&app_state, post, viewer_id).await
+}
 
-                Err(DefaultHttpError::not_found("Post not found".to_string()))
-            }
+pub async fn get_post_by_slug(
+    app_state: web::Data<AppState>,
+    path: web::Path<GetPostSlugParamsDto>,
+    viewer: Option<AuthenticatedPerson>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let viewer_id = viewer.map(|v| v.id);
 
-            Err(sqlx::Error::Database(db_err)) => {
-                Err(DefaultHttpError::server_error(db_err.to_string()))
-            }
-            Err(e) => Err(DefaultHttpError::server_error(e.to_string())),
-        };
+    let post = app_state
+        .db_client
+        .get_post_by_slug(&path.slug)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    if let Some(post) = post.filter(|post| post.is_visible_to(viewer_id)) {
+        if is_author_blocked(&app_state, &post, viewer_id).await? {
+            return Err(post_not_found());
+        }
+
+        return post_response(&app_state, post, viewer_id).await;
     }
 
-    Err(DefaultHttpError::server_error(
-        "The provided id is not valid".to_string(),
-    ))
+    // Slugs replaced by a title change permanently redirect to the current one.
+    let redirected_slug = app_state
+        .db_client
+        .get_redirected_slug(&path.slug)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    match redirected_slug {
+        Some(slug) => Ok(ActixHttpResponse::MovedPermanently()
+            .insert_header((header::LOCATION, format!("/api/posts/by-slug/{}", slug)))
+            .finish()),
+        None => Err(post_not_found()),
+    }
 }
 
 pub async fn save_post(
     app_state: web::Data<AppState>,
+    person: AuthenticatedPerson,
     body: web::Json<CreatePostDto>,
 ) -> Result<ActixHttpResponse, DefaultHttpError> {
-    body.validate()
-        .map_err(|e| DefaultHttpError::bad_request(e.to_string()))?;
+    body.validate().map_err(DefaultHttpError::from)?;
 
-    let result = app_state.db_client.save_post(body.into_inner()).await;
+    if body.publish == Some(true) && body.scheduled_at.is_some() {
+        return Err(DefaultHttpError::bad_request(
+            "A post cannot be published and scheduled at once",
+        )
+        .with_code(ErrorCode::ValidationFailed)
+        .with_errors(vec![FieldError {
+            field: String::from("scheduled_at"),
+            code: String::from("conflict"),
+            message: String::from("Scheduled posts cannot be published right away"),
+        }]));
+    }
 
-    match result {
-        Ok(post) => Ok(ActixHttpResponse::Created().json(PostResponseDto {
-            status: 200,
-            post: PostDto::filter_post(&post),
-        })),
-        Err(sqlx::Error::Database(db_err)) => {
-            Err(DefaultHttpError::server_error(db_err.to_string()))
-        }
-        Err(e) => Err(DefaultHttpError::server_error(e.to_string())),
+    let post = app_state
+        .db_client
+        .save_post(person.id, body.into_inner())
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    if post.status == PostStatus::Published {
+        app_state.events.publish(Event::post_created(&post));
     }
+
+    let tags = app_state
+        .db_client
+        .get_posts_tags(&[post.id])
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    Ok(ActixHttpResponse::Created()
+        .insert_header((header::ETAG, etag(post.version)))
+        .json(PostResponseDto {
+            status: 200,
+            post: PostDto::filter_post(&post, &tags, &[], &[]),
+        }))
 }
 
 pub async fn update_post(
     app_state: web::Data<AppState>,
-    path: web::Path<GetPostParamsDto>,
+    path: IdPath<GetPostParamsDto>,
+    person: AuthenticatedPerson,
+    if_match: IfMatch,
     body: web::Json<UpdatePostDto>,
 ) -> Result<ActixHttpResponse, DefaultHttpError> {
-    let post_id = Uuid::parse_str(&path.post_id);
+    body.validate().map_err(DefaultHttpError::from)?;
+
+    let post = find_visible_post(&app_state, path.post_id, Some(person.id)).await?;
 
-    body.validate()
-        .map_err(|e| DefaultHttpError::bad_request(e.to_string()))?;
+    if !can_manage_post(&post, &person) {
+        return Err(DefaultHttpError::forbidden(
+            "Only the author or an admin can edit this post",
+        ));
+    }
+
+    if !if_match.matches(post.version) {
+        return Err(post_modified());
+    }
 
-    if let Ok(id) = post_id {
-        let result = app_state.db_client.update_post(id, body.into_inner()).await;
+    let has_precondition = if_match.0.is_some();
 
-        return match result {
-            Ok(is_updated) => {
-                if is_updated {
-                    return Ok(
-                        DefaultHttpResponse::ok("Post has been updated").into_http_response()
-                    );
+    let result = app_state
+        .db_client
+        .update_post(post.id, person.id, body.into_inner(), if_match.0)
+        .await;
+
+    match result {
+        Ok(is_updated) => {
+            if is_updated {
+                if post.status != PostStatus::Draft {
+                    app_state.events.publish(Event::post_updated(&post));
                 }
 
-                Err(DefaultHttpError::not_found("Post not found".to_string()))
+                return Ok(DefaultHttpResponse::ok("Post has been updated").into_http_response());
             }
-            Err(sqlx::Error::Database(db_err)) => {
-                Err(DefaultHttpError::server_error(db_err.to_string()))
+
+            // The version moved on between the read above and the update.
+            if has_precondition {
+                return Err(post_modified());
             }
-            Err(e) => Err(DefaultHttpError::server_error(e.to_string())),
-        };
-    }
 
-    Err(DefaultHttpError::server_error(
-        "The provided id is not valid".to_string(),
-    ))
+            Err(DefaultHttpError::not_found("Post not found").with_code(ErrorCode::PostNotFound))
+        }
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
 }
 
 pub async fn delete_post(
     app_state: web::Data<AppState>,
-    path: web::Path<GetPostParamsDto>,
+    path: IdPath<GetPostParamsDto>,
+    person: AuthenticatedPerson,
 ) -> Result<ActixHttpResponse, DefaultHttpError> {
-    let post_id = Uuid::parse_str(&path.post_id);
+    let post = find_visible_post(&app_state, path.post_id, Some(person.id)).await?;
+
+    if !can_manage_post(&post, &person) {
+        return Err(DefaultHttpError::forbidden(
+            "Only the author or an admin can delete this post",
+        ));
+    }
 
-    if let Ok(id) = post_id {
-        let result = app_state.db_client.delete_post(id).await;
+    let result = app_state.db_client.delete_post(post.id).await;
 
-        return match result {
-            Ok(is_deleted) => {
-                if is_deleted {
-                    return Ok(
-                        DefaultHttpResponse::ok("Post has been deleted").into_http_response()
-                    );
+    match result {
+        Ok(is_deleted) => {
+            if is_deleted {
+                if post.status != PostStatus::Draft {
+                    app_state.events.publish(Event::post_deleted(&post));
                 }
 
-                Err(DefaultHttpError::not_found("Post not found".to_string()))
-            }
-            Err(sqlx::Error::Database(db_err)) => {
-                Err(DefaultHttpError::server_error(db_err.to_string()))
+                return Ok(DefaultHttpResponse::ok("Post has been deleted").into_http_response());
             }
-            Err(e) => Err(DefaultHttpError::server_error(e.to_string())),
+
+            Err(DefaultHttpError::not_found("Post not found").with_code(ErrorCode::PostNotFound))
+        }
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+pub async fn get_deleted_posts(
+    app_state: web::Data<AppState>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    // Admins see the whole trash, everyone else sees their own posts.
+    let author_id = if person.is_admin() {
+        None
+    } else {
+        Some(person.id)
+    };
+
+    let posts = app_state
+        .db_client
+        .get_deleted_posts(author_id)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    let post_ids: Vec<Uuid> = posts.iter().map(|post| post.id).collect();
+
+    let tags = app_state
+        .db_client
+        .get_posts_tags(&post_ids)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    let attachments = app_state
+        .db_client
+        .get_posts_attachments(&post_ids)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    Ok(ActixHttpResponse::Ok().json(PostListResponseDto {
+        status: 200,
+        posts: PostDto::filter_posts(&posts, &tags, &attachments, &[]),
+        results: posts.len(),
+    }))
+}
+
+pub async fn restore_post(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetPostParamsDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let retention_days = app_state.env.trash_retention_days;
+
+    let post = app_state
+        .db_client
+        .get_deleted_post(path.post_id)
+        .await
+        .map_err(DefaultHttpError::from)?
+        .ok_or_else(|| {
+            DefaultHttpError::not_found("Post not found in the trash")
+                .with_code(ErrorCode::PostNotFound)
+        })?;
+
+    if !can_manage_post(&post, &person) {
+        return Err(DefaultHttpError::forbidden(
+            "Only the author or an admin can restore this post",
+        ));
+    }
+
+    let is_expired = post
+        .deleted_at
+        .is_some_and(|deleted_at| deleted_at + Duration::days(retention_days) <= Utc::now());
+
+    if is_expired {
+        return Err(DefaultHttpError::unique_constraint_voilation(format!(
+            "Posts can only be restored within {} days of their deletion",
+            retention_days
+        ))
+        .with_code(ErrorCode::RestorePeriodExpired));
+    }
+
+    let result = app_state
+        .db_client
+        .restore_post(post.id, retention_days)
+        .await;
+
+    match result {
+        Ok(Some(post)) => {
+            let tags = app_state
+                .db_client
+                .get_posts_tags(&[post.id])
+                .await
+                .map_err(DefaultHttpError::from)?;
+
+            let attachments = app_state
+                .db_client
+                .get_posts_attachments(&[post.id])
+                .await
+                .map_err(DefaultHttpError::from)?;
+
+            Ok(ActixHttpResponse::Ok()
+                .insert_header((header::ETAG, etag(post.version)))
+                .json(PostResponseDto {
+                    status: 200,
+                    post: PostDto::filter_post(&post, &tags, &attachments, &[]),
+                }))
+        }
+        // Restored or purged in the meantime.
+        Ok(None) => Err(DefaultHttpError::not_found("Post not found in the trash")
+            .with_code(ErrorCode::PostNotFound)),
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+pub async fn save_reaction(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetReactionParamsDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let kind = path
+        .kind
+        .parse::<ReactionKind>()
+        .map_err(invalid_reaction_kind)?;
+
+    let post = find_visible_post(&app_state, path.post_id, Some(person.id)).await?;
+
+    // Reacting twice with the same kind is a no-op, so PUT stays idempotent.
+    let is_saved = app_state
+        .db_client
+        .save_reaction(path.post_id, person.id, kind)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    if let (true, Some(author_id)) = (is_saved, post.author_id) {
+        let payload = NotificationPayload::Reaction {
+            post_id: post.id,
+            reaction: kind,
         };
+
+        notify(&app_state, author_id, person.id, payload).await;
+    }
+
+    Ok(DefaultHttpResponse::ok("Reaction has been saved").into_http_response())
+}
+
+pub async fn delete_reaction(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetReactionParamsDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let kind = path
+        .kind
+        .parse::<ReactionKind>()
+        .map_err(invalid_reaction_kind)?;
+
+    find_visible_post(&app_state, path.post_id, Some(person.id)).await?;
+
+    app_state
+        .db_client
+        .delete_reaction(path.post_id, person.id, kind)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    Ok(DefaultHttpResponse::ok("Reaction has been removed").into_http_response())
+}
+
+pub async fn get_scheduled_posts(
+    app_state: web::Data<AppState>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    // Admins oversee the whole editorial queue, everyone else sees their own posts.
+    let author_id = if person.is_admin() {
+        None
+    } else {
+        Some(person.id)
+    };
+
+    let posts = app_state
+        .db_client
+        .get_scheduled_posts(author_id)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    let post_ids: Vec<Uuid> = posts.iter().map(|post| post.id).collect();
+
+    let tags = app_state
+        .db_client
+        .get_posts_tags(&post_ids)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    let attachments = app_state
+        .db_client
+        .get_posts_attachments(&post_ids)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    Ok(ActixHttpResponse::Ok().json(PostListResponseDto {
+        status: 200,
+        posts: PostDto::filter_posts(&posts, &tags, &attachments, &[]),
+        results: posts.len(),
+    }))
+}
+
+pub async fn cancel_scheduled_post(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetPostParamsDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let post = find_visible_post(&app_state, path.post_id, Some(person.id)).await?;
+
+    if !can_manage_post(&post, &person) {
+        return Err(DefaultHttpError::forbidden(
+            "Only the author or an admin can cancel this schedule",
+        ));
+    }
+
+    let result = app_state.db_client.cancel_scheduled_post(post.id).await;
+
+    match result {
+        Ok(Some(_)) => Ok(
+            DefaultHttpResponse::ok("Scheduled publishing has been cancelled").into_http_response(),
+        ),
+        Ok(None) => {
+            Err(DefaultHttpError::not_found("Post is not scheduled")
+                .with_code(ErrorCode::PostNotFound))
+        }
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+pub async fn publish_post(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetPostParamsDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    transition_post(&app_state, path.post_id, &person, PostStatus::Published).await
+}
+
+pub async fn archive_post(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetPostParamsDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    transition_post(&app_state, path.post_id, &person, PostStatus::Archived).await
+}
+
+async fn transition_post(
+    app_state: &AppState,
+    post_id: Uuid,
+    person: &AuthenticatedPerson,
+    to: PostStatus,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let post = find_visible_post(app_state, post_id, Some(person.id)).await?;
+
+    if !can_manage_post(&post, person) {
+        return Err(DefaultHttpError::forbidden(
+            "Only the author or an admin can change the status of this post",
+        ));
+    }
+
+    if !post.status.can_transition_to(to) {
+        return Err(invalid_transition(post.status, to));
+    }
+
+    let was_draft = post.status == PostStatus::Draft;
+
+    let result = app_state
+        .db_client
+        .update_post_status(post.id, post.status, to)
+        .await;
+
+    match result {
+        Ok(Some(post)) => {
+            // Drafts were never shown to anyone, so publishing one is news.
+            if was_draft {
+                app_state.events.publish(Event::post_created(&post));
+            } else {
+                app_state.events.publish(Event::post_updated(&post));
+            }
+
+            let tags = app_state
+                .db_client
+                .get_posts_tags(&[post.id])
+                .await
+                .map_err(DefaultHttpError::from)?;
+
+            let attachments = app_state
+                .db_client
+                .get_posts_attachments(&[post.id])
+                .await
+                .map_err(DefaultHttpError::from)?;
+
+            let reactions = app_state
+                .db_client
+                .get_reaction_counts(&[post.id], Some(person.id))
+                .await
+                .map_err(DefaultHttpError::from)?;
+
+            Ok(ActixHttpResponse::Ok()
+                .insert_header((header::ETAG, etag(post.version)))
+                .json(PostResponseDto {
+                    status: 200,
+                    post: PostDto::filter_post(&post, &tags, &attachments, &reactions),
+                }))
+        }
+        // Someone else changed the status in the meantime.
+        Ok(None) => Err(invalid_transition(post.status, to)),
+        Err(e) => Err(DefaultHttpError::from(e)),
     }
+}
+
+/// Loads a post the viewer is allowed to see, drafts of other people are reported as missing.
+pub(super) async fn find_visible_post(
+    app_state: &AppState,
+    post_id: Uuid,
+    viewer_id: Option<Uuid>,
+) -> Result<Post, DefaultHttpError> {
+    let post = app_state
+        .db_client
+        .get_post(post_id)
+        .await
+        .map_err(DefaultHttpError::from)?
+        .filter(|post| post.is_visible_to(viewer_id))
+        .ok_or_else(post_not_found)?;
+
+    // Blocked people can't tell the post exists, let alone comment or react.
+    if is_author_blocked(app_state, &post, viewer_id).await? {
+        return Err(post_not_found());
+    }
+
+    Ok(post)
+}
+
+async fn is_author_blocked(
+    app_state: &AppState,
+    post: &Post,
+    viewer_id: Option<Uuid>,
+) -> Result<bool, DefaultHttpError> {
+    match (post.author_id, viewer_id) {
+        (Some(author_id), Some(viewer_id)) if author_id != viewer_id => app_state
+            .db_client
+            .is_blocked(author_id, viewer_id)
+            .await
+            .map_err(DefaultHttpError::from),
+        _ => Ok(false),
+    }
+}
+
+async fn post_response(
+    app_state: &AppState,
+    post: Post,
+    viewer_id: Option<Uuid>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let tags = app_state
+        .db_client
+        .get_posts_tags(&[post.id])
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    let attachments = app_state
+        .db_client
+        .get_posts_attachments(&[post.id])
+        .await
+        .map_err(DefaultHttpError::from)?;
 
-    Err(DefaultHttpError::server_error(
-        "The provided id is not valid".to_string(),
+    let reactions = app_state
+        .db_client
+        .get_reaction_counts(&[post.id], viewer_id)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    Ok(ActixHttpResponse::Ok()
+        .insert_header((header::ETAG, etag(post.version)))
+        .json(PostResponseDto {
+            status: 200,
+            post: PostDto::filter_post(&post, &tags, &attachments, &reactions),
+        }))
+}
+
+/// Posts with their tags, attachments and reactions, loaded in one query each.
+pub(super) async fn post_list(
+    app_state: &AppState,
+    posts: &[Post],
+    viewer_id: Option<Uuid>,
+) -> Result<Vec<PostDto>, DefaultHttpError> {
+    let post_ids: Vec<Uuid> = posts.iter().map(|post| post.id).collect();
+
+    let tags = app_state
+        .db_client
+        .get_posts_tags(&post_ids)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    let attachments = app_state
+        .db_client
+        .get_posts_attachments(&post_ids)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    let reactions = app_state
+        .db_client
+        .get_reaction_counts(&post_ids, viewer_id)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    Ok(PostDto::filter_posts(
+        posts,
+        &tags,
+        &attachments,
+        &reactions,
+    ))
+}
+
+pub(super) fn can_manage_post(post: &Post, person: &AuthenticatedPerson) -> bool {
+    post.author_id == Some(person.id) || person.is_admin()
+}
+
+fn invalid_transition(from: PostStatus, to: PostStatus) -> DefaultHttpError {
+    DefaultHttpError::unique_constraint_voilation(format!(
+        "A {} post cannot be moved to {}",
+        from, to
     ))
+    .with_code(ErrorCode::InvalidStateTransition)
+}
+
+fn post_not_found() -> DefaultHttpError {
+    DefaultHttpError::not_found("Post not found").with_code(ErrorCode::PostNotFound)
+}
+
+pub(super) fn post_modified() -> DefaultHttpError {
+    DefaultHttpError::precondition_failed("The post has been modified since it was read")
+        .with_code(ErrorCode::PreconditionFailed)
+}
+
+fn invalid_reaction_kind(message: String) -> DefaultHttpError {
+    DefaultHttpError::bad_request("The reaction kind is not supported")
+        .with_code(ErrorCode::InvalidValue)
+        .with_errors(vec![FieldError {
+            field: String::from("kind"),
+            code: String::from("invalid"),
+            message,
+        }])
 }
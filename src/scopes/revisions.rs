@@ -0,0 +1,164 @@
+use actix_web::{web, HttpResponse as ActixHttpResponse, Scope};
+
+use crate::{
+    db::{post::PostExt, revision::RevisionExt},
+    dtos::{
+        post::{GetPostParamsDto, UpdatePostDto},
+        revision::{
+            GetRevisionParamsDto, PostRevisionDto, RevisionDiffDto, RevisionDiffQueryDto,
+            RevisionDiffResponseDto, RevisionListResponseDto,
+        },
+    },
+    events::Event,
+    extractors::{auth::AuthenticatedPerson, id_path::IdPath, if_match::IfMatch},
+    models::{Post, PostRevision, PostStatus},
+    response::{DefaultHttpError, DefaultHttpResponse, ErrorCode, HttpResponse},
+    utils::diff::diff_lines,
+    AppState,
+};
+
+use super::posts::{can_manage_post, find_visible_post, post_modified};
+
+/// Nested under `posts_scope`, so every route is relative to `/api/posts/{post_id}`.
+pub fn revisions_scope() -> Scope {
+    web::scope("/{post_id}/revisions")
+        // GET methods
+        .route("", web::get().to(get_revisions))
+        .route("diff", web::get().to(get_revisions_diff))
+        // POST methods
+        .route("{revision_id}/restore", web::post().to(restore_revision))
+}
+
+pub async fn get_revisions(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetPostParamsDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    find_managed_post(&app_state, path.post_id, &person).await?;
+
+    let revisions = app_state
+        .db_client
+        .get_revisions(path.post_id)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    Ok(ActixHttpResponse::Ok().json(RevisionListResponseDto {
+        status: 200,
+        revisions: PostRevisionDto::filter_revisions(&revisions),
+        results: revisions.len(),
+    }))
+}
+
+pub async fn get_revisions_diff(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetPostParamsDto>,
+    query: web::Query<RevisionDiffQueryDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let post = find_managed_post(&app_state, path.post_id, &person).await?;
+
+    let from = find_revision(&app_state, post.id, query.from).await?;
+
+    let (to_title, to_description) = match query.to {
+        Some(to) => {
+            let to = find_revision(&app_state, post.id, to).await?;
+            (to.title, to.description)
+        }
+        None => (post.title, post.description),
+    };
+
+    Ok(ActixHttpResponse::Ok().json(RevisionDiffResponseDto {
+        status: 200,
+        diff: RevisionDiffDto {
+            from: from.id.to_string(),
+            to: query.to.map(|id| id.to_string()),
+            title: diff_lines(&from.title, &to_title),
+            description: diff_lines(&from.description, &to_description),
+        },
+    }))
+}
+
+pub async fn restore_revision(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetRevisionParamsDto>,
+    person: AuthenticatedPerson,
+    if_match: IfMatch,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let post = find_managed_post(&app_state, path.post_id, &person).await?;
+
+    if !if_match.matches(post.version) {
+        return Err(post_modified());
+    }
+
+    let revision = find_revision(&app_state, post.id, path.revision_id).await?;
+
+    // Restoring is an ordinary edit, so the current version becomes a revision too.
+    let dto = UpdatePostDto {
+        title: Some(revision.title),
+        description: Some(revision.description),
+        ..Default::default()
+    };
+
+    let has_precondition = if_match.0.is_some();
+
+    let result = app_state
+        .db_client
+        .update_post(post.id, person.id, dto, if_match.0)
+        .await;
+
+    match result {
+        Ok(is_updated) => {
+            if is_updated {
+                if post.status != PostStatus::Draft {
+                    app_state.events.publish(Event::post_updated(&post));
+                }
+
+                return Ok(
+                    DefaultHttpResponse::ok("Revision has been restored").into_http_response()
+                );
+            }
+
+            // The version moved on between the read above and the update.
+            if has_precondition {
+                return Err(post_modified());
+            }
+
+            Err(DefaultHttpError::not_found("Post not found").with_code(ErrorCode::PostNotFound))
+        }
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+/// Revisions hold text the author may have removed on purpose, so only
+/// those who can edit the post see or restore them.
+async fn find_managed_post(
+    app_state: &AppState,
+    post_id: uuid::Uuid,
+    person: &AuthenticatedPerson,
+) -> Result<Post, DefaultHttpError> {
+    let post = find_visible_post(app_state, post_id, Some(person.id)).await?;
+
+    if !can_manage_post(&post, person) {
+        return Err(DefaultHttpError::forbidden(
+            "Only the author or an admin can see or restore the revisions of this post",
+        ));
+    }
+
+    Ok(post)
+}
+
+async fn find_revision(
+    app_state: &AppState,
+    post_id: uuid::Uuid,
+    revision_id: uuid::Uuid,
+) -> Result<PostRevision, DefaultHttpError> {
+    app_state
+        .db_client
+        .get_revision(revision_id)
+        .await
+        .map_err(DefaultHttpError::from)?
+        .filter(|revision| revision.post_id == post_id)
+        .ok_or_else(|| {
+            DefaultHttpError::not_found("Revision not found").with_code(ErrorCode::RevisionNotFound)
+        })
+}
@@ -0,0 +1,121 @@
+use std::{collections::VecDeque, time::Duration};
+
+use actix_web::{
+    http::header,
+    rt::time::{self, Instant, Interval},
+    web::{self, Bytes},
+    HttpRequest, HttpResponse as ActixHttpResponse,
+};
+use futures_util::stream;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::{
+    events::{EventId, LoggedEvent},
+    AppState,
+};
+
+/// How often a comment is sent so proxies keep idle streams open.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Streams post changes as Server-Sent Events, for clients that can't use the
+/// WebSocket.
+///
+/// Every event has an id, so a client reconnecting with `Last-Event-ID` first
+/// receives what it missed. When that is no longer known, it receives a
+/// `reset` message instead and should refetch what it displays.
+pub async fn stream_posts(req: HttpRequest, app_state: web::Data<AppState>) -> ActixHttpResponse {
+    // Subscribing before reading the log leaves no gap between the two,
+    // events that show up in both are skipped by their id.
+    let events = app_state.events.subscribe();
+
+    let mut pending = VecDeque::new();
+    let mut last_id = None;
+
+    let requested_id = req
+        .headers()
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim().parse::<EventId>());
+
+    match requested_id {
+        None => {}
+        Some(Ok(id)) => match app_state.events.events_after(id) {
+            Some(missed) => {
+                last_id = Some(missed.last().map(|logged| logged.id).unwrap_or(id));
+                pending.extend(
+                    missed
+                        .iter()
+                        .filter(|logged| logged.event.is_public())
+                        .map(event_frame),
+                );
+            }
+            None => pending.push_back(reset_frame("Some events are no longer available")),
+        },
+        Some(Err(message)) => pending.push_back(reset_frame(&message)),
+    }
+
+    let state = PostStream {
+        events,
+        pending,
+        last_id,
+        keep_alive: time::interval_at(Instant::now() + KEEP_ALIVE_INTERVAL, KEEP_ALIVE_INTERVAL),
+    };
+
+    ActixHttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        // Asks nginx not to buffer the stream.
+        .insert_header(("x-accel-buffering", "no"))
+        .streaming(stream::unfold(state, next_frame))
+}
+
+struct PostStream {
+    events: broadcast::Receiver<LoggedEvent>,
+    pending: VecDeque<Bytes>,
+    last_id: Option<EventId>,
+    keep_alive: Interval,
+}
+
+async fn next_frame(
+    mut state: PostStream,
+) -> Option<(Result<Bytes, actix_web::Error>, PostStream)> {
+    loop {
+        if let Some(frame) = state.pending.pop_front() {
+            return Some((Ok(frame), state));
+        }
+
+        tokio::select! {
+            event = state.events.recv() => match event {
+                Ok(logged) => {
+                    let is_sent = state.last_id.is_some_and(|id| logged.id <= id);
+
+                    if logged.event.is_public() && !is_sent {
+                        state.last_id = Some(logged.id);
+                        state.pending.push_back(event_frame(&logged));
+                    }
+                }
+                Err(RecvError::Lagged(missed)) => {
+                    let message = format!("{} events were missed", missed);
+                    state.pending.push_back(reset_frame(&message));
+                }
+                Err(RecvError::Closed) => return None,
+            },
+            _ = state.keep_alive.tick() => {
+                state.pending.push_back(Bytes::from_static(b": keep-alive\n\n"));
+            }
+        }
+    }
+}
+
+fn event_frame(logged: &LoggedEvent) -> Bytes {
+    // Events hold nothing that fails to serialize, and the JSON is on one line.
+    let data = serde_json::to_string(&logged.event).unwrap_or_default();
+
+    Bytes::from(format!("id: {}\ndata: {}\n\n", logged.id, data))
+}
+
+fn reset_frame(message: &str) -> Bytes {
+    let data = serde_json::json!({ "type": "reset", "message": message });
+
+    Bytes::from(format!("data: {}\n\n", data))
+}
@@ -0,0 +1,36 @@
+use actix_web::{web, HttpResponse as ActixHttpResponse, Scope};
+use validator::Validate;
+
+use crate::{
+    db::tag::TagExt,
+    dtos::tag::{SearchTagQueryDto, TagDto, TagListResponseDto},
+    response::DefaultHttpError,
+    AppState,
+};
+
+pub fn tags_scope() -> Scope {
+    web::scope("/api/tags")
+        // GET methods
+        .route("", web::get().to(get_tags))
+}
+
+pub async fn get_tags(
+    query: web::Query<SearchTagQueryDto>,
+    app_state: web::Data<AppState>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let query_params: SearchTagQueryDto = query.into_inner();
+
+    query_params.validate().map_err(DefaultHttpError::from)?;
+
+    let tags = app_state
+        .db_client
+        .get_tags(query_params)
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    Ok(ActixHttpResponse::Ok().json(TagListResponseDto {
+        status: 200,
+        tags: TagDto::filter_tags(&tags),
+        results: tags.len(),
+    }))
+}
@@ -0,0 +1,427 @@
+use actix_web::{http::header, web, HttpResponse as ActixHttpResponse, Scope};
+use uuid::Uuid;
+use validator::{Validate, ValidateArgs};
+
+use crate::{
+    db::{block::BlockExt, follow::FollowExt, person::PersonExt},
+    dtos::person::{
+        CreateUserDto, GetUserParamsDto, SearchUserQueryDto, UpdateUserProfileStatusDto,
+        UpdateUserPublicInfoDto, UserDto, UserListResponseDto, UserResponseDto,
+    },
+    extractors::{
+        auth::AuthenticatedPerson,
+        id_path::IdPath,
+        if_match::{etag, IfMatch},
+        upload::FileUpload,
+    },
+    models::User,
+    response::{DefaultHttpError, DefaultHttpResponse, ErrorCode, HttpResponse},
+    utils::password,
+    AppState,
+};
+
+use super::{
+    attachments::{delete_stored_file, queue_variants, store_upload},
+    blocks::blocks_routes,
+    follows::follows_routes,
+};
+
+pub fn users_scope() -> Scope {
+    web::scope("/api/users")
+        // GET methods
+        .route("", web::get().to(get_users))
+        .route("trash", web::get().to(get_deleted_users))
+        .route("{user_id}", web::get().to(get_user))
+        // POST methods
+        .route("", web::post().to(save_user))
+        .route("{user_id}/restore", web::post().to(restore_user))
+        // PUT methods
+        .route("{user_id}/avatar", web::put().to(update_user_avatar))
+        // PATCH methods
+        .route("{user_id}", web::patch().to(update_user))
+        .route("{user_id}/privacy", web::patch().to(update_user_privacy))
+        // DELETE methods
+        .route("{user_id}", web::delete().to(delete_user))
+        .route("{user_id}/avatar", web::delete().to(delete_user_avatar))
+        // Nested scopes
+        .service(
+            web::scope("/{user_id}")
+                .configure(follows_routes)
+                .configure(blocks_routes),
+        )
+}
+
+pub async fn get_users(
+    query: web::Query<SearchUserQueryDto>,
+    app_state: web::Data<AppState>,
+    viewer: Option<AuthenticatedPerson>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let query_params: SearchUserQueryDto = query.into_inner();
+
+    query_params.validate().map_err(DefaultHttpError::from)?;
+
+    let users = app_state
+        .db_client
+        .get_users(query_params, true, viewer.map(|v| v.id))
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    Ok(ActixHttpResponse::Ok().json(UserListResponseDto {
+        status: 200,
+        users: UserDto::filter_users(&users),
+        results: users.len(),
+    }))
+}
+
+pub async fn get_user(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetUserParamsDto>,
+    viewer: Option<AuthenticatedPerson>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let user = find_visible_user(&app_state, path.user_id, viewer.map(|v| v.id)).await?;
+
+    Ok(ActixHttpResponse::Ok()
+        .insert_header((header::ETAG, etag(user.version)))
+        .json(UserResponseDto {
+            status: 200,
+            user: UserDto::filter_user(&user),
+        }))
+}
+
+pub async fn save_user(
+    app_state: web::Data<AppState>,
+    body: web::Json<CreateUserDto>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    body.validate_args(app_state.env.min_age)
+        .map_err(DefaultHttpError::from)?;
+
+    let mut dto = body.into_inner();
+
+    dto.password = password::hash(&dto.password).map_err(|e| {
+        DefaultHttpError::server_error("Could not process the password")
+            .with_internal(e.to_string())
+    })?;
+
+    let result = app_state.db_client.save_user(dto).await;
+
+    match result {
+        Ok(user) => Ok(ActixHttpResponse::Created().json(UserResponseDto {
+            status: 201,
+            user: UserDto::filter_user(&user),
+        })),
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+pub async fn update_user(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetUserParamsDto>,
+    person: AuthenticatedPerson,
+    if_match: IfMatch,
+    body: web::Json<UpdateUserPublicInfoDto>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    if !can_manage_user(&person, path.user_id) {
+        return Err(DefaultHttpError::forbidden(
+            "Only the user or an admin can change this profile",
+        ));
+    }
+
+    body.validate_args(app_state.env.min_age)
+        .map_err(DefaultHttpError::from)?;
+
+    let result = app_state
+        .db_client
+        .update_user(path.user_id, body.into_inner().into(), if_match.0.clone())
+        .await;
+
+    match result {
+        Ok(true) => Ok(DefaultHttpResponse::ok("User has been updated").into_http_response()),
+        Ok(false) => Err(update_failed(&app_state, path.user_id, &if_match).await),
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+pub async fn update_user_privacy(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetUserParamsDto>,
+    person: AuthenticatedPerson,
+    if_match: IfMatch,
+    body: web::Json<UpdateUserProfileStatusDto>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    // Going public accepts every pending follow request, which only the user
+    // can decide, admins included.
+    if person.id != path.user_id {
+        return Err(DefaultHttpError::forbidden(
+            "Only the user can change the privacy of their profile",
+        ));
+    }
+
+    let is_profile_private = body.is_profile_private;
+
+    let result = app_state
+        .db_client
+        .update_user(path.user_id, body.into_inner().into(), if_match.0.clone())
+        .await;
+
+    match result {
+        Ok(true) => {
+            // A public profile can be followed by anyone, so nobody is left waiting.
+            if !is_profile_private {
+                app_state
+                    .db_client
+                    .accept_follow_requests(path.user_id)
+                    .await
+                    .map_err(DefaultHttpError::from)?;
+            }
+
+            Ok(DefaultHttpResponse::ok("Profile privacy has been updated").into_http_response())
+        }
+        Ok(false) => Err(update_failed(&app_state, path.user_id, &if_match).await),
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+pub async fn update_user_avatar(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetUserParamsDto>,
+    person: AuthenticatedPerson,
+    upload: FileUpload,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let user = find_avatar_owner(&app_state, path.user_id, &person).await?;
+
+    if !upload.is_image() {
+        return Err(
+            DefaultHttpError::unsupported_media_type("Avatars have to be images")
+                .with_code(ErrorCode::UnsupportedMediaType),
+        );
+    }
+
+    let avatar_key = store_upload(&app_state, &format!("avatars/{}", user.id), &upload).await?;
+    let avatar_url = app_state.storage.url(&avatar_key);
+
+    let result = app_state
+        .db_client
+        .update_user_avatar(user.id, Some(avatar_key.clone()), Some(avatar_url))
+        .await;
+
+    match result {
+        Ok(true) => {
+            if let Some(previous_key) = &user.avatar_key {
+                delete_stored_file(&app_state, previous_key).await;
+            }
+
+            queue_variants(&app_state, &avatar_key).await?;
+
+            user_response(&app_state, user.id).await
+        }
+        Ok(false) => {
+            delete_stored_file(&app_state, &avatar_key).await;
+            Err(DefaultHttpError::not_found("User not found").with_code(ErrorCode::UserNotFound))
+        }
+        Err(e) => {
+            delete_stored_file(&app_state, &avatar_key).await;
+            Err(DefaultHttpError::from(e))
+        }
+    }
+}
+
+pub async fn delete_user_avatar(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetUserParamsDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let user = find_avatar_owner(&app_state, path.user_id, &person).await?;
+
+    let Some(avatar_key) = &user.avatar_key else {
+        return Err(DefaultHttpError::not_found("User has no avatar")
+            .with_code(ErrorCode::AttachmentNotFound));
+    };
+
+    let result = app_state
+        .db_client
+        .update_user_avatar(user.id, None, None)
+        .await;
+
+    match result {
+        Ok(true) => {
+            delete_stored_file(&app_state, avatar_key).await;
+
+            Ok(DefaultHttpResponse::ok("Avatar has been removed").into_http_response())
+        }
+        Ok(false) => {
+            Err(DefaultHttpError::not_found("User not found").with_code(ErrorCode::UserNotFound))
+        }
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+pub async fn delete_user(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetUserParamsDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    if !can_manage_user(&person, path.user_id) {
+        return Err(DefaultHttpError::forbidden(
+            "Only the user or an admin can delete this account",
+        ));
+    }
+
+    let result = app_state.db_client.delete_user(path.user_id).await;
+
+    match result {
+        Ok(true) => Ok(DefaultHttpResponse::ok("User has been deleted").into_http_response()),
+        Ok(false) => {
+            Err(DefaultHttpError::not_found("User not found").with_code(ErrorCode::UserNotFound))
+        }
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+pub async fn get_deleted_users(
+    app_state: web::Data<AppState>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    if !person.is_admin() {
+        return Err(trash_forbidden());
+    }
+
+    let users = app_state
+        .db_client
+        .get_deleted_users()
+        .await
+        .map_err(DefaultHttpError::from)?;
+
+    Ok(ActixHttpResponse::Ok().json(UserListResponseDto {
+        status: 200,
+        users: UserDto::filter_users(&users),
+        results: users.len(),
+    }))
+}
+
+pub async fn restore_user(
+    app_state: web::Data<AppState>,
+    path: IdPath<GetUserParamsDto>,
+    person: AuthenticatedPerson,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    if !person.is_admin() {
+        return Err(trash_forbidden());
+    }
+
+    let result = app_state
+        .db_client
+        .restore_user(path.user_id, app_state.env.trash_retention_days)
+        .await;
+
+    match result {
+        Ok(true) => Ok(DefaultHttpResponse::ok("User has been restored").into_http_response()),
+        Ok(false) => Err(DefaultHttpError::not_found(
+            "User not found in the trash or past its restore period",
+        )
+        .with_code(ErrorCode::UserNotFound)),
+        Err(e) => Err(DefaultHttpError::from(e)),
+    }
+}
+
+/// Loads a user, as if they did not exist when they and the viewer blocked
+/// one another.
+pub(super) async fn find_visible_user(
+    app_state: &AppState,
+    user_id: Uuid,
+    viewer_id: Option<Uuid>,
+) -> Result<User, DefaultHttpError> {
+    let user = app_state
+        .db_client
+        .get_user(user_id)
+        .await
+        .map_err(DefaultHttpError::from)?
+        .ok_or_else(user_not_found)?;
+
+    if let Some(viewer_id) = viewer_id.filter(|viewer_id| *viewer_id != user.id) {
+        let is_blocked = app_state
+            .db_client
+            .is_blocked(user.id, viewer_id)
+            .await
+            .map_err(DefaultHttpError::from)?;
+
+        if is_blocked {
+            return Err(user_not_found());
+        }
+    }
+
+    Ok(user)
+}
+
+/// People manage their own account, admins manage everyone's.
+pub(super) fn can_manage_user(person: &AuthenticatedPerson, user_id: Uuid) -> bool {
+    person.id == user_id || person.is_admin()
+}
+
+/// Loads a user whose avatar the person may change, which is their own or
+/// anyone's for admins.
+async fn find_avatar_owner(
+    app_state: &AppState,
+    user_id: Uuid,
+    person: &AuthenticatedPerson,
+) -> Result<User, DefaultHttpError> {
+    if !can_manage_user(person, user_id) {
+        return Err(DefaultHttpError::forbidden(
+            "Only the user or an admin can change this avatar",
+        ));
+    }
+
+    app_state
+        .db_client
+        .get_user(user_id)
+        .await
+        .map_err(DefaultHttpError::from)?
+        .ok_or_else(|| {
+            DefaultHttpError::not_found("User not found").with_code(ErrorCode::UserNotFound)
+        })
+}
+
+async fn user_response(
+    app_state: &AppState,
+    user_id: Uuid,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let user = app_state
+        .db_client
+        .get_user(user_id)
+        .await
+        .map_err(DefaultHttpError::from)?
+        .ok_or_else(|| {
+            DefaultHttpError::not_found("User not found").with_code(ErrorCode::UserNotFound)
+        })?;
+
+    Ok(ActixHttpResponse::Ok()
+        .insert_header((header::ETAG, etag(user.version)))
+        .json(UserResponseDto {
+            status: 200,
+            user: UserDto::filter_user(&user),
+        }))
+}
+
+/// Tells a missing user apart from one that changed since the client read it.
+async fn update_failed(
+    app_state: &AppState,
+    user_id: Uuid,
+    if_match: &IfMatch,
+) -> DefaultHttpError {
+    let user = app_state.db_client.get_user(user_id).await;
+
+    match user {
+        Ok(Some(user)) if !if_match.matches(user.version) => {
+            DefaultHttpError::precondition_failed("The user has been modified since it was read")
+                .with_code(ErrorCode::PreconditionFailed)
+        }
+        Ok(_) => DefaultHttpError::not_found("User not found").with_code(ErrorCode::UserNotFound),
+        Err(e) => DefaultHttpError::from(e),
+    }
+}
+
+pub(super) fn user_not_found() -> DefaultHttpError {
+    DefaultHttpError::not_found("User not found").with_code(ErrorCode::UserNotFound)
+}
+
+pub(super) fn trash_forbidden() -> DefaultHttpError {
+    DefaultHttpError::forbidden("Only admins can manage deleted accounts")
+}
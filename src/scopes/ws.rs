@@ -0,0 +1,256 @@
+use std::{collections::HashSet, time::Duration};
+
+use actix_web::{
+    rt::time::{self, Instant},
+    web, HttpRequest, HttpResponse as ActixHttpResponse, Scope,
+};
+use actix_ws::{CloseReason, Message, MessageStream, Session};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::{
+    dtos::ws::{WsClientMessage, WsQueryDto, WsReplyDto},
+    events::{Event, Topic},
+    extractors::auth::{bearer_token, AuthenticatedPerson},
+    response::DefaultHttpError,
+    AppState,
+};
+
+use super::{
+    follows::{can_view_follows, private_profile},
+    posts::find_visible_post,
+    users::find_visible_user,
+};
+
+/// How often the server pings idle clients.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Clients that stay silent this long, pongs included, are disconnected.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(75);
+
+const MAX_SUBSCRIPTIONS: usize = 100;
+
+pub fn ws_scope() -> Scope {
+    web::scope("/api/ws")
+        // GET methods
+        .route("", web::get().to(connect))
+}
+
+pub async fn connect(
+    req: HttpRequest,
+    body: web::Payload,
+    query: web::Query<WsQueryDto>,
+    app_state: web::Data<AppState>,
+) -> Result<ActixHttpResponse, DefaultHttpError> {
+    let token = bearer_token(&req)
+        .or_else(|| query.into_inner().token)
+        .ok_or_else(|| {
+            DefaultHttpError::unauthorized("You are not logged in, please provide a token")
+        })?;
+
+    let person = AuthenticatedPerson::from_token(&app_state, &token).await?;
+
+    let (response, session, stream) =
+        actix_ws::handle(&req, body).map_err(|e| DefaultHttpError::bad_request(e.to_string()))?;
+
+    actix_web::rt::spawn(run_session(app_state, person, session, stream));
+
+    Ok(response)
+}
+
+/// Relays events to one client until either side hangs up.
+async fn run_session(
+    app_state: web::Data<AppState>,
+    person: AuthenticatedPerson,
+    mut session: Session,
+    mut stream: MessageStream,
+) {
+    let mut events = app_state.events.subscribe();
+    let mut topics: HashSet<Topic> = HashSet::new();
+    let mut heartbeat = time::interval_at(Instant::now() + HEARTBEAT_INTERVAL, HEARTBEAT_INTERVAL);
+    let mut last_seen = Instant::now();
+
+    let reason: Option<CloseReason> = loop {
+        tokio::select! {
+            message = stream.recv() => {
+                let message = match message {
+                    Some(Ok(message)) => message,
+                    _ => break None,
+                };
+
+                last_seen = Instant::now();
+
+                let sent = match message {
+                    Message::Text(text) => {
+                        let reply = handle_message(&app_state, &person, &mut topics, &text).await;
+
+                        send(&mut session, &reply).await
+                    }
+                    Message::Ping(bytes) => session.pong(&bytes).await,
+                    Message::Close(reason) => break reason,
+                    _ => Ok(()),
+                };
+
+                if sent.is_err() {
+                    return;
+                }
+            }
+            event = events.recv() => {
+                match event {
+                    Ok(logged) => {
+                        if !logged.event.is_delivered_to(person.id, &topics) {
+                            continue;
+                        }
+
+                        for topic in drop_hidden_topics(&app_state, &person, &mut topics, &logged.event).await {
+                            let reply = WsReplyDto::Unsubscribed { topic: topic.to_string() };
+
+                            if send(&mut session, &reply).await.is_err() {
+                                return;
+                            }
+                        }
+
+                        if logged.event.is_delivered_to(person.id, &topics)
+                            && send(&mut session, &logged.event).await.is_err()
+                        {
+                            return;
+                        }
+                    }
+                    Err(RecvError::Lagged(missed)) => {
+                        let reply = WsReplyDto::Error {
+                            message: format!("{} events were missed, refetch what you display", missed),
+                        };
+
+                        if send(&mut session, &reply).await.is_err() {
+                            return;
+                        }
+                    }
+                    Err(RecvError::Closed) => break None,
+                }
+            }
+            _ = heartbeat.tick() => {
+                if last_seen.elapsed() > CLIENT_TIMEOUT {
+                    break None;
+                }
+
+                if session.ping(b"").await.is_err() {
+                    return;
+                }
+            }
+        }
+    };
+
+    let _ = session.close(reason).await;
+}
+
+async fn handle_message(
+    app_state: &AppState,
+    person: &AuthenticatedPerson,
+    topics: &mut HashSet<Topic>,
+    text: &str,
+) -> WsReplyDto {
+    let (is_subscribe, topic) = match serde_json::from_str::<WsClientMessage>(text) {
+        Ok(WsClientMessage::Subscribe { topic }) => (true, topic),
+        Ok(WsClientMessage::Unsubscribe { topic }) => (false, topic),
+        Err(e) => {
+            return WsReplyDto::Error {
+                message: format!("Invalid message: {}", e),
+            }
+        }
+    };
+
+    let parsed = match topic.parse::<Topic>() {
+        Ok(parsed) => parsed,
+        Err(message) => return WsReplyDto::Error { message },
+    };
+
+    if !is_subscribe {
+        topics.remove(&parsed);
+
+        return WsReplyDto::Unsubscribed { topic };
+    }
+
+    if !topics.contains(&parsed) && topics.len() >= MAX_SUBSCRIPTIONS {
+        return WsReplyDto::Error {
+            message: format!("At most {} topics can be subscribed to", MAX_SUBSCRIPTIONS),
+        };
+    }
+
+    match check_topic(app_state, person, parsed).await {
+        Ok(()) => {
+            topics.insert(parsed);
+
+            WsReplyDto::Subscribed { topic }
+        }
+        Err(e) => WsReplyDto::Error { message: e.message },
+    }
+}
+
+/// Only what the person could fetch themselves, so drafts of others, people
+/// who blocked them and private profiles they don't follow stay hidden.
+async fn check_topic(
+    app_state: &AppState,
+    person: &AuthenticatedPerson,
+    topic: Topic,
+) -> Result<(), DefaultHttpError> {
+    match topic {
+        Topic::Post(post_id) => {
+            find_visible_post(app_state, post_id, Some(person.id)).await?;
+        }
+        Topic::User(user_id) => {
+            let user = find_visible_user(app_state, user_id, Some(person.id)).await?;
+
+            if !can_view_follows(app_state, &user, Some(person)).await? {
+                return Err(private_profile());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the subscribed topics of `event` again, since the person may have
+/// been blocked, the post trashed or the profile made private since they
+/// subscribed. Returns the topics that were dropped.
+pub async fn drop_hidden_topics(
+    app_state: &AppState,
+    person: &AuthenticatedPerson,
+    topics: &mut HashSet<Topic>,
+    event: &Event,
+) -> Vec<Topic> {
+    let mut dropped = vec![];
+
+    for topic in event.topics() {
+        if !topics.contains(&topic) {
+            continue;
+        }
+
+        let checked = match (topic, event) {
+            // The post is gone, so only blocks between the person and its
+            // author can hide its deletion.
+            (Topic::Post(_), Event::PostDeleted { author_id, .. }) => match author_id {
+                Some(author_id) => find_visible_user(app_state, *author_id, Some(person.id))
+                    .await
+                    .map(|_| ()),
+                None => Ok(()),
+            },
+            _ => check_topic(app_state, person, topic).await,
+        };
+
+        if checked.is_err() {
+            topics.remove(&topic);
+            dropped.push(topic);
+        }
+    }
+
+    dropped
+}
+
+async fn send<T: serde::Serialize>(
+    session: &mut Session,
+    message: &T,
+) -> Result<(), actix_ws::Closed> {
+    // Neither events nor replies hold anything that fails to serialize.
+    let text = serde_json::to_string(message).unwrap_or_default();
+
+    session.text(text).await
+}
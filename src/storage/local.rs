@@ -0,0 +1,68 @@
+use std::path::Path as FsPath;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use object_store::{local::LocalFileSystem, path::Path, ObjectStore};
+
+use crate::config::Config;
+
+use super::{join_url, Storage, StorageError};
+
+/// Keeps files in a directory on disk, served by the app under `/media`.
+#[derive(Debug)]
+pub struct LocalStorage {
+    store: LocalFileSystem,
+    public_url: String,
+}
+
+impl LocalStorage {
+    pub fn new(config: &Config) -> Self {
+        let public_url = config
+            .storage_public_url
+            .clone()
+            .unwrap_or_else(|| format!("{}:{}/media", config.url, config.port));
+
+        Self::with_root(&config.storage_local_root, public_url)
+    }
+
+    pub fn with_root(root: impl AsRef<FsPath>, public_url: impl Into<String>) -> Self {
+        std::fs::create_dir_all(&root).expect("STORAGE_LOCAL_ROOT must be writable!");
+
+        let store = LocalFileSystem::new_with_prefix(root)
+            .expect("STORAGE_LOCAL_ROOT must be a directory!")
+            // Drops the per-post directories once their last file is gone.
+            .with_automatic_cleanup(true);
+
+        LocalStorage {
+            store,
+            public_url: public_url.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    // Files are served with the content type of their key's extension.
+    async fn put(&self, key: &str, bytes: Bytes, _: &str) -> Result<(), StorageError> {
+        self.store.put(&Path::from(key), bytes.into()).await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes, StorageError> {
+        let bytes = self.store.get(&Path::from(key)).await?.bytes().await?;
+
+        Ok(bytes)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        match self.store.delete(&Path::from(key)).await {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(e) => Err(StorageError::from(e)),
+        }
+    }
+
+    fn url(&self, key: &str) -> String {
+        join_url(&self.public_url, key)
+    }
+}
@@ -0,0 +1,71 @@
+use std::{fmt, sync::Arc};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use crate::{config::Config, response::DefaultHttpError};
+
+pub mod local;
+pub mod s3;
+
+/// Where uploaded files are kept and served from.
+///
+/// Keys are generated by the server and look like `posts/<post id>/<uuid>.png`,
+/// so backends can use them as paths without sanitizing them first.
+#[async_trait]
+pub trait Storage: fmt::Debug + Send + Sync {
+    /// Stores `bytes` under `key`, replacing any previous object.
+    async fn put(&self, key: &str, bytes: Bytes, content_type: &str) -> Result<(), StorageError>;
+
+    async fn get(&self, key: &str) -> Result<Bytes, StorageError>;
+
+    /// Removes the object under `key`, missing objects are not an error.
+    async fn delete(&self, key: &str) -> Result<(), StorageError>;
+
+    /// Public URL the object under `key` is served from.
+    fn url(&self, key: &str) -> String;
+}
+
+#[derive(Debug)]
+pub struct StorageError(pub String);
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "StorageError: {}", self.0)
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<std::io::Error> for StorageError {
+    fn from(error: std::io::Error) -> Self {
+        StorageError(error.to_string())
+    }
+}
+
+impl From<object_store::Error> for StorageError {
+    fn from(error: object_store::Error) -> Self {
+        StorageError(error.to_string())
+    }
+}
+
+impl From<StorageError> for DefaultHttpError {
+    fn from(error: StorageError) -> Self {
+        Self::server_error("The file could not be stored, please try again later")
+            .with_internal(error.to_string())
+    }
+}
+
+/// Builds the backend selected by `STORAGE_BACKEND`.
+pub fn init(config: &Config) -> Arc<dyn Storage> {
+    match config.storage_backend.as_str() {
+        "local" => Arc::new(local::LocalStorage::new(config)),
+        "s3" => Arc::new(s3::S3Storage::new(config)),
+        backend => panic!("Unknown STORAGE_BACKEND: {}", backend),
+    }
+}
+
+/// Joins a public base URL and a key without doubling the slash.
+fn join_url(base_url: &str, key: &str) -> String {
+    format!("{}/{}", base_url.trim_end_matches('/'), key)
+}
@@ -0,0 +1,97 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use object_store::{
+    aws::{AmazonS3, AmazonS3Builder},
+    path::Path,
+    Attribute, Attributes, ObjectStore, PutOptions,
+};
+
+use crate::config::Config;
+
+use super::{join_url, Storage, StorageError};
+
+/// Keeps files in a bucket of any S3 compatible service, such as MinIO.
+#[derive(Debug)]
+pub struct S3Storage {
+    store: AmazonS3,
+    public_url: String,
+}
+
+impl S3Storage {
+    pub fn new(config: &Config) -> Self {
+        let endpoint = config
+            .s3_endpoint
+            .clone()
+            .expect("S3_ENDPOINT must be set!");
+        let bucket = config.s3_bucket.clone().expect("S3_BUCKET must be set!");
+
+        let public_url = config
+            .storage_public_url
+            .clone()
+            .unwrap_or_else(|| join_url(&endpoint, &bucket));
+
+        let builder = AmazonS3Builder::new()
+            .with_endpoint(&endpoint)
+            .with_allow_http(endpoint.starts_with("http://"))
+            .with_bucket_name(bucket)
+            .with_region(&config.s3_region)
+            .with_access_key_id(
+                config
+                    .s3_access_key_id
+                    .clone()
+                    .expect("S3_ACCESS_KEY_ID must be set!"),
+            )
+            .with_secret_access_key(
+                config
+                    .s3_secret_access_key
+                    .clone()
+                    .expect("S3_SECRET_ACCESS_KEY must be set!"),
+            );
+
+        Self::with_builder(builder, public_url)
+    }
+
+    pub fn with_builder(builder: AmazonS3Builder, public_url: impl Into<String>) -> Self {
+        let store = builder
+            .build()
+            .expect("The S3 storage settings are invalid!");
+
+        S3Storage {
+            store,
+            public_url: public_url.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl Storage for S3Storage {
+    async fn put(&self, key: &str, bytes: Bytes, content_type: &str) -> Result<(), StorageError> {
+        let options = PutOptions {
+            attributes: Attributes::from_iter([(Attribute::ContentType, content_type.to_string())]),
+            ..Default::default()
+        };
+
+        self.store
+            .put_opts(&Path::from(key), bytes.into(), options)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes, StorageError> {
+        let bytes = self.store.get(&Path::from(key)).await?.bytes().await?;
+
+        Ok(bytes)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        // S3 answers deletes of missing keys with a success too.
+        self.store.delete(&Path::from(key)).await?;
+
+        Ok(())
+    }
+
+    fn url(&self, key: &str) -> String {
+        join_url(&self.public_url, key)
+    }
+}
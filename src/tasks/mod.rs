@@ -0,0 +1,3 @@
+pub mod purge_trash;
+pub mod scheduled_posts;
+pub mod thumbnails;
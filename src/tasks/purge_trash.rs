@@ -0,0 +1,94 @@
+use std::{sync::Arc, time::Duration};
+
+use actix_web::rt::time;
+
+use crate::{
+    db::{image_variant::ImageVariantExt, person::PersonExt, post::PostExt, DBClient},
+    models::PurgedRow,
+    storage::Storage,
+};
+
+const BATCH_SIZE: i64 = 100;
+
+/// Hard-deletes posts and accounts that stayed in the trash longer than
+/// `retention_days`, checking every `interval`. Their attachments and avatars
+/// are removed from `storage` as well.
+pub async fn run(
+    db_client: DBClient,
+    storage: Arc<dyn Storage>,
+    interval: Duration,
+    retention_days: i64,
+) {
+    let mut ticker = time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+
+        purge_posts(&db_client, storage.as_ref(), retention_days).await;
+        purge_people(&db_client, storage.as_ref(), retention_days).await;
+    }
+}
+
+async fn purge_posts(db_client: &DBClient, storage: &dyn Storage, retention_days: i64) {
+    loop {
+        match db_client
+            .purge_deleted_posts(retention_days, BATCH_SIZE)
+            .await
+        {
+            Ok(purged) => {
+                for row in &purged {
+                    log::info!("Purged deleted post {}", row.id);
+                    delete_files(db_client, storage, row).await;
+                }
+
+                if (purged.len() as i64) < BATCH_SIZE {
+                    break;
+                }
+            }
+            Err(e) => {
+                log::error!("Could not purge deleted posts: {}", e);
+                break;
+            }
+        }
+    }
+}
+
+async fn purge_people(db_client: &DBClient, storage: &dyn Storage, retention_days: i64) {
+    loop {
+        match db_client
+            .purge_deleted_people(retention_days, BATCH_SIZE)
+            .await
+        {
+            Ok(purged) => {
+                for row in &purged {
+                    log::info!("Purged deleted account {}", row.id);
+                    delete_files(db_client, storage, row).await;
+                }
+
+                if (purged.len() as i64) < BATCH_SIZE {
+                    break;
+                }
+            }
+            Err(e) => {
+                log::error!("Could not purge deleted accounts: {}", e);
+                break;
+            }
+        }
+    }
+}
+
+// A file that cannot be removed is only logged, its row is gone already.
+async fn delete_files(db_client: &DBClient, storage: &dyn Storage, row: &PurgedRow) {
+    let mut keys = row.storage_keys.clone();
+
+    match db_client.delete_image_variants(&row.storage_keys).await {
+        Ok(variant_keys) => keys.extend(variant_keys),
+        Err(e) => log::error!("Could not delete the variants of {}: {}", row.id, e),
+    }
+
+    for key in &keys {
+        if let Err(e) = storage.delete(key).await {
+            log::error!("Could not delete stored file {}: {}", key, e);
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use std::{sync::Arc, time::Duration};
+
+use actix_web::rt::time;
+
+use crate::{
+    db::{post::PostExt, DBClient},
+    events::{Event, EventHub},
+};
+
+const BATCH_SIZE: i64 = 50;
+
+/// Publishes scheduled posts whose time has come, checking every `interval`.
+///
+/// Nothing is kept in memory, so posts that fell due while the server was down
+/// are published on the first tick after a restart.
+pub async fn run(db_client: DBClient, events: Arc<dyn EventHub>, interval: Duration) {
+    let mut ticker = time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+
+        publish_due_posts(&db_client, events.as_ref()).await;
+    }
+}
+
+async fn publish_due_posts(db_client: &DBClient, events: &dyn EventHub) {
+    loop {
+        match db_client.publish_due_posts(BATCH_SIZE).await {
+            Ok(posts) => {
+                for post in &posts {
+                    log::info!("Published scheduled post {}", post.id);
+                    events.publish(Event::post_created(post));
+                }
+
+                if (posts.len() as i64) < BATCH_SIZE {
+                    break;
+                }
+            }
+            Err(e) => {
+                log::error!("Could not publish scheduled posts: {}", e);
+                break;
+            }
+        }
+    }
+}
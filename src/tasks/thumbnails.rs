@@ -0,0 +1,160 @@
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use actix_web::{rt::time, web};
+
+use crate::{
+    db::{image_variant::ImageVariantExt, DBClient},
+    models::ImageVariant,
+    storage::Storage,
+    utils::thumbnail::{self, Thumbnail},
+};
+
+const BATCH_SIZE: i64 = 20;
+
+// Long enough to render a batch, after which another instance may take over.
+const LEASE_SECONDS: i64 = 300;
+
+const MAX_ATTEMPTS: i32 = 3;
+
+/// Renders pending image variants, checking every `interval`.
+///
+/// Variants are leased rather than locked, so several instances can run this
+/// at once and a variant whose renderer crashed is picked up again once its
+/// lease runs out.
+pub async fn run(db_client: DBClient, storage: Arc<dyn Storage>, interval: Duration) {
+    let mut ticker = time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+
+        render_pending_variants(&db_client, storage.as_ref()).await;
+    }
+}
+
+/// Storage key of a variant, next to its original, e.g. `posts/1/abc_card.png`.
+pub fn variant_key(source_key: &str, name: &str, extension: &str) -> String {
+    let stem = source_key
+        .rsplit_once('.')
+        .map_or(source_key, |(stem, _)| stem);
+
+    format!("{}_{}.{}", stem, name, extension)
+}
+
+/// Renders pending variants in batches until none are left.
+pub async fn render_pending_variants(db_client: &DBClient, storage: &dyn Storage) {
+    loop {
+        let variants = match db_client
+            .claim_pending_image_variants(BATCH_SIZE, LEASE_SECONDS)
+            .await
+        {
+            Ok(variants) => variants,
+            Err(e) => {
+                log::error!("Could not claim pending image variants: {}", e);
+                break;
+            }
+        };
+
+        let count = variants.len();
+
+        // Each original is downloaded and decoded once for all its variants.
+        let mut by_source: BTreeMap<String, Vec<ImageVariant>> = BTreeMap::new();
+
+        for variant in variants {
+            by_source
+                .entry(variant.source_key.to_owned())
+                .or_default()
+                .push(variant);
+        }
+
+        for (source_key, variants) in by_source {
+            render_variants(db_client, storage, &source_key, variants).await;
+        }
+
+        if (count as i64) < BATCH_SIZE {
+            break;
+        }
+    }
+}
+
+async fn render_variants(
+    db_client: &DBClient,
+    storage: &dyn Storage,
+    source_key: &str,
+    variants: Vec<ImageVariant>,
+) {
+    let thumbnails = match storage.get(source_key).await {
+        Ok(bytes) => {
+            let sizes: Vec<u32> = variants
+                .iter()
+                .map(|variant| variant.max_size as u32)
+                .collect();
+
+            // Decoding and resizing is CPU bound, so it runs off the async workers.
+            web::block(move || -> Result<Vec<Thumbnail>, String> {
+                let (image, format) = thumbnail::decode(&bytes).map_err(|e| e.to_string())?;
+
+                sizes
+                    .into_iter()
+                    .map(|size| thumbnail::render(&image, format, size).map_err(|e| e.to_string()))
+                    .collect()
+            })
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|result| result)
+        }
+        Err(e) => Err(e.to_string()),
+    };
+
+    let thumbnails = match thumbnails {
+        Ok(thumbnails) => thumbnails,
+        Err(e) => {
+            log::error!("Could not render variants of {}: {}", source_key, e);
+
+            for variant in &variants {
+                fail_variant(db_client, variant).await;
+            }
+
+            return;
+        }
+    };
+
+    for (variant, thumbnail) in variants.iter().zip(thumbnails) {
+        let key = variant_key(source_key, &variant.name, thumbnail.extension);
+
+        if let Err(e) = storage
+            .put(&key, thumbnail.bytes.into(), thumbnail.content_type)
+            .await
+        {
+            log::error!("Could not store image variant {}: {}", key, e);
+            fail_variant(db_client, variant).await;
+            continue;
+        }
+
+        let result = db_client
+            .complete_image_variant(
+                variant.id,
+                &key,
+                &storage.url(&key),
+                thumbnail.width as i32,
+                thumbnail.height as i32,
+            )
+            .await;
+
+        match result {
+            Ok(true) => log::info!("Rendered image variant {}", key),
+            // The original went away while the variant was being rendered.
+            Ok(false) => {
+                if let Err(e) = storage.delete(&key).await {
+                    log::error!("Could not delete stored file {}: {}", key, e);
+                }
+            }
+            Err(e) => log::error!("Could not save image variant {}: {}", key, e),
+        }
+    }
+}
+
+async fn fail_variant(db_client: &DBClient, variant: &ImageVariant) {
+    if let Err(e) = db_client.fail_image_variant(variant.id, MAX_ATTEMPTS).await {
+        log::error!("Could not release image variant {}: {}", variant.id, e);
+    }
+}
@@ -0,0 +1,38 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Position in a list sorted newest first, by time and then by id so that
+/// rows sharing a timestamp are neither skipped nor repeated.
+///
+/// Clients get it as an opaque string and pass it back to fetch the rows
+/// that come after it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cursor {
+    pub timestamp: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl Cursor {
+    pub fn new(timestamp: DateTime<Utc>, id: Uuid) -> Self {
+        Self { timestamp, id }
+    }
+
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!("{}|{}", self.timestamp.to_rfc3339(), self.id))
+    }
+
+    /// Reads a cursor made by `encode`, `None` if it was tampered with.
+    pub fn decode(value: &str) -> Option<Self> {
+        let bytes = URL_SAFE_NO_PAD.decode(value).ok()?;
+        let value = String::from_utf8(bytes).ok()?;
+        let (timestamp, id) = value.split_once('|')?;
+
+        Some(Self {
+            timestamp: DateTime::parse_from_rfc3339(timestamp)
+                .ok()?
+                .with_timezone(&Utc),
+            id: Uuid::parse_str(id).ok()?,
+        })
+    }
+}
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffOp {
+    Equal,
+    Insert,
+    Delete,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiffChange {
+    pub op: DiffOp,
+    pub value: String,
+}
+
+/// Line based diff of two texts, consecutive lines with the same op are merged.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffChange> {
+    let diff = TextDiff::from_lines(old, new);
+    let mut changes: Vec<DiffChange> = vec![];
+
+    for change in diff.iter_all_changes() {
+        let op = match change.tag() {
+            ChangeTag::Equal => DiffOp::Equal,
+            ChangeTag::Insert => DiffOp::Insert,
+            ChangeTag::Delete => DiffOp::Delete,
+        };
+
+        match changes.last_mut() {
+            Some(last) if last.op == op => last.value.push_str(change.value()),
+            _ => changes.push(DiffChange {
+                op,
+                value: change.value().to_string(),
+            }),
+        }
+    }
+
+    changes
+}
@@ -0,0 +1,67 @@
+use pulldown_cmark::{html, Event, Parser, TagEnd};
+
+pub const EXCERPT_LENGTH: usize = 200;
+pub const WORDS_PER_MINUTE: usize = 200;
+
+/// A CommonMark source rendered once on write and cached next to it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedMarkdown {
+    pub html: String,
+    pub excerpt: String,
+    pub reading_time: i32,
+}
+
+/// Renders `source` to HTML with anything unsafe, such as scripts, inline
+/// event handlers or `javascript:` links, stripped out.
+pub fn render(source: &str) -> RenderedMarkdown {
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, Parser::new(source));
+
+    let text = plain_text(source);
+    let words = text.split_whitespace().count();
+
+    RenderedMarkdown {
+        html: ammonia::clean(&unsafe_html),
+        excerpt: excerpt(&text),
+        reading_time: words.div_ceil(WORDS_PER_MINUTE).max(1) as i32,
+    }
+}
+
+fn plain_text(source: &str) -> String {
+    let mut text = String::new();
+
+    for event in Parser::new(source) {
+        match event {
+            Event::Text(value) | Event::Code(value) => text.push_str(&value),
+            Event::SoftBreak
+            | Event::HardBreak
+            | Event::End(TagEnd::Paragraph)
+            | Event::End(TagEnd::Heading(_))
+            | Event::End(TagEnd::Item)
+            | Event::End(TagEnd::CodeBlock) => text.push(' '),
+            _ => {}
+        }
+    }
+
+    text
+}
+
+/// First words of `text`, cut on a word boundary.
+fn excerpt(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+
+    if text.chars().count() <= EXCERPT_LENGTH {
+        return text;
+    }
+
+    let cut: String = text.chars().take(EXCERPT_LENGTH).collect();
+    let cut = match cut.rfind(' ') {
+        Some(index) => &cut[..index],
+        None => cut.as_str(),
+    };
+
+    format!(
+        "{}…",
+        cut.trim_end_matches(|c: char| c.is_ascii_punctuation())
+    )
+}
@@ -1 +1,8 @@
+pub mod cursor;
+pub mod diff;
+pub mod markdown;
+pub mod password;
+pub mod slug;
 pub mod test;
+pub mod thumbnail;
+pub mod token;
@@ -0,0 +1,28 @@
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+
+pub fn hash(password: impl Into<String>) -> Result<String, argon2::password_hash::Error> {
+    let password = password.into();
+    let salt = SaltString::generate(&mut OsRng);
+
+    let hashed = Argon2::default()
+        .hash_password(password.as_bytes(), &salt)?
+        .to_string();
+
+    Ok(hashed)
+}
+
+pub fn compare(
+    password: &str,
+    hashed_password: &str,
+) -> Result<bool, argon2::password_hash::Error> {
+    let parsed_hash = PasswordHash::new(hashed_password)?;
+
+    let is_match = Argon2::default()
+        .verify_password(password.as_bytes(), &parsed_hash)
+        .is_ok();
+
+    Ok(is_match)
+}
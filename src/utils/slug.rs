@@ -0,0 +1,19 @@
+use deunicode::deunicode;
+
+/// Transliterates `value` to ASCII, lowercases it and collapses every run of
+/// characters that are not letters or digits into a single `-`, e.g.
+/// `" Rust & Café "` becomes `"rust-cafe"`.
+pub fn slugify(value: &str) -> String {
+    let value = deunicode(value.trim());
+    let mut slug = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    slug.trim_end_matches('-').to_string()
+}
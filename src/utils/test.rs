@@ -1,18 +1,29 @@
+use std::sync::Arc;
+
+use actix_web::web;
+use chrono::NaiveDate;
 use sqlx::{Pool, Postgres};
+use uuid::Uuid;
 
 use crate::{
+    config::Config,
     db::person::PersonExt,
     db::post::PostExt,
     db::DBClient,
-    dtos::person::CreateUserDto,
+    dtos::person::{CreateAdminDto, CreateUserDto},
     dtos::post::CreatePostDto,
-    models::{Post, User},
+    events::{local::LocalEventHub, EventHub},
+    extractors::auth::AuthenticatedPerson,
+    models::{Admin, Gender, Post, User},
+    storage::local::LocalStorage,
+    AppState,
 };
 
 #[allow(dead_code)]
 pub struct TestPost {
     title: &'static str,
     description: &'static str,
+    tags: &'static [&'static str],
 }
 
 #[allow(dead_code)]
@@ -22,7 +33,7 @@ pub struct TestUser {
     pub lastname: &'static str,
     pub password: &'static str,
     pub email: &'static str,
-    pub gender: &'static str,
+    pub gender: Gender,
     pub birthdate: &'static str,
     pub is_profile_private: bool,
 }
@@ -31,26 +42,47 @@ pub struct TestUser {
 pub async fn init_test_posts(pool: &Pool<Postgres>) -> (Post, Post, Post, Post, Post) {
     let db_client = DBClient::new(pool.clone());
 
+    let author = db_client
+        .save_user(CreateUserDto {
+            firstname: "Paula".to_string(),
+            lastname: "Writer".to_string(),
+            username: "post_author".to_string(),
+            password: "writer123".to_string(),
+            birthdate: NaiveDate::parse_from_str("1990-01-01", "%Y-%m-%d").unwrap(),
+            gender: None,
+            gender_description: None,
+            pronouns: None,
+            is_profile_private: Some(false),
+            email: "paula.writer@example.com".to_string(),
+        })
+        .await
+        .unwrap();
+
     let posts: Vec<TestPost> = vec![
             TestPost {
                 title: "Mastering Data Structures and Algorithms: A Comprehensive Guide for Programmers",
                 description: "Dive into the world of data structures and algorithms with this comprehensive guide aimed at programmers looking to enhance their problem-solving skills. Explore essential data structures like arrays, linked lists, trees, and advanced algorithms such as sorting, searching, and graph algorithms. Mastering these concepts is crucial for writing efficient and optimized code in various programming languages.",
+                tags: &["Algorithms", "Programming"],
             },
             TestPost {
                 title: "Effective Debugging Techniques: Strategies to Improve Code Quality and Productivity" ,
                 description: "Enhance your programming skills with a deep dive into effective debugging techniques to streamline your development process. This post covers essential strategies for identifying and fixing bugs efficiently, optimizing code performance, and improving overall code quality. Learn valuable tips and tools that will boost your productivity and make you a more proficient programmer.",
+                tags: &["Debugging", "Programming"],
             },
             TestPost {
                 title : "Demystifying Machine Learning: A Beginner's Journey into AI Programming",
                 description: "Embark on a beginner-friendly journey into the exciting world of machine learning and artificial intelligence programming. Unravel the mysteries behind key machine learning concepts, such as supervised and unsupervised learning, neural networks, and deep learning. Gain insights into practical applications of machine learning algorithms and how they are revolutionizing various industries.",
+                tags: &["Machine Learning", "AI"],
             },
             TestPost {
                 title: "Building Scalable Web Applications with Microservices Architecture",
                 description : "Learn how to design and implement scalable web applications using microservices architecture. This post explores the advantages of microservices, guiding developers through the process of breaking down monolithic applications into smaller, independent services. Discover best practices for building resilient, highly scalable systems that can adapt to growing user demands and evolving business requirements.",
+                tags: &["Web", "Architecture"],
             },
             TestPost {
                 title: "Web Accessibility: Creating Inclusive User Experiences for All",
                 description : "Delve into the crucial topic of web accessibility and learn how to design and develop websites that are inclusive and usable by all individuals. This post explores the importance of accessibility in web design, addressing the needs of users with disabilities and diverse abilities. Discover techniques and best practices for creating accessible web content, including proper HTML semantics, keyboard navigation, color contrast, and assistive technologies compatibility. Empower yourself to make the web a more inclusive and welcoming space for everyone, ensuring that all users can access and interact with digital content seamlessly.",
+                tags: &["Web", "Accessibility"],
             },
         ];
 
@@ -58,10 +90,16 @@ pub async fn init_test_posts(pool: &Pool<Postgres>) -> (Post, Post, Post, Post,
 
     for post_data in posts {
         let post = db_client
-            .save_post(CreatePostDto {
-                title: post_data.title.to_string(),
-                description: post_data.description.to_string(),
-            })
+            .save_post(
+                author.id,
+                CreatePostDto {
+                    title: post_data.title.to_string(),
+                    description: post_data.description.to_string(),
+                    tags: Some(post_data.tags.iter().map(|t| t.to_string()).collect()),
+                    publish: Some(true),
+                    scheduled_at: None,
+                },
+            )
             .await
             .unwrap();
 
@@ -88,7 +126,7 @@ pub async fn init_test_users(pool: &Pool<Postgres>) -> (User, User, User, User)
             username: "alice_smith",
             password: "password123",
             email: "alice@example.com",
-            gender: "female",
+            gender: Gender::Female,
             birthdate: "1999-01-01",
             is_profile_private: false,
         },
@@ -98,7 +136,7 @@ pub async fn init_test_users(pool: &Pool<Postgres>) -> (User, User, User, User)
             username: "john_doe123",
             password: "doe1234",
             email: "john.doe@example.com",
-            gender: "female",
+            gender: Gender::Female,
             birthdate: "1999-01-01",
             is_profile_private: false,
         },
@@ -108,7 +146,7 @@ pub async fn init_test_users(pool: &Pool<Postgres>) -> (User, User, User, User)
             username: "sarah_j",
             password: "sarahpw",
             email: "sarah.j@example.com",
-            gender: "female",
+            gender: Gender::Female,
             birthdate: "1999-01-01",
             is_profile_private: false,
         },
@@ -118,7 +156,7 @@ pub async fn init_test_users(pool: &Pool<Postgres>) -> (User, User, User, User)
             username: "mbrown123",
             password: "brownie456",
             email: "michael.b@example.com",
-            gender: "female",
+            gender: Gender::Female,
             birthdate: "1999-01-01",
             is_profile_private: false,
         },
@@ -133,8 +171,10 @@ pub async fn init_test_users(pool: &Pool<Postgres>) -> (User, User, User, User)
                 lastname: user_data.lastname.to_string(),
                 username: user_data.username.to_string(),
                 password: user_data.password.to_string(),
-                birthdate: user_data.birthdate.to_string(),
-                gender: user_data.gender.to_string(),
+                birthdate: NaiveDate::parse_from_str(user_data.birthdate, "%Y-%m-%d").unwrap(),
+                gender: Some(user_data.gender),
+                gender_description: None,
+                pronouns: None,
                 is_profile_private: Some(user_data.is_profile_private),
                 email: user_data.email.to_string(),
             })
@@ -151,3 +191,83 @@ pub async fn init_test_users(pool: &Pool<Postgres>) -> (User, User, User, User)
         created_users[3].clone(),
     )
 }
+
+#[allow(dead_code)]
+pub async fn init_test_admin(pool: &Pool<Postgres>) -> Admin {
+    let db_client = DBClient::new(pool.clone());
+
+    db_client
+        .save_admin(CreateAdminDto {
+            firstname: "Ada".to_string(),
+            lastname: "Admin".to_string(),
+            username: "ada_admin".to_string(),
+            email: "ada.admin@example.com".to_string(),
+            birthdate: NaiveDate::parse_from_str("1985-01-01", "%Y-%m-%d").unwrap(),
+            gender: None,
+            gender_description: None,
+            pronouns: None,
+            password: "admin123".to_string(),
+        })
+        .await
+        .unwrap()
+}
+
+/// State for calling handlers directly, with files kept in a temporary directory.
+#[allow(dead_code)]
+pub fn test_app_state(pool: &Pool<Postgres>) -> web::Data<AppState> {
+    test_app_state_with_events(pool, Arc::new(LocalEventHub::new(16, 16)))
+}
+
+/// Like `test_app_state`, with events going through `events`.
+#[allow(dead_code)]
+pub fn test_app_state_with_events(
+    pool: &Pool<Postgres>,
+    events: Arc<dyn EventHub>,
+) -> web::Data<AppState> {
+    let storage_root = std::env::temp_dir().join(format!("storage-{}", Uuid::new_v4()));
+
+    web::Data::new(AppState {
+        env: Config {
+            db_url: String::new(),
+            host_ip: String::from("127.0.0.1"),
+            url: String::from("http://localhost"),
+            port: 5000,
+            min_age: 13,
+            jwt_secret: String::from("test-secret"),
+            jwt_max_age: 60,
+            scheduler_interval: 30,
+            require_if_match: false,
+            trash_retention_days: 30,
+            purge_interval: 3600,
+            storage_backend: String::from("local"),
+            storage_local_root: storage_root.to_string_lossy().to_string(),
+            storage_public_url: None,
+            s3_endpoint: None,
+            s3_bucket: None,
+            s3_region: String::from("us-east-1"),
+            s3_access_key_id: None,
+            s3_secret_access_key: None,
+            max_upload_size: 5242880,
+            thumbnail_variants: vec![],
+            thumbnail_interval: 10,
+        },
+        db_client: DBClient::new(pool.clone()),
+        storage: Arc::new(LocalStorage::with_root(
+            &storage_root,
+            "http://localhost:5000/media/",
+        )),
+        events,
+    })
+}
+
+/// Signs in as the person with the given id, as the auth extractor would.
+#[allow(dead_code)]
+pub async fn authenticated(pool: &Pool<Postgres>, person_id: Uuid) -> AuthenticatedPerson {
+    let person = DBClient::new(pool.clone())
+        .get_person(person_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    AuthenticatedPerson(person)
+}
@@ -0,0 +1,75 @@
+use std::io::Cursor;
+
+use image::{
+    codecs::{jpeg::JpegEncoder, png::PngEncoder},
+    DynamicImage, ImageDecoder, ImageFormat, ImageReader, ImageResult, Limits,
+};
+
+/// Largest width or height of an image we are willing to decode, which keeps
+/// decompression bombs from exhausting memory.
+pub const MAX_DIMENSION: u32 = 10_000;
+
+pub const JPEG_QUALITY: u8 = 85;
+
+/// A resized image, encoded without any of the source's metadata.
+#[derive(Debug)]
+pub struct Thumbnail {
+    pub bytes: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub content_type: &'static str,
+    pub extension: &'static str,
+}
+
+/// Decodes an uploaded image, turning it upright according to its EXIF
+/// orientation since the metadata itself is not carried over.
+pub fn decode(bytes: &[u8]) -> ImageResult<(DynamicImage, ImageFormat)> {
+    let mut reader = ImageReader::new(Cursor::new(bytes)).with_guessed_format()?;
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DIMENSION);
+    limits.max_image_height = Some(MAX_DIMENSION);
+    reader.limits(limits);
+
+    // Unknown formats are refused by `into_decoder` below.
+    let format = reader.format().unwrap_or(ImageFormat::Png);
+
+    let mut decoder = reader.into_decoder()?;
+    let orientation = decoder.orientation()?;
+
+    let mut image = DynamicImage::from_decoder(decoder)?;
+    image.apply_orientation(orientation);
+
+    Ok((image, format))
+}
+
+/// Scales `image` down to fit in a `size` square, keeping its aspect ratio.
+///
+/// Images that already fit are re-encoded at their own size. JPEGs stay JPEGs,
+/// everything else becomes a PNG so that transparency survives.
+pub fn render(image: &DynamicImage, format: ImageFormat, size: u32) -> ImageResult<Thumbnail> {
+    let resized = if image.width() > size || image.height() > size {
+        image.thumbnail(size, size)
+    } else {
+        image.clone()
+    };
+
+    let mut bytes = Vec::new();
+
+    let (content_type, extension) = if format == ImageFormat::Jpeg {
+        let encoder = JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY);
+        DynamicImage::ImageRgb8(resized.to_rgb8()).write_with_encoder(encoder)?;
+        ("image/jpeg", "jpg")
+    } else {
+        resized.write_with_encoder(PngEncoder::new(&mut bytes))?;
+        ("image/png", "png")
+    };
+
+    Ok(Thumbnail {
+        bytes,
+        width: resized.width(),
+        height: resized.height(),
+        content_type,
+        extension,
+    })
+}
@@ -0,0 +1,40 @@
+use chrono::{Duration, Utc};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TokenClaims {
+    pub sub: String,
+    pub iat: usize,
+    pub exp: usize,
+}
+
+pub fn create_token(
+    person_id: &str,
+    secret: &[u8],
+    expires_in_minutes: i64,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    let now = Utc::now();
+
+    let claims = TokenClaims {
+        sub: person_id.to_string(),
+        iat: now.timestamp() as usize,
+        exp: (now + Duration::minutes(expires_in_minutes)).timestamp() as usize,
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret),
+    )
+}
+
+pub fn decode_token(token: &str, secret: &[u8]) -> Result<String, jsonwebtoken::errors::Error> {
+    let decoded = decode::<TokenClaims>(
+        token,
+        &DecodingKey::from_secret(secret),
+        &Validation::default(),
+    )?;
+
+    Ok(decoded.claims.sub)
+}